@@ -0,0 +1,91 @@
+//! Adapters implementing or transforming decorators.
+
+use std::fmt;
+
+use super::{DecorateTest, TestFn};
+
+/// [Test decorator](DecorateTest) defined by a function. This allows defining simple one-off
+/// decorators without defining a type and implementing [`DecorateTest`] for it.
+///
+/// The function receives the tested function as a trait object and should call it zero or more
+/// times, returning the test output.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::FnDecorator};
+/// use std::time::Instant;
+///
+/// static MEASURE_TIME: FnDecorator<()> = FnDecorator::new(|test_fn| {
+///     let started_at = Instant::now();
+///     test_fn();
+///     println!("Test took {:?}", started_at.elapsed());
+/// });
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(&MEASURE_TIME)]
+/// fn measured_test() {
+///     // test logic
+/// }
+/// ```
+pub struct FnDecorator<R> {
+    decorate_fn: fn(&dyn Fn() -> R) -> R,
+}
+
+impl<R> fmt::Debug for FnDecorator<R> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("FnDecorator")
+            .field("decorate_fn", &(self.decorate_fn as *const ()))
+            .finish()
+    }
+}
+
+impl<R> Clone for FnDecorator<R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R> Copy for FnDecorator<R> {}
+
+impl<R> FnDecorator<R> {
+    /// Creates a decorator from the provided function.
+    pub const fn new(decorate_fn: fn(&dyn Fn() -> R) -> R) -> Self {
+        Self { decorate_fn }
+    }
+}
+
+impl<R: 'static> DecorateTest<R> for FnDecorator<R> {
+    fn decorate_and_test<F: TestFn<R>>(&self, test_fn: F) -> R {
+        (self.decorate_fn)(&test_fn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn function_decorator() {
+        static DECORATOR: FnDecorator<Result<(), u32>> = FnDecorator::new(|test_fn| {
+            test_fn()?;
+            test_fn()
+        });
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        let test_fn: fn() -> Result<(), u32> = || {
+            let count = COUNTER.fetch_add(1, Ordering::SeqCst);
+            if count == 0 {
+                Ok(())
+            } else {
+                Err(count)
+            }
+        };
+        assert_eq!(DECORATOR.decorate_and_test(test_fn), Err(1));
+        assert_eq!(COUNTER.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,489 @@
+//! Async-native test decorators.
+
+use std::{
+    any::Any,
+    fmt,
+    future::Future,
+    panic::{self, AssertUnwindSafe, Location},
+    pin::Pin,
+    sync::{atomic::AtomicUsize, Arc, Mutex, PoisonError},
+    task::{Context, Poll, Waker},
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::{
+    decorators_disabled, test_location_suffix, LocationGuard, Retry, RetryBudget, RetryErrors,
+    RetryPanics, Timeout, TIMEOUT_EXPIRED,
+};
+
+/// Boxed future returned by [async tested functions](AsyncTestFn) and [async decorators](DecorateTestAsync).
+pub type TestFuture<R> = Pin<Box<dyn Future<Output = R> + Send>>;
+
+/// Async tested function that can be wrapped by an [async decorator](DecorateTestAsync).
+/// Each call of the function creates a new test future.
+pub trait AsyncTestFn<R>: Fn() -> TestFuture<R> + Send + Sync + Copy + 'static {}
+
+impl<R, F> AsyncTestFn<R> for F where F: Fn() -> TestFuture<R> + Send + Sync + Copy + 'static {}
+
+/// Async counterpart of [`DecorateTest`](super::DecorateTest), which wraps the test future itself
+/// rather than a blocking function running it.
+///
+/// Async decorators are used by the [`decorate`](crate::decorate) macro if it is placed
+/// *before* the async test attribute (e.g., `#[tokio::test]`), so that it wraps an `async fn`.
+/// In this case, the decorators operate inside the async runtime: e.g., a [`Timeout`] drops
+/// the test future once it expires instead of spawning a thread for the test, and [`Retry`]
+/// re-creates the test future for each attempt without blocking the runtime between attempts.
+///
+/// `DecorateTestAsync` is implemented for [`Retry`], [`RetryErrors`], [`RetryPanics`],
+/// [`RetryBudget`], [`Timeout`] and [`Logged`](super::Logged) (with the `tracing` crate feature).
+/// Similarly to `DecorateTest`, it is implemented for tuples with 2..=8 async decorators
+/// and for optional decorators.
+///
+/// Async decorators rely on a runtime-agnostic timer driven by a helper thread.
+/// The [default timeout] is not applied to tests decorated in this way since it requires running
+/// the test on a separate thread.
+///
+/// [default timeout]: Timeout::set_default()
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{Retry, Timeout}};
+///
+/// #[decorate(Timeout::secs(5), Retry::times(2))]
+/// #[async_std::test]
+/// // ^ `decorate` is placed before the test attribute, so that it wraps the test future
+/// async fn async_test() {
+///     // test logic
+/// }
+/// ```
+///
+/// Implementing an async decorator:
+///
+/// ```
+/// use test_casing::decorators::{AsyncTestFn, DecorateTestAsync, TestFuture};
+/// use std::time::Instant;
+///
+/// #[derive(Debug)]
+/// struct MeasureTime;
+///
+/// impl<R: 'static> DecorateTestAsync<R> for MeasureTime {
+///     fn decorate_and_test_async<F: AsyncTestFn<R>>(&'static self, test_fn: F) -> TestFuture<R> {
+///         Box::pin(async move {
+///             let started_at = Instant::now();
+///             let output = test_fn().await;
+///             println!("Test took {:?}", started_at.elapsed());
+///             output
+///         })
+///     }
+/// }
+/// ```
+pub trait DecorateTestAsync<R>: Send + Sync + 'static {
+    /// Decorates the provided async test function and returns the future running the test.
+    fn decorate_and_test_async<F: AsyncTestFn<R>>(&'static self, test_fn: F) -> TestFuture<R>;
+}
+
+impl<R, T: DecorateTestAsync<R> + ?Sized> DecorateTestAsync<R> for &'static T {
+    fn decorate_and_test_async<F: AsyncTestFn<R>>(&'static self, test_fn: F) -> TestFuture<R> {
+        (**self).decorate_and_test_async(test_fn)
+    }
+}
+
+impl<R, D: DecorateTestAsync<R>> DecorateTestAsync<R> for Option<D> {
+    fn decorate_and_test_async<F: AsyncTestFn<R>>(&'static self, test_fn: F) -> TestFuture<R> {
+        match self {
+            Some(decorator) => decorator.decorate_and_test_async(test_fn),
+            None => test_fn(),
+        }
+    }
+}
+
+/// Object-safe version of [`DecorateTestAsync`].
+#[doc(hidden)] // used in the `decorate` proc macro; logically private
+pub trait DecorateTestAsyncFn<R>: Send + Sync + 'static {
+    /// Decorates the provided async test function and returns the future running the test.
+    /// The caller location is considered to be the location of the test and is mentioned
+    /// in panic messages produced by decorators.
+    #[track_caller]
+    fn decorate_and_test_async_fn(&'static self, test_fn: fn() -> TestFuture<R>) -> TestFuture<R>;
+}
+
+impl<R: 'static, T: DecorateTestAsync<R>> DecorateTestAsyncFn<R> for T {
+    fn decorate_and_test_async_fn(&'static self, test_fn: fn() -> TestFuture<R>) -> TestFuture<R> {
+        if decorators_disabled() {
+            test_fn()
+        } else {
+            Box::pin(WithLocation {
+                location: Location::caller(),
+                inner: self.decorate_and_test_async(test_fn),
+            })
+        }
+    }
+}
+
+/// Sets the test location for the duration of each poll of the wrapped future.
+struct WithLocation<R> {
+    location: &'static Location<'static>,
+    inner: TestFuture<R>,
+}
+
+impl<R> Future for WithLocation<R> {
+    type Output = R;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<R> {
+        let this = self.get_mut();
+        let _guard = LocationGuard::at(this.location);
+        this.inner.as_mut().poll(cx)
+    }
+}
+
+/// Async counterpart of [`panic::catch_unwind()`].
+pub(crate) struct CatchUnwind<F>(pub F);
+
+impl<F: Future + Unpin> Future for CatchUnwind<F> {
+    type Output = Result<F::Output, Box<dyn Any + Send>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = &mut self.get_mut().0;
+        match panic::catch_unwind(AssertUnwindSafe(|| Pin::new(&mut *inner).poll(cx))) {
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(panic_object) => Poll::Ready(Err(panic_object)),
+        }
+    }
+}
+
+/// Runtime-agnostic timer future. The timer is driven by a helper thread spawned on the first poll,
+/// so that it works with any async runtime.
+struct Sleep {
+    deadline: Instant,
+    waker: Option<Arc<Mutex<Option<Waker>>>>,
+}
+
+impl fmt::Debug for Sleep {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("Sleep")
+            .field("deadline", &self.deadline)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Sleep {
+    fn new(duration: Duration) -> Self {
+        Self {
+            deadline: Instant::now() + duration,
+            waker: None,
+        }
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        if Instant::now() >= this.deadline {
+            return Poll::Ready(());
+        }
+
+        let deadline = this.deadline;
+        let waker = this.waker.get_or_insert_with(|| {
+            let waker = Arc::new(Mutex::new(None::<Waker>));
+            let thread_waker = Arc::clone(&waker);
+            thread::spawn(move || {
+                thread::sleep(deadline.saturating_duration_since(Instant::now()));
+                let waker = thread_waker.lock().unwrap_or_else(PoisonError::into_inner);
+                if let Some(waker) = &*waker {
+                    waker.wake_by_ref();
+                }
+            });
+            waker
+        });
+        *waker.lock().unwrap_or_else(PoisonError::into_inner) = Some(cx.waker().clone());
+
+        // The timer thread may have fired before the waker was updated; it only fires
+        // after the deadline, so checking the deadline again is sufficient.
+        if Instant::now() >= deadline {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Races a test future against a [`Sleep`]. Resolves to `None` if the timer fires first.
+struct WithTimeout<R> {
+    inner: TestFuture<R>,
+    sleep: Sleep,
+}
+
+impl<R> Future for WithTimeout<R> {
+    type Output = Option<R>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<R>> {
+        let this = self.get_mut();
+        if let Poll::Ready(output) = this.inner.as_mut().poll(cx) {
+            return Poll::Ready(Some(output));
+        }
+        Pin::new(&mut this.sleep).poll(cx).map(|()| None)
+    }
+}
+
+impl<R: 'static> DecorateTestAsync<R> for Timeout {
+    fn decorate_and_test_async<F: AsyncTestFn<R>>(&'static self, test_fn: F) -> TestFuture<R> {
+        let timeout = self.0;
+        Box::pin(async move {
+            let output = WithTimeout {
+                inner: test_fn(),
+                sleep: Sleep::new(timeout),
+            }
+            .await;
+            // The test future is dropped at this point, so that it doesn't run in the background.
+            output.unwrap_or_else(|| {
+                TIMEOUT_EXPIRED.with(|cell| cell.set(true));
+                panic!(
+                    "Timeout {timeout:?} expired for the test{}",
+                    test_location_suffix()
+                );
+            })
+        })
+    }
+}
+
+impl Retry {
+    async fn run_async(
+        &'static self,
+        test_fn: impl AsyncTestFn<()>,
+        should_retry_panic: fn(&str) -> bool,
+        budget: Option<&'static AtomicUsize>,
+    ) {
+        for attempt in 0..=self.times {
+            self.emit(format_args!("Test attempt #{attempt}"));
+            match CatchUnwind(test_fn()).await {
+                Ok(()) => break,
+                Err(panic_object) => {
+                    self.handle_panic(attempt, panic_object, should_retry_panic, budget);
+                }
+            }
+            let delay = self.delay(attempt);
+            if delay > Duration::ZERO {
+                Sleep::new(delay).await;
+            }
+        }
+    }
+
+    async fn run_with_retries_async<E: fmt::Debug>(
+        &'static self,
+        test_fn: impl AsyncTestFn<Result<(), E>>,
+        should_retry: fn(&E) -> bool,
+        should_retry_panic: fn(&str) -> bool,
+        budget: Option<&'static AtomicUsize>,
+    ) -> Result<(), E> {
+        for attempt in 0..=self.times {
+            self.emit(format_args!("Test attempt #{attempt}"));
+            match CatchUnwind(test_fn()).await {
+                Ok(Ok(())) => return Ok(()),
+                Ok(Err(err)) => self.handle_error(attempt, err, should_retry, budget)?,
+                Err(panic_object) => {
+                    self.handle_panic(attempt, panic_object, should_retry_panic, budget);
+                }
+            }
+            let delay = self.delay(attempt);
+            if delay > Duration::ZERO {
+                Sleep::new(delay).await;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl DecorateTestAsync<()> for Retry {
+    fn decorate_and_test_async<F: AsyncTestFn<()>>(&'static self, test_fn: F) -> TestFuture<()> {
+        Box::pin(self.run_async(test_fn, |_| true, None))
+    }
+}
+
+impl<E: fmt::Debug + 'static> DecorateTestAsync<Result<(), E>> for Retry {
+    fn decorate_and_test_async<F>(&'static self, test_fn: F) -> TestFuture<Result<(), E>>
+    where
+        F: AsyncTestFn<Result<(), E>>,
+    {
+        Box::pin(self.run_with_retries_async(test_fn, |_| true, |_| true, None))
+    }
+}
+
+impl<E: fmt::Debug + 'static> DecorateTestAsync<Result<(), E>> for RetryErrors<E> {
+    fn decorate_and_test_async<F>(&'static self, test_fn: F) -> TestFuture<Result<(), E>>
+    where
+        F: AsyncTestFn<Result<(), E>>,
+    {
+        Box::pin(
+            self.inner
+                .run_with_retries_async(test_fn, self.matcher, |_| true, None),
+        )
+    }
+}
+
+impl DecorateTestAsync<()> for RetryPanics {
+    fn decorate_and_test_async<F: AsyncTestFn<()>>(&'static self, test_fn: F) -> TestFuture<()> {
+        Box::pin(self.inner.run_async(test_fn, self.matcher, None))
+    }
+}
+
+impl<E: fmt::Debug + 'static> DecorateTestAsync<Result<(), E>> for RetryPanics {
+    fn decorate_and_test_async<F>(&'static self, test_fn: F) -> TestFuture<Result<(), E>>
+    where
+        F: AsyncTestFn<Result<(), E>>,
+    {
+        Box::pin(
+            self.inner
+                .run_with_retries_async(test_fn, |_| true, self.matcher, None),
+        )
+    }
+}
+
+impl DecorateTestAsync<()> for RetryBudget {
+    fn decorate_and_test_async<F: AsyncTestFn<()>>(&'static self, test_fn: F) -> TestFuture<()> {
+        Box::pin(
+            self.inner
+                .run_async(test_fn, |_| true, Some(&self.remaining)),
+        )
+    }
+}
+
+impl<E: fmt::Debug + 'static> DecorateTestAsync<Result<(), E>> for RetryBudget {
+    fn decorate_and_test_async<F>(&'static self, test_fn: F) -> TestFuture<Result<(), E>>
+    where
+        F: AsyncTestFn<Result<(), E>>,
+    {
+        Box::pin(self.inner.run_with_retries_async(
+            test_fn,
+            |_| true,
+            |_| true,
+            Some(&self.remaining),
+        ))
+    }
+}
+
+macro_rules! impl_decorate_test_async_for_tuple {
+    ($($field:ident : $ty:ident),* => $last_field:ident : $last_ty:ident) => {
+        impl<R, $($ty,)* $last_ty> DecorateTestAsync<R> for ($($ty,)* $last_ty,)
+        where
+            $($ty: DecorateTestAsync<R>,)*
+            $last_ty: DecorateTestAsync<R>,
+        {
+            fn decorate_and_test_async<Fn: AsyncTestFn<R>>(
+                &'static self,
+                test_fn: Fn,
+            ) -> TestFuture<R> {
+                let ($($field,)* $last_field,) = self;
+                $(
+                let test_fn = move || $field.decorate_and_test_async(test_fn);
+                )*
+                $last_field.decorate_and_test_async(test_fn)
+            }
+        }
+    };
+}
+
+impl_decorate_test_async_for_tuple!(=> a: A);
+impl_decorate_test_async_for_tuple!(a: A => b: B);
+impl_decorate_test_async_for_tuple!(a: A, b: B => c: C);
+impl_decorate_test_async_for_tuple!(a: A, b: B, c: C => d: D);
+impl_decorate_test_async_for_tuple!(a: A, b: B, c: C, d: D => e: E);
+impl_decorate_test_async_for_tuple!(a: A, b: B, c: C, d: D, e: E => f: F);
+impl_decorate_test_async_for_tuple!(a: A, b: B, c: C, d: D, e: E, f: F => g: G);
+impl_decorate_test_async_for_tuple!(a: A, b: B, c: C, d: D, e: E, f: F, g: G => h: H);
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        sync::atomic::{AtomicBool, AtomicU32, Ordering},
+    };
+
+    use async_std::task;
+
+    use super::*;
+    use crate::decorators::extract_panic_str;
+
+    #[test]
+    fn sleeping() {
+        let started_at = Instant::now();
+        task::block_on(Sleep::new(Duration::from_millis(50)));
+        assert!(started_at.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[test]
+    fn async_timeout_drops_test_future() {
+        static TIMEOUT: Timeout = Timeout::millis(50);
+        static IS_DROPPED: AtomicBool = AtomicBool::new(false);
+
+        struct DropGuard;
+
+        impl Drop for DropGuard {
+            fn drop(&mut self) {
+                IS_DROPPED.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let test_fn: fn() -> TestFuture<()> = || {
+            Box::pin(async {
+                let _guard = DropGuard;
+                task::sleep(Duration::from_secs(5)).await;
+            })
+        };
+        let started_at = Instant::now();
+        let future = CatchUnwind(TIMEOUT.decorate_and_test_async(test_fn));
+        let panic_object = task::block_on(future).unwrap_err();
+        assert!(started_at.elapsed() < Duration::from_secs(1));
+        let message = extract_panic_str(&*panic_object).unwrap();
+        assert!(message.starts_with("Timeout 50ms expired"), "{message}");
+        assert!(IS_DROPPED.load(Ordering::SeqCst));
+        TIMEOUT_EXPIRED.with(|cell| cell.set(false));
+    }
+
+    #[test]
+    fn async_retries_with_timeout() {
+        static DECORATORS: (Timeout, Retry) = (
+            Timeout::millis(50),
+            Retry::times(2).with_delay(Duration::from_millis(10)),
+        );
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        let test_fn: fn() -> TestFuture<Result<(), &'static str>> = || {
+            Box::pin(async {
+                match COUNTER.fetch_add(1, Ordering::SeqCst) {
+                    0 => {
+                        task::sleep(Duration::from_secs(5)).await;
+                        Ok(())
+                    }
+                    1 => Err("oops"),
+                    _ => Ok(()),
+                }
+            })
+        };
+        task::block_on(DECORATORS.decorate_and_test_async(test_fn)).unwrap();
+        assert_eq!(COUNTER.load(Ordering::SeqCst), 3);
+        assert!(!TIMEOUT_EXPIRED.with(Cell::get));
+    }
+
+    #[test]
+    fn async_retries_exhaustion() {
+        static RETRY: Retry = Retry::times(1);
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        let test_fn: fn() -> TestFuture<()> = || {
+            Box::pin(async {
+                COUNTER.fetch_add(1, Ordering::SeqCst);
+                panic!("oops");
+            })
+        };
+        let panic_object =
+            task::block_on(CatchUnwind(RETRY.decorate_and_test_async(test_fn))).unwrap_err();
+        assert_eq!(extract_panic_str(&*panic_object), Some("oops"));
+        assert_eq!(COUNTER.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,177 @@
+//! Timeouts for async tests based on the timers of async runtimes.
+
+use std::time::Duration;
+
+use super::{test_location_suffix, AsyncTestFn, DecorateTestAsync, TestFuture, TIMEOUT_EXPIRED};
+
+/// Timer used by an [`AsyncTimeout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Timer {
+    #[cfg(feature = "tokio")]
+    Tokio,
+    #[cfg(feature = "async-std")]
+    AsyncStd,
+}
+
+/// [Async test decorator](DecorateTestAsync) that fails a wrapped test if it doesn't complete
+/// in the specified [`Duration`]. Unlike [`Timeout`](super::Timeout), the timeout is tracked
+/// by the timer of the async runtime (`tokio` or `async-std`), and the test future is dropped
+/// once the timeout expires. Thus, the test doesn't keep running in the background,
+/// and resources held by it are released.
+///
+/// Since the timer of the runtime is used, the timeout respects runtime-specific time control;
+/// e.g., it is auto-advanced if the `tokio` clock is paused. The test must run
+/// on the corresponding runtime; e.g., using a `tokio` timeout outside of a `tokio` runtime
+/// leads to a panic.
+///
+/// The decorator is only available with the `tokio` and/or `async-std` crate features. It only
+/// applies to async tests decorated natively, i.e., if the [`decorate`](crate::decorate) macro
+/// is placed *before* the async test attribute.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{AsyncTimeout, Retry}};
+///
+/// #[decorate(AsyncTimeout::tokio_secs(5), Retry::times(2))]
+/// #[tokio::test]
+/// async fn test_with_timeout() {
+///     // test logic
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(any(feature = "tokio", feature = "async-std"))))]
+#[derive(Debug, Clone, Copy)]
+pub struct AsyncTimeout {
+    timeout: Duration,
+    timer: Timer,
+}
+
+impl AsyncTimeout {
+    /// Creates a timeout tracked by the `tokio` timer.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub const fn tokio(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            timer: Timer::Tokio,
+        }
+    }
+
+    /// Creates a timeout with the specified number of seconds tracked by the `tokio` timer.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub const fn tokio_secs(secs: u64) -> Self {
+        Self::tokio(Duration::from_secs(secs))
+    }
+
+    /// Creates a timeout tracked by the `async-std` timer.
+    #[cfg(feature = "async-std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async-std")))]
+    pub const fn async_std(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            timer: Timer::AsyncStd,
+        }
+    }
+
+    /// Creates a timeout with the specified number of seconds tracked by the `async-std` timer.
+    #[cfg(feature = "async-std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async-std")))]
+    pub const fn async_std_secs(secs: u64) -> Self {
+        Self::async_std(Duration::from_secs(secs))
+    }
+
+    /// Returns the timeout duration.
+    pub const fn duration(&self) -> Duration {
+        self.timeout
+    }
+}
+
+impl<R: 'static> DecorateTestAsync<R> for AsyncTimeout {
+    fn decorate_and_test_async<F: AsyncTestFn<R>>(&'static self, test_fn: F) -> TestFuture<R> {
+        let timeout = self.timeout;
+        Box::pin(async move {
+            let output = match self.timer {
+                #[cfg(feature = "tokio")]
+                Timer::Tokio => tokio::time::timeout(timeout, test_fn()).await.ok(),
+                #[cfg(feature = "async-std")]
+                Timer::AsyncStd => async_std::future::timeout(timeout, test_fn()).await.ok(),
+            };
+            // The test future is dropped at this point.
+            output.unwrap_or_else(|| {
+                TIMEOUT_EXPIRED.with(|cell| cell.set(true));
+                panic!(
+                    "Timeout {timeout:?} expired for the test{}",
+                    test_location_suffix()
+                );
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "async-std")]
+    use std::{
+        cell::Cell,
+        sync::atomic::{AtomicU32, Ordering},
+    };
+
+    use super::*;
+    #[cfg(feature = "async-std")]
+    use crate::decorators::Retry;
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn tokio_timeout_with_paused_clock() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        use crate::decorators::{async_test::CatchUnwind, extract_panic_str};
+
+        static IS_DROPPED: AtomicBool = AtomicBool::new(false);
+
+        struct DropGuard;
+
+        impl Drop for DropGuard {
+            fn drop(&mut self) {
+                IS_DROPPED.store(true, Ordering::SeqCst);
+            }
+        }
+
+        static TIMEOUT: AsyncTimeout = AsyncTimeout::tokio_secs(60);
+
+        let test_fn: fn() -> TestFuture<()> = || {
+            Box::pin(async {
+                let _guard = DropGuard;
+                tokio::time::sleep(Duration::from_secs(3_600)).await;
+            })
+        };
+        let future = CatchUnwind(TIMEOUT.decorate_and_test_async(test_fn));
+        let panic_object = future.await.unwrap_err();
+        let message = extract_panic_str(&*panic_object).unwrap();
+        assert!(message.starts_with("Timeout 60s expired"), "{message}");
+        assert!(IS_DROPPED.load(Ordering::SeqCst));
+        TIMEOUT_EXPIRED.with(|cell| cell.set(false));
+    }
+
+    #[cfg(feature = "async-std")]
+    #[async_std::test]
+    async fn async_std_timeout_with_retries() {
+        static DECORATORS: (AsyncTimeout, Retry) = (
+            AsyncTimeout::async_std(Duration::from_millis(50)),
+            Retry::times(1),
+        );
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        let test_fn: fn() -> TestFuture<()> = || {
+            Box::pin(async {
+                if COUNTER.fetch_add(1, Ordering::SeqCst) == 0 {
+                    async_std::task::sleep(Duration::from_secs(5)).await;
+                }
+            })
+        };
+        DECORATORS.decorate_and_test_async(test_fn).await;
+        assert_eq!(COUNTER.load(Ordering::SeqCst), 2);
+        assert!(!TIMEOUT_EXPIRED.with(Cell::get));
+    }
+}
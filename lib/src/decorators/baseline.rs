@@ -0,0 +1,313 @@
+//! Coarse performance regression checks based on recorded test durations.
+
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::Path,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use super::{process::current_test_name, test_location_suffix, DecorateTest, OutputSink, TestFn};
+
+/// Name of the env variable that switches [`PerfBaseline`] to recording durations.
+const RECORD_ENV_VAR: &str = "TEST_CASING_RECORD_BASELINE";
+
+/// Synchronizes access to baseline files among tests in the same process.
+static BASELINE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Test durations in microseconds keyed by the test name.
+type Baseline = BTreeMap<String, u64>;
+
+fn read_baseline(path: &Path) -> io::Result<Baseline> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Baseline::new()),
+        Err(err) => return Err(err),
+    };
+
+    let mut baseline = Baseline::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let entry = line
+            .rsplit_once(' ')
+            .and_then(|(name, micros)| Some((name.trim(), micros.parse::<u64>().ok()?)));
+        let Some((name, micros)) = entry else {
+            let message = format!("invalid baseline entry: `{line}`");
+            return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+        };
+        baseline.insert(name.to_owned(), micros);
+    }
+    Ok(baseline)
+}
+
+fn write_baseline(path: &Path, baseline: &Baseline) -> io::Result<()> {
+    let mut contents =
+        String::from("# Test durations in microseconds recorded by `PerfBaseline`\n");
+    for (name, micros) in baseline {
+        contents.push_str(name);
+        contents.push(' ');
+        contents.push_str(&micros.to_string());
+        contents.push('\n');
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)
+}
+
+/// Action taken by [`PerfBaseline`] if a test exceeds its baseline duration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RegressionAction {
+    Fail,
+    Warn,
+}
+
+/// Outcome of comparing a test duration with its baseline.
+#[derive(Debug, PartialEq)]
+enum Comparison {
+    Recorded,
+    NoBaseline,
+    WithinBaseline,
+    Regression { baseline: Duration },
+}
+
+/// [Test decorator](DecorateTest) that compares the duration of a wrapped test with
+/// a baseline duration recorded in a file, turning the test suite into a coarse performance
+/// regression harness.
+///
+/// If the `TEST_CASING_RECORD_BASELINE` env variable is set, durations of successfully completed
+/// tests are recorded into the baseline file (the file is created if necessary, and entries
+/// for other tests are retained). Otherwise, the decorator fails the test (or only emits
+/// a warning if [`Self::warn_only()`] is specified) if it completes successfully, but exceeds
+/// its baseline duration by more than the configured tolerance. Tests without a baseline entry
+/// are not checked. Warnings and other messages are emitted
+/// to the [global sink](OutputSink::global()).
+///
+/// The baseline file is a text file with `test_name duration_in_micros` entries on separate lines.
+/// A relative file path is resolved relative to the current directory; for tests run by Cargo,
+/// this is the package root. Access to the file is synchronized among tests in the same process,
+/// but not across processes; thus, baselines should be recorded with a harness running all tests
+/// in the same process (e.g., the standard test harness).
+///
+/// # Limitations
+///
+/// The test must run on a thread named after the test, which is what the standard test harness
+/// does. The decorator will panic if it cannot determine the test name.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::PerfBaseline};
+///
+/// static BASELINE: PerfBaseline =
+///     PerfBaseline::new("tests/perf-baseline.txt").with_tolerance(50);
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(&BASELINE)]
+/// fn performance_sensitive_test() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct PerfBaseline {
+    path: &'static str,
+    tolerance_percent: u32,
+    action: RegressionAction,
+}
+
+impl PerfBaseline {
+    /// Creates a decorator with the specified path to the baseline file. The default tolerance
+    /// is 20%; exceeding it fails the test.
+    pub const fn new(path: &'static str) -> Self {
+        Self {
+            path,
+            tolerance_percent: 20,
+            action: RegressionAction::Fail,
+        }
+    }
+
+    /// Specifies the tolerance as a percentage of the baseline duration.
+    #[must_use]
+    pub const fn with_tolerance(self, tolerance_percent: u32) -> Self {
+        Self {
+            tolerance_percent,
+            ..self
+        }
+    }
+
+    /// Only prints a warning if the test exceeds its baseline, instead of failing the test.
+    #[must_use]
+    pub const fn warn_only(self) -> Self {
+        Self {
+            action: RegressionAction::Warn,
+            ..self
+        }
+    }
+
+    fn is_recording() -> bool {
+        env::var_os(RECORD_ENV_VAR).is_some()
+    }
+
+    fn compare(
+        self,
+        path: &Path,
+        test_name: &str,
+        elapsed: Duration,
+        record: bool,
+    ) -> io::Result<Comparison> {
+        let elapsed_micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        let _guard = BASELINE_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut baseline = read_baseline(path)?;
+
+        if record {
+            baseline.insert(test_name.to_owned(), elapsed_micros);
+            write_baseline(path, &baseline)?;
+            return Ok(Comparison::Recorded);
+        }
+
+        let Some(&baseline_micros) = baseline.get(test_name) else {
+            return Ok(Comparison::NoBaseline);
+        };
+        let threshold =
+            u128::from(baseline_micros) * (100 + u128::from(self.tolerance_percent)) / 100;
+        Ok(if u128::from(elapsed_micros) > threshold {
+            Comparison::Regression {
+                baseline: Duration::from_micros(baseline_micros),
+            }
+        } else {
+            Comparison::WithinBaseline
+        })
+    }
+
+    fn decorate_inner<R, F: TestFn<R>>(self, test_fn: F, is_ok: fn(&R) -> bool) -> R {
+        let test_name = current_test_name().unwrap_or_else(|| {
+            panic!(
+                "Cannot determine the test name; `PerfBaseline` must be used in tests \
+                 executed by the standard test harness"
+            );
+        });
+
+        let started_at = Instant::now();
+        let output = test_fn();
+        let elapsed = started_at.elapsed();
+        if !is_ok(&output) {
+            return output;
+        }
+
+        let path = Path::new(self.path);
+        let comparison = self
+            .compare(path, &test_name, elapsed, Self::is_recording())
+            .unwrap_or_else(|err| {
+                panic!("Failed accessing baseline file `{}`: {err}", self.path);
+            });
+        let sink = OutputSink::global();
+        let location = test_location_suffix();
+        match comparison {
+            Comparison::Recorded => {
+                sink.emit(format_args!(
+                    "Recorded baseline duration for the test{location}: {elapsed:?}"
+                ));
+            }
+            Comparison::NoBaseline => {
+                sink.emit(format_args!(
+                    "No baseline duration recorded for the test{location}; took {elapsed:?}"
+                ));
+            }
+            Comparison::WithinBaseline => { /* do nothing */ }
+            Comparison::Regression { baseline } => {
+                let message = format!(
+                    "Test took {elapsed:?}, exceeding its baseline {baseline:?} by more than {}%",
+                    self.tolerance_percent
+                );
+                match self.action {
+                    RegressionAction::Fail => panic!("{message}"),
+                    RegressionAction::Warn => sink.emit(format_args!("Warning: {message}")),
+                }
+            }
+        }
+        output
+    }
+}
+
+impl DecorateTest<()> for PerfBaseline {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        self.decorate_inner(test_fn, |()| true);
+    }
+}
+
+impl<E> DecorateTest<Result<(), E>> for PerfBaseline {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.decorate_inner(test_fn, Result::is_ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, process};
+
+    use super::*;
+
+    fn temp_baseline_path(name: &str) -> PathBuf {
+        let file_name = format!("test-casing-{name}-{}.txt", process::id());
+        env::temp_dir().join(file_name)
+    }
+
+    #[test]
+    fn recording_and_comparing_durations() {
+        let path = temp_baseline_path("baseline");
+        let decorator = PerfBaseline::new("unused").with_tolerance(50);
+
+        let comparison = decorator
+            .compare(&path, "test", Duration::from_millis(10), false)
+            .unwrap();
+        assert_eq!(comparison, Comparison::NoBaseline);
+
+        let comparison = decorator
+            .compare(&path, "test", Duration::from_millis(10), true)
+            .unwrap();
+        assert_eq!(comparison, Comparison::Recorded);
+        decorator
+            .compare(&path, "other::test", Duration::from_millis(1), true)
+            .unwrap();
+
+        let baseline = read_baseline(&path).unwrap();
+        assert_eq!(baseline.len(), 2);
+        assert_eq!(baseline["test"], 10_000);
+        assert_eq!(baseline["other::test"], 1_000);
+
+        let comparison = decorator
+            .compare(&path, "test", Duration::from_millis(14), false)
+            .unwrap();
+        assert_eq!(comparison, Comparison::WithinBaseline);
+        let comparison = decorator
+            .compare(&path, "test", Duration::from_millis(16), false)
+            .unwrap();
+        assert_eq!(
+            comparison,
+            Comparison::Regression {
+                baseline: Duration::from_millis(10),
+            }
+        );
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn invalid_baseline_entry() {
+        let path = temp_baseline_path("invalid-baseline");
+        fs::write(&path, "test not-a-number\n").unwrap();
+        let err = read_baseline(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("not-a-number"), "{err}");
+    }
+}
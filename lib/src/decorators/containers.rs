@@ -0,0 +1,379 @@
+//! Docker containers managed by the `testcontainers` crate.
+
+use std::{
+    cell::RefCell,
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
+
+use testcontainers::{
+    core::{ContainerPort, WaitFor},
+    runners::SyncRunner,
+    Container, ContainerRequest, GenericImage, ImageExt,
+};
+
+use super::{DecorateTest, TestFn};
+
+thread_local! {
+    static RUNNING_CONTAINERS: RefCell<Vec<ContainerInfo>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Information about a running container exposed to the test.
+#[derive(Debug, Clone)]
+struct ContainerInfo {
+    host: String,
+    /// Mapping from internal ports to host ports.
+    ports: Vec<(u16, u16)>,
+}
+
+/// Exposes container info to the test on the current thread until dropped.
+#[derive(Debug)]
+struct ContainerScope(());
+
+impl ContainerScope {
+    fn new(info: ContainerInfo) -> Self {
+        RUNNING_CONTAINERS.with(|cell| cell.borrow_mut().push(info));
+        Self(())
+    }
+}
+
+impl Drop for ContainerScope {
+    fn drop(&mut self) {
+        RUNNING_CONTAINERS.with(|cell| cell.borrow_mut().pop());
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Readiness {
+    Started,
+    StdoutMessage(&'static str),
+    StderrMessage(&'static str),
+    Healthcheck,
+}
+
+/// [Test decorator](DecorateTest) that runs a Docker container for the duration of the test
+/// using the [`testcontainers`] crate.
+///
+/// The container is started before the test and is removed after the test completes,
+/// including if the test panics. Host ports mapped to the container ports can be obtained
+/// in the test body via [`Self::host_port()`]. To share a container among multiple tests,
+/// use [`SharedContainer`].
+///
+/// The container info is tied to the thread executing the decorator. Hence, if the test
+/// is decorated with a [`Timeout`](super::Timeout), `DockerContainer` should be placed *before*
+/// the timeout in the decorator list. Likewise, if the decorator is placed before
+/// [`Retry`](super::Retry), a fresh container will be started for each attempt.
+///
+/// [`testcontainers`]: https://docs.rs/testcontainers/
+///
+/// # Examples
+///
+/// ```no_run
+/// use test_casing::{decorate, decorators::{DockerContainer, Timeout}};
+///
+/// const REDIS: DockerContainer = DockerContainer::new("redis", "7.2")
+///     .with_ports(&[6379])
+///     .wait_for_stdout("Ready to accept connections");
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(REDIS, Timeout::secs(60))]
+/// fn test_with_redis() {
+///     let port = DockerContainer::host_port(6379);
+///     let url = format!("redis://{}:{port}", DockerContainer::host());
+///     // test logic
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "testcontainers")))]
+#[derive(Debug, Clone, Copy)]
+pub struct DockerContainer {
+    image: &'static str,
+    tag: &'static str,
+    ports: &'static [u16],
+    env: &'static [(&'static str, &'static str)],
+    readiness: Readiness,
+    startup_timeout: Option<Duration>,
+}
+
+impl DockerContainer {
+    /// Creates a container with the specified image name and tag.
+    pub const fn new(image: &'static str, tag: &'static str) -> Self {
+        Self {
+            image,
+            tag,
+            ports: &[],
+            env: &[],
+            readiness: Readiness::Started,
+            startup_timeout: None,
+        }
+    }
+
+    /// Exposes the specified TCP ports of the container.
+    #[must_use]
+    pub const fn with_ports(mut self, ports: &'static [u16]) -> Self {
+        self.ports = ports;
+        self
+    }
+
+    /// Sets env variables for the container.
+    #[must_use]
+    pub const fn with_env(mut self, env: &'static [(&'static str, &'static str)]) -> Self {
+        self.env = env;
+        self
+    }
+
+    /// Considers the container ready once the specified message is output to its stdout.
+    #[must_use]
+    pub const fn wait_for_stdout(mut self, message: &'static str) -> Self {
+        self.readiness = Readiness::StdoutMessage(message);
+        self
+    }
+
+    /// Considers the container ready once the specified message is output to its stderr.
+    #[must_use]
+    pub const fn wait_for_stderr(mut self, message: &'static str) -> Self {
+        self.readiness = Readiness::StderrMessage(message);
+        self
+    }
+
+    /// Considers the container ready once its health check (defined in the image) succeeds.
+    #[must_use]
+    pub const fn wait_for_healthcheck(mut self) -> Self {
+        self.readiness = Readiness::Healthcheck;
+        self
+    }
+
+    /// Sets the timeout for the container to start and become ready.
+    #[must_use]
+    pub const fn with_startup_timeout(mut self, timeout: Duration) -> Self {
+        self.startup_timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the host on which the container ports are exposed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a test decorated with `DockerContainer` or [`SharedContainer`].
+    pub fn host() -> String {
+        RUNNING_CONTAINERS.with(|cell| {
+            let containers = cell.borrow();
+            let container = containers.last().unwrap_or_else(|| {
+                panic!("no running containers; decorate the test with `DockerContainer`");
+            });
+            container.host.clone()
+        })
+    }
+
+    /// Returns the host port mapped to the specified internal TCP port of a container
+    /// running for the current test. If several containers expose the port,
+    /// the innermost one (i.e., the latest in the decorator list) is used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a test decorated with `DockerContainer` or [`SharedContainer`],
+    /// or if none of the containers expose the port.
+    pub fn host_port(internal_port: u16) -> u16 {
+        let host_port = RUNNING_CONTAINERS.with(|cell| {
+            cell.borrow().iter().rev().find_map(|container| {
+                let (_, host_port) = container
+                    .ports
+                    .iter()
+                    .find(|(port, _)| *port == internal_port)?;
+                Some(*host_port)
+            })
+        });
+        host_port.unwrap_or_else(|| {
+            panic!("port {internal_port} is not exposed by any container running for the test");
+        })
+    }
+
+    fn start(&self) -> (Container<GenericImage>, ContainerInfo) {
+        let mut image = GenericImage::new(self.image, self.tag);
+        for &port in self.ports {
+            image = image.with_exposed_port(ContainerPort::Tcp(port));
+        }
+        image = match self.readiness {
+            Readiness::Started => image,
+            Readiness::StdoutMessage(message) => {
+                image.with_wait_for(WaitFor::message_on_stdout(message))
+            }
+            Readiness::StderrMessage(message) => {
+                image.with_wait_for(WaitFor::message_on_stderr(message))
+            }
+            Readiness::Healthcheck => image.with_wait_for(WaitFor::healthcheck()),
+        };
+
+        let mut request = ContainerRequest::from(image);
+        for &(name, value) in self.env {
+            request = request.with_env_var(name, value);
+        }
+        if let Some(timeout) = self.startup_timeout {
+            request = request.with_startup_timeout(timeout);
+        }
+
+        let image_name = format!("{}:{}", self.image, self.tag);
+        let container = request.start().unwrap_or_else(|err| {
+            panic!("Failed starting container `{image_name}`: {err}");
+        });
+        let host = container.get_host().unwrap_or_else(|err| {
+            panic!("Failed getting host for container `{image_name}`: {err}");
+        });
+        let ports = self.ports.iter().map(|&port| {
+            let host_port = container.get_host_port_ipv4(port).unwrap_or_else(|err| {
+                panic!(
+                    "Failed getting host port for port {port} of container `{image_name}`: {err}"
+                );
+            });
+            (port, host_port)
+        });
+        let info = ContainerInfo {
+            host: host.to_string(),
+            ports: ports.collect(),
+        };
+        (container, info)
+    }
+}
+
+impl<R> DecorateTest<R> for DockerContainer {
+    fn decorate_and_test<F: TestFn<R>>(&self, test_fn: F) -> R {
+        let (_container, info) = self.start();
+        // `_scope` is dropped before `_container`, so the container info is never stale.
+        let _scope = ContainerScope::new(info);
+        test_fn()
+    }
+}
+
+#[derive(Debug)]
+struct SharedState {
+    users: usize,
+    running: Option<(Container<GenericImage>, ContainerInfo)>,
+}
+
+/// [Test decorator](DecorateTest) that shares a [`DockerContainer`] among a group of tests.
+///
+/// The container is started when the first test in the group starts, and is removed
+/// once no tests in the group are running (including if tests panic). Thus, the container
+/// is shared among tests in the group running concurrently; if tests run one after another
+/// (e.g., if they are in a [`Sequence`](super::Sequence)), it may be restarted between tests.
+/// The tests in the group must not rely on the container state left by other tests.
+///
+/// # Examples
+///
+/// ```no_run
+/// use test_casing::{decorate, decorators::{DockerContainer, SharedContainer}};
+///
+/// static POSTGRES: SharedContainer = SharedContainer::new(
+///     DockerContainer::new("postgres", "16")
+///         .with_ports(&[5432])
+///         .with_env(&[("POSTGRES_PASSWORD", "postgres")])
+///         .wait_for_stderr("database system is ready to accept connections"),
+/// );
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(&POSTGRES)]
+/// fn first_db_test() {
+///     let port = DockerContainer::host_port(5432);
+///     // test logic
+/// }
+///
+/// #[test]
+/// # fn eat_test_attribute2() {}
+/// #[decorate(&POSTGRES)]
+/// fn second_db_test() {
+///     // test logic
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "testcontainers")))]
+#[derive(Debug)]
+pub struct SharedContainer {
+    container: DockerContainer,
+    state: Mutex<SharedState>,
+}
+
+impl SharedContainer {
+    /// Creates a shared container.
+    pub const fn new(container: DockerContainer) -> Self {
+        Self {
+            container,
+            state: Mutex::new(SharedState {
+                users: 0,
+                running: None,
+            }),
+        }
+    }
+
+    fn acquire(&self) -> ContainerInfo {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let (_, info) = state.running.get_or_insert_with(|| self.container.start());
+        let info = info.clone();
+        state.users += 1;
+        info
+    }
+
+    fn release(&self) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.users -= 1;
+        if state.users == 0 {
+            // Removes the container.
+            state.running = None;
+        }
+    }
+}
+
+/// Releases a [`SharedContainer`] on drop.
+#[derive(Debug)]
+struct SharedContainerGuard(&'static SharedContainer);
+
+impl Drop for SharedContainerGuard {
+    fn drop(&mut self) {
+        self.0.release();
+    }
+}
+
+impl<R> DecorateTest<R> for SharedContainer {
+    fn decorate_and_test<F: TestFn<R>>(&'static self, test_fn: F) -> R {
+        let info = self.acquire();
+        let _guard = SharedContainerGuard(self);
+        let _scope = ContainerScope::new(info);
+        test_fn()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn looking_up_host_ports() {
+        let _outer = ContainerScope::new(ContainerInfo {
+            host: "localhost".to_owned(),
+            ports: vec![(5432, 10_000), (6379, 10_001)],
+        });
+        let _inner = ContainerScope::new(ContainerInfo {
+            host: "127.0.0.1".to_owned(),
+            ports: vec![(6379, 10_002)],
+        });
+
+        assert_eq!(DockerContainer::host(), "127.0.0.1");
+        assert_eq!(DockerContainer::host_port(5432), 10_000);
+        assert_eq!(DockerContainer::host_port(6379), 10_002);
+    }
+
+    #[test]
+    #[ignore = "requires Docker"]
+    fn running_container() {
+        static CONTAINER: DockerContainer = DockerContainer::new("redis", "7.2")
+            .with_ports(&[6379])
+            .wait_for_stdout("Ready to accept connections");
+        static SHARED_CONTAINER: SharedContainer = SharedContainer::new(CONTAINER);
+
+        let test_fn: fn() -> u16 = || DockerContainer::host_port(6379);
+        let port = CONTAINER.decorate_and_test(test_fn);
+        assert_ne!(port, 6379);
+        let port = SHARED_CONTAINER.decorate_and_test(test_fn);
+        assert_ne!(port, 6379);
+        assert!(SHARED_CONTAINER.state.lock().unwrap().running.is_none());
+    }
+}
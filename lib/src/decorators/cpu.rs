@@ -0,0 +1,216 @@
+//! Decorators checking CPU capabilities.
+
+use super::{test_location_suffix, DecorateTest, OutputSink, TestFn};
+
+/// Detects whether the specified target feature is supported by the CPU at runtime.
+/// Returns `None` if the feature is unknown for the current target architecture.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn detect_feature(feature: &str) -> Option<bool> {
+    macro_rules! detect {
+        ($($name:tt),+) => {
+            match feature {
+                $($name => Some(std::arch::is_x86_feature_detected!($name)),)+
+                _ => None,
+            }
+        };
+    }
+
+    detect!(
+        "aes",
+        "pclmulqdq",
+        "rdrand",
+        "rdseed",
+        "tsc",
+        "mmx",
+        "sse",
+        "sse2",
+        "sse3",
+        "ssse3",
+        "sse4.1",
+        "sse4.2",
+        "sse4a",
+        "sha",
+        "avx",
+        "avx2",
+        "avx512f",
+        "avx512cd",
+        "avx512er",
+        "avx512pf",
+        "avx512bw",
+        "avx512dq",
+        "avx512vl",
+        "avx512ifma",
+        "avx512vbmi",
+        "avx512vpopcntdq",
+        "f16c",
+        "fma",
+        "bmi1",
+        "bmi2",
+        "abm",
+        "lzcnt",
+        "tbm",
+        "popcnt",
+        "fxsr",
+        "xsave",
+        "xsaveopt",
+        "xsaves",
+        "xsavec",
+        "cmpxchg16b",
+        "adx",
+        "rtm"
+    )
+}
+
+/// Detects whether the specified target feature is supported by the CPU at runtime.
+/// Returns `None` if the feature is unknown for the current target architecture.
+#[cfg(target_arch = "aarch64")]
+fn detect_feature(feature: &str) -> Option<bool> {
+    macro_rules! detect {
+        ($($name:tt),+) => {
+            match feature {
+                $($name => Some(std::arch::is_aarch64_feature_detected!($name)),)+
+                _ => None,
+            }
+        };
+    }
+
+    detect!(
+        "asimd",
+        "neon",
+        "pmull",
+        "fp",
+        "fp16",
+        "sve",
+        "crc",
+        "lse",
+        "lse2",
+        "rdm",
+        "rcpc",
+        "rcpc2",
+        "dotprod",
+        "tme",
+        "fhm",
+        "dit",
+        "flagm",
+        "ssbs",
+        "sb",
+        "paca",
+        "pacg",
+        "dpb",
+        "dpb2",
+        "sve2",
+        "sve2-aes",
+        "sve2-sm4",
+        "sve2-sha3",
+        "sve2-bitperm",
+        "frintts",
+        "i8mm",
+        "f32mm",
+        "f64mm",
+        "bf16",
+        "rand",
+        "bti",
+        "mte",
+        "jsconv",
+        "fcma",
+        "aes",
+        "sha2",
+        "sha3",
+        "sm4"
+    )
+}
+
+/// Fallback for architectures without runtime feature detection.
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+fn detect_feature(_feature: &str) -> Option<bool> {
+    None
+}
+
+/// [Test decorator](DecorateTest) that skips the wrapped test if the CPU doesn't support
+/// all of the specified target features (e.g., `avx2`), as determined by runtime detection
+/// (i.e., `is_x86_feature_detected!` on x86 / `x86_64` and `is_aarch64_feature_detected!`
+/// on `AArch64`).
+///
+/// Features unknown for the target architecture are considered unsupported. Since the standard
+/// test harness doesn't support skipping tests at runtime, a skipped test is reported as passed;
+/// the decorator emits a message with the list of unsupported features
+/// to the [global sink](OutputSink::global()).
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::RequiresCpuFeatures};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(RequiresCpuFeatures(&["avx2", "fma"]))]
+/// fn simd_implementation_works() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RequiresCpuFeatures(pub &'static [&'static str]);
+
+impl RequiresCpuFeatures {
+    fn unsupported_features(self) -> Vec<&'static str> {
+        self.0
+            .iter()
+            .copied()
+            .filter(|&feature| detect_feature(feature) != Some(true))
+            .collect()
+    }
+
+    fn decorate_inner<R, F: TestFn<R>>(self, test_fn: F, skipped_value: R) -> R {
+        let unsupported_features = self.unsupported_features();
+        if unsupported_features.is_empty() {
+            test_fn()
+        } else {
+            let unsupported_features = unsupported_features.join(", ");
+            OutputSink::global().emit(format_args!(
+                "Skipping test{}: CPU features are not supported: {unsupported_features}",
+                test_location_suffix()
+            ));
+            skipped_value
+        }
+    }
+}
+
+impl DecorateTest<()> for RequiresCpuFeatures {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        self.decorate_inner(test_fn, ());
+    }
+}
+
+impl<E> DecorateTest<Result<(), E>> for RequiresCpuFeatures {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.decorate_inner(test_fn, Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn detecting_features() {
+        // SSE2 is supported by all x86_64 CPUs.
+        assert_eq!(detect_feature("sse2"), Some(true));
+        assert_eq!(detect_feature("neon"), None);
+        assert_eq!(
+            RequiresCpuFeatures(&["sse2", "neon"]).unsupported_features(),
+            ["neon"]
+        );
+    }
+
+    #[test]
+    fn skipping_test() {
+        static DECORATOR: RequiresCpuFeatures = RequiresCpuFeatures(&["unknown"]);
+
+        let test_fn: fn() -> Result<(), &'static str> = || Err("should be skipped");
+        assert_eq!(DECORATOR.decorate_and_test(test_fn), Ok(()));
+    }
+}
@@ -0,0 +1,162 @@
+//! Measuring CPU time consumed by tests.
+
+use std::time::{Duration, Instant};
+
+use super::{test_location_suffix, DecorateTest, OutputSink, TestFn};
+
+/// Returns the CPU time (user + system) consumed by the current thread.
+#[cfg(target_os = "linux")]
+fn thread_cpu_time() -> Option<Duration> {
+    /// Clock ticks per second used in `/proc` stats (`USER_HZ`), which is 100 on all
+    /// mainstream architectures.
+    const TICKS_PER_SEC: u64 = 100;
+
+    let stat = std::fs::read_to_string("/proc/thread-self/stat").ok()?;
+    // The thread name in parentheses may contain spaces, so fields are counted after it.
+    let (_, fields) = stat.rsplit_once(')')?;
+    let mut fields = fields.split_whitespace().skip(11);
+    let user_ticks: u64 = fields.next()?.parse().ok()?;
+    let system_ticks: u64 = fields.next()?.parse().ok()?;
+    let ticks = user_ticks + system_ticks;
+    Some(Duration::from_millis(ticks * 1_000 / TICKS_PER_SEC))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn thread_cpu_time() -> Option<Duration> {
+    None
+}
+
+/// [Test decorator](DecorateTest) that measures wall-clock time and CPU time consumed
+/// by the wrapped test and reports them to the [global sink](OutputSink::global())
+/// after the test completes. Optionally, the decorator
+/// can fail a successfully completed test if it consumes more CPU time than the specified limit.
+///
+/// Unlike wall-clock time limits (e.g., a [`Timeout`](super::Timeout)), CPU time limits
+/// do not punish tests waiting for a shared resource, such as a test [`Sequence`](super::Sequence).
+///
+/// # Limitations
+///
+/// - CPU time is measured for the thread executing the decorator. Thus, `CpuTime` should precede
+///   decorators spawning threads, such as a `Timeout`, in the decorator list. CPU time
+///   of threads spawned by the test itself is not accounted for.
+/// - CPU time is only measured on Linux, with the resolution of 10 ms. On other platforms,
+///   only wall-clock time is printed, and the CPU time limit is not enforced.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{CpuTime, Sequence, Timeout}};
+/// use std::time::Duration;
+///
+/// static SEQUENCE: Sequence = Sequence::new();
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(
+///     CpuTime::new().with_limit(Duration::from_secs(2)),
+///     Timeout::secs(30),
+///     &SEQUENCE,
+/// )]
+/// fn compute_heavy_test() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CpuTime {
+    limit: Option<Duration>,
+}
+
+impl CpuTime {
+    /// Creates a decorator that only measures and prints time.
+    pub const fn new() -> Self {
+        Self { limit: None }
+    }
+
+    /// Fails the test if it consumes more CPU time than the specified limit.
+    #[must_use]
+    pub const fn with_limit(mut self, limit: Duration) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    fn decorate_inner<R, F: TestFn<R>>(self, test_fn: F, is_ok: fn(&R) -> bool) -> R {
+        let cpu_time_at_start = thread_cpu_time();
+        let started_at = Instant::now();
+        let output = test_fn();
+        let wall_time = started_at.elapsed();
+        let cpu_time = thread_cpu_time()
+            .zip(cpu_time_at_start)
+            .map(|(end, start)| end.saturating_sub(start));
+
+        let sink = OutputSink::global();
+        let location = test_location_suffix();
+        let Some(cpu_time) = cpu_time else {
+            sink.emit(format_args!(
+                "Test{location} took {wall_time:?} (CPU time is not available)"
+            ));
+            return output;
+        };
+        sink.emit(format_args!(
+            "Test{location} took {wall_time:?}, including {cpu_time:?} CPU time"
+        ));
+        if let Some(limit) = self.limit {
+            assert!(
+                !is_ok(&output) || cpu_time <= limit,
+                "Test consumed {cpu_time:?} CPU time, exceeding the limit {limit:?}"
+            );
+        }
+        output
+    }
+}
+
+impl DecorateTest<()> for CpuTime {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        self.decorate_inner(test_fn, |()| true);
+    }
+}
+
+impl<E> DecorateTest<Result<(), E>> for CpuTime {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.decorate_inner(test_fn, Result::is_ok)
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use std::{panic, thread};
+
+    use super::*;
+    use crate::decorators::extract_panic_str;
+
+    fn busy_loop(duration: Duration) {
+        let started_at = Instant::now();
+        while started_at.elapsed() < duration {
+            std::hint::spin_loop();
+        }
+    }
+
+    #[test]
+    fn measuring_cpu_time() {
+        let start = thread_cpu_time().unwrap();
+        busy_loop(Duration::from_millis(100));
+        let cpu_time = thread_cpu_time().unwrap().saturating_sub(start);
+        assert!(cpu_time > Duration::ZERO, "{cpu_time:?}");
+    }
+
+    #[test]
+    fn cpu_time_limit() {
+        static DECORATOR: CpuTime = CpuTime::new().with_limit(Duration::from_millis(50));
+
+        // Waiting doesn't consume CPU time.
+        DECORATOR.decorate_and_test(|| thread::sleep(Duration::from_millis(200)));
+
+        let test_fn: fn() = || busy_loop(Duration::from_millis(500));
+        let panic_object =
+            panic::catch_unwind(|| DECORATOR.decorate_and_test(test_fn)).unwrap_err();
+        let panic_str = extract_panic_str(&*panic_object).unwrap();
+        assert!(panic_str.contains("exceeding the limit"), "{panic_str}");
+    }
+}
@@ -0,0 +1,187 @@
+//! Default timeout for tests generated by the `decorate` and `test_casing` macros.
+
+use std::{
+    cell::RefCell,
+    env,
+    marker::PhantomData,
+    sync::{OnceLock, PoisonError, RwLock},
+    time::Duration,
+};
+
+use super::{decorators_disabled, TestFn, Timeout, TimeoutHandle};
+
+/// Name of the env variable specifying the default timeout.
+pub(super) const DEFAULT_TIMEOUT_ENV_VAR: &str = "TEST_CASING_DEFAULT_TIMEOUT";
+
+/// Source of the default timeout.
+#[derive(Debug, Clone, Copy)]
+enum DefaultTimeout {
+    /// The timeout is taken from the env variable.
+    FromEnv,
+    /// The timeout is set programmatically; takes precedence over the env variable.
+    Set(Option<Duration>),
+}
+
+static DEFAULT_TIMEOUT: RwLock<DefaultTimeout> = RwLock::new(DefaultTimeout::FromEnv);
+
+thread_local! {
+    /// Default timeout for the test running on the current thread. Set for the entire duration
+    /// of the test, even after the timeout is disarmed, so that the default timeout is not nested.
+    static DEFAULT_TIMEOUT_HANDLE: RefCell<Option<TimeoutHandle>> = const { RefCell::new(None) };
+}
+
+/// Parses a duration with an optional `ms`, `s`, `m` or `h` suffix; durations without a suffix
+/// are in seconds.
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (number, unit_millis) = if let Some(number) = value.strip_suffix("ms") {
+        (number, 1)
+    } else if let Some(number) = value.strip_suffix('s') {
+        (number, 1_000)
+    } else if let Some(number) = value.strip_suffix('m') {
+        (number, 60_000)
+    } else if let Some(number) = value.strip_suffix('h') {
+        (number, 3_600_000)
+    } else {
+        (value, 1_000)
+    };
+    let number: u64 = number.trim_end().parse().ok()?;
+    Some(Duration::from_millis(number.checked_mul(unit_millis)?))
+}
+
+/// Gets the default timeout from the [`DEFAULT_TIMEOUT_ENV_VAR`] env variable.
+fn default_timeout_from_env() -> Option<Duration> {
+    static TIMEOUT: OnceLock<Option<Duration>> = OnceLock::new();
+
+    *TIMEOUT.get_or_init(|| {
+        let value = env::var(DEFAULT_TIMEOUT_ENV_VAR).ok()?;
+        if value.trim().is_empty() {
+            return None;
+        }
+        let timeout = parse_duration(&value).unwrap_or_else(|| {
+            panic!(
+                "Invalid value of the `{DEFAULT_TIMEOUT_ENV_VAR}` env variable: `{value}`; \
+                 expected a duration like `60s`, `500ms` or `5m`"
+            );
+        });
+        Some(timeout)
+    })
+}
+
+pub(super) fn set_default_timeout(timeout: Option<Duration>) {
+    *DEFAULT_TIMEOUT
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = DefaultTimeout::Set(timeout);
+}
+
+pub(super) fn default_timeout() -> Option<Duration> {
+    match *DEFAULT_TIMEOUT
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+    {
+        DefaultTimeout::FromEnv => default_timeout_from_env(),
+        DefaultTimeout::Set(timeout) => timeout,
+    }
+}
+
+pub(super) fn set_handle(handle: TimeoutHandle) {
+    DEFAULT_TIMEOUT_HANDLE.with(|cell| *cell.borrow_mut() = Some(handle));
+}
+
+/// Disarms the default timeout for the test running on the current thread. Called by decorators
+/// specifying a timeout themselves.
+pub(super) fn disarm() {
+    DEFAULT_TIMEOUT_HANDLE.with(|cell| {
+        if let Some(handle) = &*cell.borrow() {
+            handle.disarm();
+        }
+    });
+}
+
+/// Test function wrapper applying the default timeout (if any) to the test.
+///
+/// The default timeout runs the test on a separate thread, so it can only be applied to tests
+/// with output that can be sent across threads. The `decorate` and `test_casing` macros
+/// choose between [`ApplyDefaultTimeout`] and [`SkipDefaultTimeout`] at compile time
+/// by calling `(&DefaultTimeoutFn::new(test_fn)).run_with_default_timeout()`. Method resolution
+/// prefers `ApplyDefaultTimeout` (which doesn't require auto-referencing the receiver),
+/// and falls back to `SkipDefaultTimeout` if the output is not `Send`.
+#[doc(hidden)] // used by the `decorate` and `test_casing` macros; not public API
+#[derive(Debug)]
+pub struct DefaultTimeoutFn<F, R> {
+    test_fn: F,
+    _output: PhantomData<fn() -> R>,
+}
+
+impl<F: Fn() -> R, R> DefaultTimeoutFn<F, R> {
+    pub fn new(test_fn: F) -> Self {
+        Self {
+            test_fn,
+            _output: PhantomData,
+        }
+    }
+}
+
+#[doc(hidden)] // used by the `decorate` and `test_casing` macros; not public API
+pub trait ApplyDefaultTimeout<R> {
+    fn run_with_default_timeout(&self) -> R;
+}
+
+impl<F: TestFn<R>, R: Send + 'static> ApplyDefaultTimeout<R> for DefaultTimeoutFn<F, R> {
+    fn run_with_default_timeout(&self) -> R {
+        let is_nested = DEFAULT_TIMEOUT_HANDLE.with(|cell| cell.borrow().is_some());
+        if is_nested || decorators_disabled() {
+            return (self.test_fn)();
+        }
+        match default_timeout() {
+            Some(timeout) => Timeout::run_default(timeout, self.test_fn),
+            None => (self.test_fn)(),
+        }
+    }
+}
+
+#[doc(hidden)] // used by the `decorate` and `test_casing` macros; not public API
+pub trait SkipDefaultTimeout<R> {
+    fn run_with_default_timeout(&self) -> R;
+}
+
+impl<F: Fn() -> R, R> SkipDefaultTimeout<R> for &DefaultTimeoutFn<F, R> {
+    fn run_with_default_timeout(&self) -> R {
+        (self.test_fn)()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn parsing_durations() {
+        assert_eq!(parse_duration("60"), Some(Duration::from_secs(60)));
+        assert_eq!(parse_duration("60s"), Some(Duration::from_secs(60)));
+        assert_eq!(parse_duration(" 500ms "), Some(Duration::from_millis(500)));
+        assert_eq!(parse_duration("5m"), Some(Duration::from_secs(300)));
+        assert_eq!(parse_duration("2 h"), Some(Duration::from_secs(7_200)));
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("1.5s"), None);
+        assert_eq!(parse_duration("-1s"), None);
+        assert_eq!(parse_duration("5 days"), None);
+    }
+
+    #[test]
+    fn disarming_default_timeout() {
+        let handle = TimeoutHandle::new(Duration::from_secs(3_600));
+        set_handle(handle.clone());
+        disarm();
+        assert_eq!(handle.remaining(), None);
+        DEFAULT_TIMEOUT_HANDLE.with(|cell| cell.borrow_mut().take());
+    }
+
+    #[test]
+    fn skipping_default_timeout_for_non_send_output() {
+        let output = (&DefaultTimeoutFn::new(|| Rc::new(42))).run_with_default_timeout();
+        assert_eq!(*output, 42);
+    }
+}
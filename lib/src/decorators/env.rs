@@ -0,0 +1,235 @@
+//! Decorators mutating env variables for the duration of a test.
+
+use std::{
+    cell::Cell,
+    env,
+    ffi::OsString,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use super::{DecorateTest, TestFn};
+
+/// Lock serializing tests that mutate env variables via decorators.
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+thread_local! {
+    static HOLDS_ENV_LOCK: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Sets env variables and restores their previous values on drop (including when
+/// the test panics). The guard holds [`ENV_LOCK`] unless it's already held by the current thread,
+/// which allows nesting env-mutating decorators.
+#[derive(Debug)]
+pub(crate) struct EnvGuard {
+    lock: Option<MutexGuard<'static, ()>>,
+    prev_values: Vec<(&'static str, Option<OsString>)>,
+}
+
+impl EnvGuard {
+    pub(crate) fn set(vars: &[(&'static str, &'static str)]) -> Self {
+        let lock = if HOLDS_ENV_LOCK.with(Cell::get) {
+            None
+        } else {
+            let lock = ENV_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+            HOLDS_ENV_LOCK.with(|flag| flag.set(true));
+            Some(lock)
+        };
+
+        let prev_values = vars.iter().map(|&(name, value)| {
+            let prev_value = env::var_os(name);
+            env::set_var(name, value);
+            (name, prev_value)
+        });
+        let prev_values = prev_values.collect();
+        Self { lock, prev_values }
+    }
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        for (name, prev_value) in self.prev_values.drain(..).rev() {
+            if let Some(value) = prev_value {
+                env::set_var(name, value);
+            } else {
+                env::remove_var(name);
+            }
+        }
+        if self.lock.take().is_some() {
+            HOLDS_ENV_LOCK.with(|flag| flag.set(false));
+        }
+    }
+}
+
+/// [Test decorator](DecorateTest) that sets the specified env variables for the wrapped test.
+/// The previous values of the variables are restored after the test, including if it panics.
+///
+/// See [`Locale`] docs for details on serialization of env-mutating tests.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::EnvVars};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(EnvVars::new(&[("RUST_LOG", "debug"), ("APP_PORT", "8080")]))]
+/// fn test_with_env() {
+///     assert_eq!(std::env::var("APP_PORT").unwrap(), "8080");
+///     // other test logic
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct EnvVars {
+    vars: &'static [(&'static str, &'static str)],
+}
+
+impl EnvVars {
+    /// Creates a decorator setting the specified env variables, provided as `(name, value)` tuples.
+    pub const fn new(vars: &'static [(&'static str, &'static str)]) -> Self {
+        Self { vars }
+    }
+}
+
+impl<R> DecorateTest<R> for EnvVars {
+    fn decorate_and_test<F: TestFn<R>>(&self, test_fn: F) -> R {
+        let _guard = EnvGuard::set(self.vars);
+        test_fn()
+    }
+}
+
+/// [Test decorator](DecorateTest) that sets the locale for the wrapped test via `LANG`
+/// and `LC_ALL` env variables. The previous values of the variables are restored after the test.
+///
+/// Since env variables are process-wide, tests decorated with `Locale`, [`Timezone`], [`EnvVars`]
+/// and other env-mutating decorators from this crate are serialized with each other (but not
+/// with tests that don't use these decorators). Env-mutating decorators in a single decorator list
+/// can be freely combined, but should not be separated by a [`Timeout`](super::Timeout),
+/// since this would lead to a deadlock.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{Locale, Timezone}};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(Locale::new("en_US.UTF-8"), Timezone::UTC)]
+/// fn test_date_formatting() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Locale {
+    locale: &'static str,
+}
+
+impl Locale {
+    /// The `C` locale.
+    pub const C: Self = Self::new("C");
+
+    /// Creates a decorator setting the specified locale (e.g., `en_US.UTF-8`).
+    pub const fn new(locale: &'static str) -> Self {
+        Self { locale }
+    }
+}
+
+impl<R> DecorateTest<R> for Locale {
+    fn decorate_and_test<F: TestFn<R>>(&self, test_fn: F) -> R {
+        let _guard = EnvGuard::set(&[("LANG", self.locale), ("LC_ALL", self.locale)]);
+        test_fn()
+    }
+}
+
+/// [Test decorator](DecorateTest) that sets the timezone for the wrapped test via the `TZ`
+/// env variable. The previous value of the variable is restored after the test.
+///
+/// The timezone is only respected by code reading `TZ` each time the local time is computed
+/// (e.g., the `chrono` crate does this). See [`Locale`] docs for details on serialization
+/// of env-mutating tests.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::Timezone};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(Timezone::new("America/New_York"))]
+/// fn test_local_time() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Timezone {
+    tz: &'static str,
+}
+
+impl Timezone {
+    /// The UTC timezone.
+    pub const UTC: Self = Self::new("UTC");
+
+    /// Creates a decorator setting the specified timezone, e.g. `Europe/Berlin`.
+    pub const fn new(tz: &'static str) -> Self {
+        Self { tz }
+    }
+}
+
+impl<R> DecorateTest<R> for Timezone {
+    fn decorate_and_test<F: TestFn<R>>(&self, test_fn: F) -> R {
+        let _guard = EnvGuard::set(&[("TZ", self.tz)]);
+        test_fn()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic;
+
+    use super::*;
+
+    #[test]
+    fn setting_and_restoring_env() {
+        static DECORATORS: (Locale, Timezone) = (Locale::C, Timezone::new("Europe/Berlin"));
+
+        let _guard = EnvGuard::set(&[("TZ", "UTC")]);
+        let test_fn: fn() = || {
+            assert_eq!(env::var("LANG").unwrap(), "C");
+            assert_eq!(env::var("LC_ALL").unwrap(), "C");
+            assert_eq!(env::var("TZ").unwrap(), "Europe/Berlin");
+        };
+        DECORATORS.decorate_and_test(test_fn);
+        assert_eq!(env::var("TZ").unwrap(), "UTC");
+
+        let test_fn: fn() = || panic!("oops");
+        panic::catch_unwind(|| DECORATORS.decorate_and_test(test_fn)).unwrap_err();
+        assert_eq!(env::var("TZ").unwrap(), "UTC");
+        assert!(HOLDS_ENV_LOCK.with(Cell::get));
+    }
+
+    #[test]
+    fn setting_env_vars() {
+        const VAR_NAME: &str = "TEST_CASING_ENV_VARS_TEST";
+        static ENV_VARS: EnvVars = EnvVars::new(&[(VAR_NAME, "1"), ("TZ", "UTC")]);
+
+        let test_fn: fn() = || {
+            assert_eq!(env::var(VAR_NAME).unwrap(), "1");
+            assert_eq!(env::var("TZ").unwrap(), "UTC");
+            panic!("oops");
+        };
+        panic::catch_unwind(|| ENV_VARS.decorate_and_test(test_fn)).unwrap_err();
+        assert!(env::var_os(VAR_NAME).is_none());
+        assert!(!HOLDS_ENV_LOCK.with(Cell::get));
+    }
+
+    #[test]
+    fn restoring_unset_env_var() {
+        const VAR_NAME: &str = "TEST_CASING_ENV_GUARD_TEST";
+
+        {
+            let _guard = EnvGuard::set(&[(VAR_NAME, "1")]);
+            assert_eq!(env::var(VAR_NAME).unwrap(), "1");
+        }
+        assert!(env::var_os(VAR_NAME).is_none());
+        assert!(!HOLDS_ENV_LOCK.with(Cell::get));
+    }
+}
@@ -0,0 +1,341 @@
+//! Decorators expecting a test to fail in a specific way.
+
+use std::{cell::Cell, fmt, panic};
+
+use super::{extract_panic_str, DecorateTest, ExpectedPanicGuard, TestFn, TIMEOUT_EXPIRED};
+
+/// Matcher for failure messages.
+#[derive(Debug, Clone, Copy)]
+enum MessageMatcher {
+    Any,
+    Substring(&'static str),
+    Predicate(fn(&str) -> bool),
+    #[cfg(feature = "regex")]
+    Regex(&'static str),
+}
+
+impl MessageMatcher {
+    /// Checks whether `message` matches, panicking with a descriptive message if it doesn't.
+    fn check(self, message: &str, subject: &str) {
+        match self {
+            Self::Any => { /* always matches */ }
+            Self::Substring(substring) => {
+                assert!(
+                    message.contains(substring),
+                    "Expected {subject} message to contain `{substring}`, but it was: {message}"
+                );
+            }
+            Self::Predicate(predicate) => {
+                assert!(
+                    predicate(message),
+                    "Expected {subject} message to match the predicate, but it was: {message}"
+                );
+            }
+            #[cfg(feature = "regex")]
+            Self::Regex(pattern) => {
+                let regex = regex::Regex::new(pattern).unwrap_or_else(|err| {
+                    panic!("Invalid regular expression `{pattern}`: {err}");
+                });
+                assert!(
+                    regex.is_match(message),
+                    "Expected {subject} message to match `{pattern}`, but it was: {message}"
+                );
+            }
+        }
+    }
+
+    /// Creates a guard marking the test as expected to panic, so that wrapped decorators
+    /// (e.g., [`Retry`](super::Retry)) treat the expected panic as a success. The guard can
+    /// only express substring matching, so it's not created for other matchers.
+    fn expected_panic_guard(self) -> Option<ExpectedPanicGuard> {
+        match self {
+            Self::Any => Some(ExpectedPanicGuard::new(None)),
+            Self::Substring(substring) => Some(ExpectedPanicGuard::new(Some(substring))),
+            Self::Predicate(_) => None,
+            #[cfg(feature = "regex")]
+            Self::Regex(_) => None,
+        }
+    }
+}
+
+/// [Test decorator](DecorateTest) that expects a wrapped test to return an error, i.e.,
+/// an analogue of `#[should_panic]` for tests returning `Result`s.
+///
+/// If the test returns an error matching the expectation, the decorator returns `Ok(())`.
+/// If the test completes successfully, or the error message (obtained via the [`Display`](fmt::Display)
+/// implementation) doesn't match the expectation, the decorator panics.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::ShouldError};
+/// use std::error::Error;
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(ShouldError::containing("oops"))]
+/// fn test_with_an_error() -> Result<(), Box<dyn Error>> {
+///     Err("oops, this test failed".into())
+/// }
+///
+/// #[test]
+/// # fn eat_test_attribute2() {}
+/// #[decorate(ShouldError::matching(|message| message.starts_with("invalid")))]
+/// fn test_with_an_invalid_input() -> Result<(), Box<dyn Error>> {
+///     Err("invalid input: 42".into())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ShouldError {
+    matcher: MessageMatcher,
+}
+
+impl ShouldError {
+    /// Expects the test to return any error.
+    pub const ANY: Self = Self {
+        matcher: MessageMatcher::Any,
+    };
+
+    /// Expects the test to return an error with the message containing the specified substring.
+    pub const fn containing(substring: &'static str) -> Self {
+        Self {
+            matcher: MessageMatcher::Substring(substring),
+        }
+    }
+
+    /// Expects the test to return an error with the message matching the specified predicate.
+    pub const fn matching(predicate: fn(&str) -> bool) -> Self {
+        Self {
+            matcher: MessageMatcher::Predicate(predicate),
+        }
+    }
+}
+
+impl<E: fmt::Display> DecorateTest<Result<(), E>> for ShouldError {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        let Err(err) = test_fn() else {
+            panic!("Expected test to error, but it completed successfully");
+        };
+        self.matcher.check(&err.to_string(), "error");
+        Ok(())
+    }
+}
+
+/// [Test decorator](DecorateTest) that expects a wrapped test to panic with a message
+/// matching the expectation, similar to the built-in `#[should_panic]` attribute.
+///
+/// If the test doesn't panic, returns an error, or the panic message doesn't match
+/// the expectation, the decorator panics. Unlike `#[should_panic]`, `ExpectPanic` is applied
+/// in a defined order relative to other decorators. For example, with
+/// `#[decorate(ExpectPanic::containing("oops"), Retry::times(2))]`, each attempt is expected
+/// to panic, and attempts not panicking are retried.
+///
+/// If `ExpectPanic` with [`ANY`](Self::ANY) or [substring](Self::containing()) matching
+/// wraps a [`Retry`](super::Retry) (i.e., follows it in the decorator list), the expected panic
+/// is not retried, similarly to tests marked with `#[should_panic]`. Panics caused
+/// by an expired [`Timeout`](super::Timeout) are never considered expected.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{ExpectPanic, Timeout}};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(ExpectPanic::containing("division by zero"), Timeout::secs(5))]
+/// fn test_with_panic() {
+///     let divisor = 0;
+///     # let divisor = std::hint::black_box(divisor);
+///     println!("{}", 1 / divisor);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ExpectPanic {
+    matcher: MessageMatcher,
+}
+
+impl ExpectPanic {
+    /// Expects the test to panic with any message.
+    pub const ANY: Self = Self {
+        matcher: MessageMatcher::Any,
+    };
+
+    /// Expects the test to panic with the message containing the specified substring.
+    pub const fn containing(substring: &'static str) -> Self {
+        Self {
+            matcher: MessageMatcher::Substring(substring),
+        }
+    }
+
+    /// Expects the test to panic with the message matching the specified predicate.
+    pub const fn matching(predicate: fn(&str) -> bool) -> Self {
+        Self {
+            matcher: MessageMatcher::Predicate(predicate),
+        }
+    }
+
+    /// Expects the test to panic with the message matching the specified regular expression.
+    /// The expression is validated when the test is run.
+    #[cfg(feature = "regex")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "regex")))]
+    pub const fn matching_regex(pattern: &'static str) -> Self {
+        Self {
+            matcher: MessageMatcher::Regex(pattern),
+        }
+    }
+
+    /// Runs the test, returning `None` if it panicked as expected.
+    fn run<R, F: TestFn<R>>(self, test_fn: F) -> Option<R> {
+        let guard = self.matcher.expected_panic_guard();
+        let output = panic::catch_unwind(test_fn);
+        drop(guard);
+
+        let panic_object = match output {
+            Ok(output) => return Some(output),
+            Err(panic_object) => panic_object,
+        };
+        if TIMEOUT_EXPIRED.with(Cell::get) {
+            panic::resume_unwind(panic_object);
+        }
+        let message = extract_panic_str(&*panic_object).unwrap_or("");
+        self.matcher.check(message, "panic");
+        None
+    }
+}
+
+impl DecorateTest<()> for ExpectPanic {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        assert!(
+            self.run(test_fn).is_none(),
+            "Expected test to panic, but it completed successfully"
+        );
+    }
+}
+
+impl<E: fmt::Debug> DecorateTest<Result<(), E>> for ExpectPanic {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        match self.run(test_fn) {
+            None => Ok(()),
+            Some(Ok(())) => panic!("Expected test to panic, but it completed successfully"),
+            Some(Err(err)) => panic!("Expected test to panic, but it returned an error: {err:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+    use crate::decorators::Retry;
+
+    fn test_fn() -> Result<(), String> {
+        Err("oops, this test failed".to_owned())
+    }
+
+    #[test]
+    fn expecting_error() {
+        static DECORATORS: [ShouldError; 3] = [
+            ShouldError::ANY,
+            ShouldError::containing("oops"),
+            ShouldError::matching(|message| message.ends_with("failed")),
+        ];
+
+        for decorator in &DECORATORS {
+            decorator.decorate_and_test(test_fn).unwrap();
+        }
+    }
+
+    #[test]
+    fn error_mismatch() {
+        static DECORATOR: ShouldError = ShouldError::containing("what?");
+
+        let panic_object =
+            panic::catch_unwind(|| DECORATOR.decorate_and_test(test_fn)).unwrap_err();
+        let panic_str = extract_panic_str(&*panic_object).unwrap();
+        assert!(panic_str.contains("to contain `what?`"), "{panic_str}");
+
+        let ok_fn: fn() -> Result<(), String> = || Ok(());
+        let panic_object = panic::catch_unwind(|| DECORATOR.decorate_and_test(ok_fn)).unwrap_err();
+        let panic_str = extract_panic_str(&*panic_object).unwrap();
+        assert!(panic_str.contains("completed successfully"), "{panic_str}");
+    }
+
+    #[test]
+    fn expecting_panic() {
+        static DECORATORS: [ExpectPanic; 3] = [
+            ExpectPanic::ANY,
+            ExpectPanic::containing("oops"),
+            ExpectPanic::matching(|message| message.ends_with("failed")),
+        ];
+
+        let test_fn: fn() = || panic!("oops, this test failed");
+        for decorator in &DECORATORS {
+            decorator.decorate_and_test(test_fn);
+        }
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn expecting_panic_with_regex() {
+        static DECORATOR: ExpectPanic = ExpectPanic::matching_regex(r"^oops, \w+ test");
+
+        let test_fn: fn() = || panic!("oops, this test failed");
+        DECORATOR.decorate_and_test(test_fn);
+
+        let test_fn: fn() = || panic!("this test failed");
+        let panic_object =
+            panic::catch_unwind(|| DECORATOR.decorate_and_test(test_fn)).unwrap_err();
+        let panic_str = extract_panic_str(&*panic_object).unwrap();
+        assert!(panic_str.contains("to match `^oops"), "{panic_str}");
+    }
+
+    #[test]
+    fn panic_mismatch() {
+        static DECORATOR: ExpectPanic = ExpectPanic::containing("what?");
+
+        let test_fn: fn() = || panic!("oops");
+        let panic_object =
+            panic::catch_unwind(|| DECORATOR.decorate_and_test(test_fn)).unwrap_err();
+        let panic_str = extract_panic_str(&*panic_object).unwrap();
+        assert!(panic_str.contains("to contain `what?`"), "{panic_str}");
+
+        let panic_object = panic::catch_unwind(|| DECORATOR.decorate_and_test(|| {})).unwrap_err();
+        let panic_str = extract_panic_str(&*panic_object).unwrap();
+        assert!(panic_str.contains("completed successfully"), "{panic_str}");
+
+        let err_fn: fn() -> Result<(), &'static str> = || Err("oops");
+        let panic_object = panic::catch_unwind(|| DECORATOR.decorate_and_test(err_fn)).unwrap_err();
+        let panic_str = extract_panic_str(&*panic_object).unwrap();
+        assert!(panic_str.contains("returned an error"), "{panic_str}");
+    }
+
+    #[test]
+    fn expected_panic_is_not_retried() {
+        static DECORATORS: (Retry, ExpectPanic) =
+            (Retry::times(2), ExpectPanic::containing("oops"));
+        static RETRIED_DECORATORS: (ExpectPanic, Retry) =
+            (ExpectPanic::containing("oops"), Retry::times(2));
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        let test_fn: fn() = || {
+            COUNTER.fetch_add(1, Ordering::SeqCst);
+            panic!("oops");
+        };
+        DECORATORS.decorate_and_test(test_fn);
+        assert_eq!(COUNTER.load(Ordering::SeqCst), 1);
+
+        // Each attempt is expected to panic; the attempts not panicking are retried.
+        let test_fn: fn() = || {
+            assert!(COUNTER.fetch_add(1, Ordering::SeqCst) == 1, "oops");
+        };
+        RETRIED_DECORATORS.decorate_and_test(test_fn);
+        assert_eq!(COUNTER.load(Ordering::SeqCst), 3);
+    }
+}
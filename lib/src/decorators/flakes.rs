@@ -0,0 +1,852 @@
+//! Decorators helping to deal with flaky tests.
+
+use std::{
+    cell::Cell,
+    collections::hash_map::RandomState,
+    env,
+    hash::{BuildHasher, Hasher},
+    panic,
+    sync::OnceLock,
+    time::{Duration, Instant, SystemTime},
+};
+
+use super::{is_truthy_env_value, test_location_suffix, DecorateTest, OutputSink, TestFn};
+
+/// Name of the env variable making [`Quarantine`]d tests fail on failures.
+const STRICT_QUARANTINE_ENV_VAR: &str = "TEST_CASING_STRICT_QUARANTINE";
+
+/// Name of the env variable restricting [`SeedSweep`] to a single seed and fixing
+/// the seed for [`RandomSeed`].
+const SEED_ENV_VAR: &str = "TEST_CASING_SEED";
+
+thread_local! {
+    static CURRENT_SEED: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Sets the seed for the current iteration and restores the previous one on drop.
+#[derive(Debug)]
+struct SeedGuard {
+    prev_seed: Option<u64>,
+}
+
+impl SeedGuard {
+    fn new(seed: u64) -> Self {
+        Self {
+            prev_seed: CURRENT_SEED.with(|cell| cell.replace(Some(seed))),
+        }
+    }
+}
+
+impl Drop for SeedGuard {
+    fn drop(&mut self) {
+        CURRENT_SEED.with(|cell| cell.set(self.prev_seed));
+    }
+}
+
+/// Gets the seed from the [`SEED_ENV_VAR`] env variable.
+fn seed_from_env() -> Option<u64> {
+    static SEED: OnceLock<Option<u64>> = OnceLock::new();
+
+    *SEED.get_or_init(|| {
+        let value = env::var(SEED_ENV_VAR).ok()?;
+        let seed = value.parse().unwrap_or_else(|err| {
+            panic!("Invalid value of the `{SEED_ENV_VAR}` env variable: `{value}`: {err}");
+        });
+        Some(seed)
+    })
+}
+
+/// Derives a seed from the base seed and an index using the `SplitMix64` mixing function.
+fn derive_seed(base_seed: u64, index: usize) -> u64 {
+    let mut z = base_seed.wrapping_add((index as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// [Test decorator](DecorateTest) that reruns a wrapped test up to the specified number
+/// of times, stopping at the first failure. This is the inverse of [`Retry`](super::Retry),
+/// useful to reproduce flaky behavior locally.
+///
+/// On failure, the decorator reports the failed iteration, its seed and timing info
+/// to the [global sink](OutputSink::global()), and propagates the failure. Each iteration gets a seed deterministically derived
+/// from the base seed (configurable via [`Self::with_seed()`]) and the iteration index;
+/// the test can access it via [`Self::seed()`] (e.g., to initialize an RNG), so that
+/// a failing iteration can be reproduced.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::ReproduceFlake};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(ReproduceFlake::times(100))]
+/// fn possibly_flaky_test() {
+///     let seed = ReproduceFlake::seed().unwrap_or(0);
+///     // test logic using `seed`
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ReproduceFlake {
+    iterations: usize,
+    seed: u64,
+}
+
+impl ReproduceFlake {
+    /// Specifies the maximum number of iterations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `iterations` is 0. If the decorator is constructed in a constant context
+    /// (e.g., in a `static`), this is a compile-time error.
+    pub const fn times(iterations: usize) -> Self {
+        assert!(
+            iterations > 0,
+            "`ReproduceFlake` must have at least 1 iteration"
+        );
+        Self {
+            iterations,
+            seed: 0,
+        }
+    }
+
+    /// Specifies the base seed for iterations.
+    #[must_use]
+    pub const fn with_seed(self, seed: u64) -> Self {
+        Self { seed, ..self }
+    }
+
+    /// Returns the seed for the current iteration, or `None` if called outside a test
+    /// decorated with `ReproduceFlake` or [`SeedSweep`].
+    pub fn seed() -> Option<u64> {
+        CURRENT_SEED.with(Cell::get)
+    }
+
+    fn iteration_seed(self, iteration: usize) -> u64 {
+        derive_seed(self.seed, iteration)
+    }
+
+    fn report_failure(self, iteration: usize, seed: u64, elapsed: Duration, total: Duration) {
+        OutputSink::global().emit(format_args!(
+            "Test failed on iteration #{iteration} of {iterations} (seed: {seed}){}; \
+             iteration took {elapsed:?}, {total:?} in total",
+            test_location_suffix(),
+            iterations = self.iterations
+        ));
+    }
+
+    fn report_success(iterations: usize, total: Duration) {
+        OutputSink::global().emit(format_args!(
+            "Test passed all {iterations} iterations{} in {total:?}",
+            test_location_suffix()
+        ));
+    }
+
+    fn run_iterations<R, F: TestFn<R>>(self, test_fn: F, is_ok: fn(&R) -> bool) -> R {
+        let started_at = Instant::now();
+        for iteration in 0..self.iterations {
+            let seed = self.iteration_seed(iteration);
+            let iteration_started_at = Instant::now();
+            let output = {
+                let _guard = SeedGuard::new(seed);
+                panic::catch_unwind(test_fn)
+            };
+            let elapsed = iteration_started_at.elapsed();
+
+            match output {
+                Ok(output) if is_ok(&output) => {
+                    if iteration + 1 == self.iterations {
+                        return output;
+                    }
+                }
+                Ok(output) => {
+                    self.report_failure(iteration, seed, elapsed, started_at.elapsed());
+                    return output;
+                }
+                Err(panic_object) => {
+                    self.report_failure(iteration, seed, elapsed, started_at.elapsed());
+                    panic::resume_unwind(panic_object);
+                }
+            }
+        }
+        unreachable!("`ReproduceFlake` must have at least 1 iteration");
+    }
+}
+
+impl DecorateTest<()> for ReproduceFlake {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        let started_at = Instant::now();
+        self.run_iterations(test_fn, |()| true);
+        Self::report_success(self.iterations, started_at.elapsed());
+    }
+}
+
+impl<E> DecorateTest<Result<(), E>> for ReproduceFlake {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        let started_at = Instant::now();
+        let output = self.run_iterations(test_fn, Result::is_ok);
+        if output.is_ok() {
+            Self::report_success(self.iterations, started_at.elapsed());
+        }
+        output
+    }
+}
+
+/// [Test decorator](DecorateTest) that runs a wrapped test the specified number of times,
+/// failing on the first failure. Useful to shake out race conditions without writing loops
+/// inside tests.
+///
+/// On failure, the decorator reports the index of the failed repetition to
+/// the [global sink](OutputSink::global()) and propagates the failure. Unlike
+/// [`ReproduceFlake`], repetitions are not assigned seeds, and timings are not reported.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{Repeat, Timeout}};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(Repeat::times(100), Timeout::secs(30))]
+/// fn racy_test() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Repeat {
+    times: usize,
+}
+
+impl Repeat {
+    /// Specifies the number of repetitions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `times` is 0. If the decorator is constructed in a constant context
+    /// (e.g., in a `static`), this is a compile-time error.
+    pub const fn times(times: usize) -> Self {
+        assert!(times > 0, "`Repeat` must have at least 1 repetition");
+        Self { times }
+    }
+
+    fn report_failure(self, repetition: usize) {
+        OutputSink::global().emit(format_args!(
+            "Test failed on repetition #{repetition} of {times}{}",
+            test_location_suffix(),
+            times = self.times
+        ));
+    }
+
+    fn run<R, F: TestFn<R>>(self, test_fn: F, is_ok: fn(&R) -> bool) -> R {
+        for repetition in 0..self.times {
+            match panic::catch_unwind(test_fn) {
+                Ok(output) if is_ok(&output) => {
+                    if repetition + 1 == self.times {
+                        return output;
+                    }
+                }
+                Ok(output) => {
+                    self.report_failure(repetition);
+                    return output;
+                }
+                Err(panic_object) => {
+                    self.report_failure(repetition);
+                    panic::resume_unwind(panic_object);
+                }
+            }
+        }
+        unreachable!("`Repeat` must have at least 1 repetition");
+    }
+}
+
+impl DecorateTest<()> for Repeat {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        self.run(test_fn, |()| true);
+    }
+}
+
+impl<E> DecorateTest<Result<(), E>> for Repeat {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.run(test_fn, Result::is_ok)
+    }
+}
+
+/// [Test decorator](DecorateTest) that runs a wrapped test the specified number of times
+/// with different seeds, stopping at the first failure. Useful to shake out bugs depending
+/// on randomness, e.g. on the task order in async schedulers or on the iteration order
+/// of hash maps.
+///
+/// Seeds are deterministically derived from the iteration index, so they are the same
+/// across runs. The test can access the seed via [`ReproduceFlake::seed()`] (e.g., to initialize
+/// an RNG); it is also picked up by decorators using seeds, such as `NetworkSim`, if they
+/// precede `SeedSweep` in the decorator list.
+///
+/// On failure, the decorator reports the failing seed to the [global sink](OutputSink::global())
+/// and propagates the failure. To reproduce the failure, set the `TEST_CASING_SEED` env variable
+/// to the reported seed; in this case, the test is run once with this seed.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{ReproduceFlake, SeedSweep}};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(SeedSweep::new(20))]
+/// fn test_with_seeds() {
+///     let seed = ReproduceFlake::seed().unwrap();
+///     // test logic using `seed`, e.g. to shuffle inputs
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SeedSweep {
+    iterations: usize,
+}
+
+impl SeedSweep {
+    /// Specifies the number of iterations, i.e. the number of seeds to test.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `iterations` is 0. If the decorator is constructed in a constant context
+    /// (e.g., in a `static`), this is a compile-time error.
+    pub const fn new(iterations: usize) -> Self {
+        assert!(iterations > 0, "`SeedSweep` must have at least 1 iteration");
+        Self { iterations }
+    }
+
+    fn seeds(self) -> Vec<u64> {
+        if let Some(seed) = seed_from_env() {
+            vec![seed]
+        } else {
+            (0..self.iterations).map(|i| derive_seed(0, i)).collect()
+        }
+    }
+
+    fn report_failure(seed: u64, iteration: usize, iterations: usize) {
+        OutputSink::global().emit(format_args!(
+            "Test failed with seed {seed} (iteration #{iteration} of {iterations}){}; \
+             set `{SEED_ENV_VAR}={seed}` to reproduce",
+            test_location_suffix()
+        ));
+    }
+
+    fn run_seeds<R, F: TestFn<R>>(self, test_fn: F, is_ok: fn(&R) -> bool) -> R {
+        let seeds = self.seeds();
+        let iterations = seeds.len();
+        let mut seeds = seeds.into_iter().enumerate().peekable();
+        while let Some((iteration, seed)) = seeds.next() {
+            let output = {
+                let _guard = SeedGuard::new(seed);
+                panic::catch_unwind(test_fn)
+            };
+            match output {
+                Ok(output) if is_ok(&output) => {
+                    if seeds.peek().is_none() {
+                        return output;
+                    }
+                }
+                Ok(output) => {
+                    Self::report_failure(seed, iteration, iterations);
+                    return output;
+                }
+                Err(panic_object) => {
+                    Self::report_failure(seed, iteration, iterations);
+                    panic::resume_unwind(panic_object);
+                }
+            }
+        }
+        unreachable!("`SeedSweep` must have at least 1 iteration");
+    }
+}
+
+impl DecorateTest<()> for SeedSweep {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        self.run_seeds(test_fn, |()| true);
+    }
+}
+
+impl<E> DecorateTest<Result<(), E>> for SeedSweep {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.run_seeds(test_fn, Result::is_ok)
+    }
+}
+
+/// [Test decorator](DecorateTest) that provides a fresh random seed for each run of the wrapped
+/// test. The test can access the seed via [`Self::seed()`] (e.g., to initialize an RNG), which
+/// makes randomized tests cover new inputs on each run, yet remain reproducible.
+///
+/// The seed is printed to the [global sink](OutputSink::global()) before the test starts.
+/// To reproduce a failure, set the `TEST_CASING_SEED` env variable to the printed seed; in this
+/// case, the seed is taken from the variable instead of being generated.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::RandomSeed};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(RandomSeed)]
+/// fn randomized_test() {
+///     let seed = RandomSeed::seed().unwrap();
+///     // test logic using `seed`, e.g. `StdRng::seed_from_u64(seed)`
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RandomSeed;
+
+impl RandomSeed {
+    /// Returns the seed for the current test, or `None` if called outside a test decorated
+    /// with `RandomSeed`. Equivalent to [`ReproduceFlake::seed()`].
+    pub fn seed() -> Option<u64> {
+        CURRENT_SEED.with(Cell::get)
+    }
+
+    fn generate_seed() -> u64 {
+        // `RandomState` is randomly keyed per process and per instance; mixing in the current time
+        // guards against platforms with weak randomness.
+        let mut hasher = RandomState::new().build_hasher();
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        hasher.write_u128(now.as_nanos());
+        hasher.finish()
+    }
+}
+
+impl<R> DecorateTest<R> for RandomSeed {
+    fn decorate_and_test<F: TestFn<R>>(&self, test_fn: F) -> R {
+        let seed = seed_from_env().unwrap_or_else(Self::generate_seed);
+        OutputSink::global().emit(format_args!(
+            "Using seed {seed}{}; set `{SEED_ENV_VAR}={seed}` to reproduce",
+            test_location_suffix()
+        ));
+        let _guard = SeedGuard::new(seed);
+        test_fn()
+    }
+}
+
+/// [Test decorator](DecorateTest) that runs a wrapped test the specified number of times
+/// regardless of the outcome, and checks that the pass rate is at least the specified threshold.
+/// Unlike [`Retry`](super::Retry), which masks flakiness, this decorator is useful to detect
+/// and quantify it.
+///
+/// After all runs, the decorator reports the pass rate and indices of the failed runs
+/// to the [global sink](OutputSink::global()). If the pass rate is below the threshold
+/// (by default, 1, i.e. all runs must pass), the test panics, unless the decorator is configured
+/// to [only report](Self::report_only()) the pass rate. Errors returned by the test are treated
+/// as failures, but are not propagated.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::FlakinessProbe};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(FlakinessProbe::runs(50).with_min_pass_rate(0.95))]
+/// fn possibly_flaky_test() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct FlakinessProbe {
+    runs: usize,
+    min_pass_rate: f64,
+    report_only: bool,
+}
+
+impl FlakinessProbe {
+    /// Specifies the number of test runs.
+    pub const fn runs(runs: usize) -> Self {
+        Self {
+            runs,
+            min_pass_rate: 1.0,
+            report_only: false,
+        }
+    }
+
+    /// Specifies the minimum pass rate in the `0.0..=1.0` range.
+    #[must_use]
+    pub const fn with_min_pass_rate(self, min_pass_rate: f64) -> Self {
+        Self {
+            min_pass_rate,
+            ..self
+        }
+    }
+
+    /// Only reports the pass rate instead of failing the test if it is below the threshold.
+    #[must_use]
+    pub const fn report_only(self) -> Self {
+        Self {
+            report_only: true,
+            ..self
+        }
+    }
+
+    fn probe<R, F: TestFn<R>>(self, test_fn: F, is_ok: fn(&R) -> bool) {
+        assert!(self.runs > 0, "`FlakinessProbe` must have at least 1 run");
+        assert!(
+            (0.0..=1.0).contains(&self.min_pass_rate),
+            "Minimum pass rate must be in 0.0..=1.0, got {}",
+            self.min_pass_rate
+        );
+
+        let failed_runs: Vec<_> = (0..self.runs)
+            .filter(|_| !panic::catch_unwind(test_fn).is_ok_and(|output| is_ok(&output)))
+            .collect();
+        let passed = self.runs - failed_runs.len();
+        #[allow(clippy::cast_precision_loss)] // not an issue for realistic run counts
+        let pass_rate = passed as f64 / self.runs as f64;
+
+        OutputSink::global().emit(format_args!(
+            "Test passed {passed} of {runs} runs (pass rate: {pass_rate:.3}){location}; \
+             failed runs: {failed_runs:?}",
+            runs = self.runs,
+            location = test_location_suffix()
+        ));
+        assert!(
+            self.report_only || pass_rate >= self.min_pass_rate,
+            "Test pass rate {pass_rate:.3} is below the threshold {}",
+            self.min_pass_rate
+        );
+    }
+}
+
+impl DecorateTest<()> for FlakinessProbe {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        self.probe(test_fn, |()| true);
+    }
+}
+
+impl<E> DecorateTest<Result<(), E>> for FlakinessProbe {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.probe(test_fn, Result::is_ok);
+        Ok(())
+    }
+}
+
+/// [Test decorator](DecorateTest) that quarantines a known flaky test. The test is run as usual,
+/// but its failures (panics or errors) are reported to the [output sink](Self::with_sink())
+/// instead of failing the test. This allows parking flaky tests without `#[ignore]`-ing them,
+/// so that they are still exercised, and their outcomes are visible.
+///
+/// If the `TEST_CASING_STRICT_QUARANTINE` env variable is set to a non-empty value other than `0`,
+/// quarantined tests are run in the strict mode, i.e. their failures are propagated as usual.
+/// This is useful e.g. to check whether a quarantined test is fixed.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{Quarantine, Timeout}};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(Timeout::secs(5), Quarantine::new("flaky on CI, see issue #123"))]
+/// fn flaky_test() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Quarantine {
+    reason: &'static str,
+    sink: Option<OutputSink>,
+}
+
+impl Quarantine {
+    /// Creates a decorator with the specified human-readable reason for quarantining the test
+    /// (e.g., a link to the issue tracking the flakiness).
+    pub const fn new(reason: &'static str) -> Self {
+        Self { reason, sink: None }
+    }
+
+    /// Specifies the sink for reports about failures. If not specified,
+    /// the [global sink](OutputSink::global()) is used.
+    #[must_use]
+    pub const fn with_sink(self, sink: OutputSink) -> Self {
+        Self {
+            sink: Some(sink),
+            ..self
+        }
+    }
+
+    fn is_strict() -> bool {
+        static IS_STRICT: OnceLock<bool> = OnceLock::new();
+
+        *IS_STRICT.get_or_init(|| {
+            env::var_os(STRICT_QUARANTINE_ENV_VAR).is_some_and(|value| is_truthy_env_value(&value))
+        })
+    }
+
+    fn run<R, F: TestFn<R>>(
+        self,
+        test_fn: F,
+        is_strict: bool,
+        ok_value: R,
+        is_ok: fn(&R) -> bool,
+    ) -> R {
+        if is_strict {
+            return test_fn();
+        }
+
+        match panic::catch_unwind(test_fn) {
+            Ok(output) if is_ok(&output) => return output,
+            _ => {}
+        }
+        OutputSink::resolve(self.sink).emit(format_args!(
+            "Quarantined test{} failed (known flaky: {}); ignoring the failure. \
+             Set `{STRICT_QUARANTINE_ENV_VAR}=1` to fail quarantined tests",
+            test_location_suffix(),
+            self.reason
+        ));
+        ok_value
+    }
+}
+
+impl DecorateTest<()> for Quarantine {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        self.run(test_fn, Self::is_strict(), (), |()| true);
+    }
+}
+
+impl<E> DecorateTest<Result<(), E>> for Quarantine {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.run(test_fn, Self::is_strict(), Ok(()), Result::is_ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashSet,
+        sync::{
+            atomic::{AtomicU32, Ordering},
+            Mutex,
+        },
+    };
+
+    use super::*;
+
+    #[test]
+    fn iteration_seeds_are_distinct() {
+        let decorator = ReproduceFlake::times(3).with_seed(123);
+        let seeds: Vec<_> = (0..3).map(|i| decorator.iteration_seed(i)).collect();
+        assert_ne!(seeds[0], seeds[1]);
+        assert_ne!(seeds[1], seeds[2]);
+        assert_eq!(seeds[0], decorator.iteration_seed(0));
+    }
+
+    #[test]
+    fn stopping_at_first_error() {
+        static DECORATOR: ReproduceFlake = ReproduceFlake::times(10);
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        let test_fn: fn() -> Result<(), u32> = || {
+            assert!(ReproduceFlake::seed().is_some());
+            let iteration = COUNTER.fetch_add(1, Ordering::SeqCst);
+            if iteration == 3 {
+                Err(iteration)
+            } else {
+                Ok(())
+            }
+        };
+        assert_eq!(DECORATOR.decorate_and_test(test_fn), Err(3));
+        assert_eq!(COUNTER.load(Ordering::SeqCst), 4);
+        assert!(ReproduceFlake::seed().is_none());
+    }
+
+    #[test]
+    fn stopping_at_first_panic() {
+        static DECORATOR: ReproduceFlake = ReproduceFlake::times(10);
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        let test_fn: fn() = || {
+            assert!(COUNTER.fetch_add(1, Ordering::SeqCst) < 2, "oops");
+        };
+        panic::catch_unwind(|| DECORATOR.decorate_and_test(test_fn)).unwrap_err();
+        assert_eq!(COUNTER.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn passing_all_iterations() {
+        static DECORATOR: ReproduceFlake = ReproduceFlake::times(5);
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        let test_fn: fn() = || {
+            COUNTER.fetch_add(1, Ordering::SeqCst);
+        };
+        DECORATOR.decorate_and_test(test_fn);
+        assert_eq!(COUNTER.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "`ReproduceFlake` must have at least 1 iteration")]
+    fn zero_reproduce_flake_iterations() {
+        ReproduceFlake::times(std::hint::black_box(0));
+    }
+
+    #[test]
+    fn repeating_test() {
+        static REPEAT: Repeat = Repeat::times(5);
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        let test_fn: fn() -> Result<(), u32> = || {
+            let repetition = COUNTER.fetch_add(1, Ordering::SeqCst);
+            if repetition == 7 {
+                Err(repetition)
+            } else {
+                Ok(())
+            }
+        };
+        REPEAT.decorate_and_test(test_fn).unwrap();
+        assert_eq!(COUNTER.load(Ordering::SeqCst), 5);
+        assert_eq!(REPEAT.decorate_and_test(test_fn), Err(7));
+        assert_eq!(COUNTER.load(Ordering::SeqCst), 8);
+    }
+
+    #[test]
+    #[should_panic(expected = "`Repeat` must have at least 1 repetition")]
+    fn zero_repetitions() {
+        Repeat::times(std::hint::black_box(0));
+    }
+
+    #[test]
+    fn sweeping_seeds() {
+        static DECORATOR: SeedSweep = SeedSweep::new(10);
+        static SEEDS: Mutex<Vec<u64>> = Mutex::new(Vec::new());
+
+        let test_fn: fn() -> Result<(), u64> = || {
+            let seed = ReproduceFlake::seed().unwrap();
+            let mut seeds = SEEDS.lock().unwrap();
+            seeds.push(seed);
+            if seeds.len() == 5 {
+                Err(seed)
+            } else {
+                Ok(())
+            }
+        };
+        let err = DECORATOR.decorate_and_test(test_fn).unwrap_err();
+        let seeds = SEEDS.lock().unwrap();
+        assert_eq!(seeds.len(), 5);
+        assert_eq!(err, seeds[4]);
+        let unique_seeds: HashSet<_> = seeds.iter().copied().collect();
+        assert_eq!(unique_seeds.len(), 5);
+        assert_eq!(*seeds, DECORATOR.seeds()[..5]);
+        assert!(ReproduceFlake::seed().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "`SeedSweep` must have at least 1 iteration")]
+    fn zero_seed_sweep_iterations() {
+        SeedSweep::new(std::hint::black_box(0));
+    }
+
+    #[test]
+    fn probing_flakiness() {
+        static PROBE: FlakinessProbe = FlakinessProbe::runs(10).with_min_pass_rate(0.8);
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        let test_fn: fn() -> Result<(), u32> = || {
+            let run = COUNTER.fetch_add(1, Ordering::SeqCst);
+            assert!(run != 2, "oops");
+            if run == 5 {
+                Err(run)
+            } else {
+                Ok(())
+            }
+        };
+        PROBE.decorate_and_test(test_fn).unwrap();
+        assert_eq!(COUNTER.load(Ordering::SeqCst), 10);
+    }
+
+    #[test]
+    fn probing_flakiness_below_threshold() {
+        static PROBE: FlakinessProbe = FlakinessProbe::runs(4);
+        static REPORTING_PROBE: FlakinessProbe = FlakinessProbe::runs(4).report_only();
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        let test_fn: fn() = || {
+            assert!(COUNTER.fetch_add(1, Ordering::SeqCst) % 2 == 0, "oops");
+        };
+        REPORTING_PROBE.decorate_and_test(test_fn);
+        assert_eq!(COUNTER.load(Ordering::SeqCst), 4);
+
+        let panic_object = panic::catch_unwind(|| PROBE.decorate_and_test(test_fn)).unwrap_err();
+        let message = panic_object.downcast_ref::<String>().unwrap();
+        assert!(message.contains("pass rate 0.500"), "{message}");
+        assert_eq!(COUNTER.load(Ordering::SeqCst), 8);
+    }
+
+    #[test]
+    fn providing_random_seed() {
+        static SEEDS: Mutex<Vec<u64>> = Mutex::new(Vec::new());
+
+        let test_fn: fn() = || {
+            SEEDS.lock().unwrap().push(RandomSeed::seed().unwrap());
+        };
+        RandomSeed.decorate_and_test(test_fn);
+        RandomSeed.decorate_and_test(test_fn);
+        let seeds = SEEDS.lock().unwrap();
+        assert_eq!(seeds.len(), 2);
+        if seed_from_env().is_none() {
+            assert_ne!(seeds[0], seeds[1]);
+        }
+        assert!(RandomSeed::seed().is_none());
+    }
+
+    #[test]
+    fn quarantining_test() {
+        static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+        const DECORATOR: Quarantine =
+            Quarantine::new("flaky").with_sink(OutputSink::Custom(|message| {
+                MESSAGES.lock().unwrap().push(message.to_owned());
+            }));
+
+        DECORATOR.run(|| {}, false, (), |()| true);
+        assert!(MESSAGES.lock().unwrap().is_empty());
+
+        let test_fn: fn() = || panic!("oops");
+        DECORATOR.run(test_fn, false, (), |()| true);
+        let test_fn: fn() -> Result<(), &'static str> = || Err("oops");
+        assert_eq!(DECORATOR.run(test_fn, false, Ok(()), Result::is_ok), Ok(()));
+
+        let messages = MESSAGES.lock().unwrap();
+        assert_eq!(messages.len(), 2, "{messages:?}");
+        assert!(messages[0].contains("known flaky: flaky"), "{messages:?}");
+    }
+
+    #[test]
+    fn quarantining_test_in_strict_mode() {
+        let test_fn: fn() -> Result<(), &'static str> = || Err("oops");
+        let output = Quarantine::new("flaky").with_sink(OutputSink::Silent).run(
+            test_fn,
+            true,
+            Ok(()),
+            Result::is_ok,
+        );
+        assert_eq!(output, Err("oops"));
+
+        let test_fn: fn() = || panic!("oops");
+        panic::catch_unwind(|| {
+            Quarantine::new("flaky").run(test_fn, true, (), |()| true);
+        })
+        .unwrap_err();
+    }
+}
@@ -0,0 +1,199 @@
+//! Allocation leak checks based on an instrumented global allocator.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use super::{DecorateTest, TestFn};
+
+/// Set once the first allocation goes through a [`TrackingAllocator`].
+static IS_INSTALLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    // Must not allocate or register a destructor, since it's accessed from the allocator.
+    static LIVE_ALLOCATIONS: Cell<isize> = const { Cell::new(0) };
+}
+
+fn adjust_live_allocations(diff: isize) {
+    // `try_with()` guards against accessing the counter after the thread-local storage
+    // is torn down.
+    LIVE_ALLOCATIONS
+        .try_with(|cell| cell.set(cell.get() + diff))
+        .ok();
+}
+
+fn live_allocations() -> isize {
+    LIVE_ALLOCATIONS.with(Cell::get)
+}
+
+/// Global allocator wrapper counting live allocations made by each thread. Required by
+/// the [`LeakCheck`] decorator; must be installed as the global allocator in the test binary.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::decorators::TrackingAllocator;
+///
+/// #[global_allocator]
+/// static ALLOCATOR: TrackingAllocator = TrackingAllocator::system();
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "leak-check")))]
+#[derive(Debug, Default)]
+pub struct TrackingAllocator<A = System> {
+    inner: A,
+}
+
+impl TrackingAllocator {
+    /// Wraps the [`System`] allocator.
+    pub const fn system() -> Self {
+        Self::new(System)
+    }
+}
+
+impl<A: GlobalAlloc> TrackingAllocator<A> {
+    /// Wraps the specified allocator.
+    pub const fn new(inner: A) -> Self {
+        Self { inner }
+    }
+
+    fn track(ptr: *mut u8) -> *mut u8 {
+        if !ptr.is_null() {
+            IS_INSTALLED.store(true, Ordering::Relaxed);
+            adjust_live_allocations(1);
+        }
+        ptr
+    }
+}
+
+// SAFETY: All methods delegate to the wrapped allocator.
+unsafe impl<A: GlobalAlloc> GlobalAlloc for TrackingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        Self::track(self.inner.alloc(layout))
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        Self::track(self.inner.alloc_zeroed(layout))
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.inner.dealloc(ptr, layout);
+        adjust_live_allocations(-1);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // Reallocation doesn't change the number of live allocations.
+        self.inner.realloc(ptr, layout, new_size)
+    }
+}
+
+/// [Test decorator](DecorateTest) that fails a wrapped test if it leaves allocations
+/// outstanding, i.e. if the test thread makes more allocations than it frees.
+/// Requires the [`TrackingAllocator`] to be installed as the global allocator.
+///
+/// Allocations are tracked per thread, so the check is not affected by tests running
+/// in parallel. This has some consequences:
+///
+/// - `LeakCheck` must precede decorators running the test on another thread
+///   (e.g., a [`Timeout`](super::Timeout)) in the decorator list.
+/// - Allocations made or freed by other threads (e.g., ones spawned by the test) are not tracked.
+///   Thus, data allocated by the test and freed by another thread is reported as leaked.
+/// - Data lazily initialized by the test and stored in a `static` is reported as leaked.
+///   Likewise, output captured by the test harness may be reported as leaked; to avoid this,
+///   don't print from the test or run tests with `--nocapture`.
+///
+/// The check is only performed if the test succeeds; panics and errors returned by the test
+/// are propagated as is.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{LeakCheck, TrackingAllocator}};
+///
+/// #[global_allocator]
+/// static ALLOCATOR: TrackingAllocator = TrackingAllocator::system();
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(LeakCheck)]
+/// fn test_without_leaks() {
+///     let data = vec![1, 2, 3];
+///     assert_eq!(data.len(), 3);
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "leak-check")))]
+#[derive(Debug, Clone, Copy)]
+pub struct LeakCheck;
+
+impl LeakCheck {
+    fn run<R, F: TestFn<R>>(test_fn: F, is_ok: fn(&R) -> bool) -> R {
+        assert!(
+            IS_INSTALLED.load(Ordering::Relaxed),
+            "`LeakCheck` requires `TrackingAllocator` to be installed as the global allocator"
+        );
+        let allocations_before = live_allocations();
+        let output = test_fn();
+        if is_ok(&output) {
+            let leaked = live_allocations() - allocations_before;
+            assert!(leaked <= 0, "Test leaked {leaked} allocation(s)");
+        }
+        output
+    }
+}
+
+impl DecorateTest<()> for LeakCheck {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        Self::run(test_fn, |()| true);
+    }
+}
+
+impl<E> DecorateTest<Result<(), E>> for LeakCheck {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        Self::run(test_fn, Result::is_ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{mem, panic};
+
+    use super::*;
+    use crate::decorators::extract_panic_str;
+
+    #[global_allocator]
+    static ALLOCATOR: TrackingAllocator = TrackingAllocator::system();
+
+    #[test]
+    fn checking_test_without_leaks() {
+        let test_fn: fn() = || {
+            let mut data = vec![1_u64, 2, 3];
+            data.extend(4..100);
+            let boxed = Box::new(data);
+            assert_eq!(boxed.len(), 99);
+        };
+        LeakCheck.decorate_and_test(test_fn);
+    }
+
+    #[test]
+    fn checking_test_with_leaks() {
+        let test_fn: fn() = || {
+            mem::forget(vec![1_u64, 2, 3]);
+            Box::leak(Box::new(42_u64));
+        };
+        let panic_object =
+            panic::catch_unwind(|| LeakCheck.decorate_and_test(test_fn)).unwrap_err();
+        let panic_str = extract_panic_str(&*panic_object).unwrap();
+        assert_eq!(panic_str, "Test leaked 2 allocation(s)");
+    }
+
+    #[test]
+    fn skipping_check_on_error() {
+        let test_fn: fn() -> Result<(), String> = || Err("oops".to_owned());
+        let err = LeakCheck.decorate_and_test(test_fn).unwrap_err();
+        assert_eq!(err, "oops");
+    }
+}
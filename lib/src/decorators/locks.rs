@@ -0,0 +1,224 @@
+//! Decorators limiting concurrency of tests.
+
+use std::{
+    collections::HashMap,
+    sync::{Condvar, Mutex, OnceLock, PoisonError},
+};
+
+use super::{DecorateTest, TestFn};
+
+/// Returns a lock with the specified key from the global registry, creating it if necessary.
+fn named_lock(key: &'static str) -> &'static Mutex<()> {
+    static LOCKS: OnceLock<Mutex<HashMap<&'static str, &'static Mutex<()>>>> = OnceLock::new();
+
+    let mut locks = LOCKS
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    // Locks are never removed from the registry, so leaking them is fine.
+    locks
+        .entry(key)
+        .or_insert_with(|| Box::leak(Box::default()))
+}
+
+/// [Test decorator](DecorateTest) that serializes all tests decorated with a lock
+/// having the same key. Unlike a [`Sequence`](super::Sequence), locks are looked up
+/// by key in a global registry, so tests using the same lock don't need to share a `static`
+/// and can be defined in different modules.
+///
+/// A failed test doesn't affect other tests using the same lock. To lock several resources,
+/// add multiple `ResourceLock`s to the decorator list; to prevent deadlocks, use the same order
+/// of locks for all tests.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{ResourceLock, Timeout}};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(Timeout::secs(5), ResourceLock::new("db"))]
+/// fn db_test() {
+///     // test logic
+/// }
+///
+/// // Can be defined in another module.
+/// #[test]
+/// # fn eat_test_attribute2() {}
+/// #[decorate(ResourceLock::new("db"))]
+/// fn other_db_test() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ResourceLock {
+    key: &'static str,
+}
+
+impl ResourceLock {
+    /// Creates a lock with the specified key.
+    pub const fn new(key: &'static str) -> Self {
+        Self { key }
+    }
+}
+
+impl<R> DecorateTest<R> for ResourceLock {
+    fn decorate_and_test<F: TestFn<R>>(&'static self, test_fn: F) -> R {
+        // A test panicking while holding the lock doesn't invalidate the locked resource.
+        let _guard = named_lock(self.key)
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        test_fn()
+    }
+}
+
+/// [Test decorator](DecorateTest) that limits the number of concurrently running tests
+/// decorated with the same instance. This is a middle ground between fully parallel tests
+/// and a fully sequential [`Sequence`](super::Sequence), useful e.g. for tests that each
+/// spin up heavy resources.
+///
+/// The decorator is backed by a counting semaphore; a test waits until the number of running
+/// tests drops below the limit. The decorator should be placed into a `static` and referenced
+/// in the [`decorate`](crate::decorate) attributes.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{MaxConcurrency, Timeout}, test_casing};
+///
+/// static LIMIT: MaxConcurrency = MaxConcurrency::new(2);
+///
+/// #[test_casing(10, 0..10)]
+/// #[decorate(Timeout::secs(30), &LIMIT)]
+/// fn heavy_test(index: usize) {
+///     // test logic
+/// }
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(&LIMIT)]
+/// fn other_heavy_test() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug)]
+pub struct MaxConcurrency {
+    limit: usize,
+    running: Mutex<usize>,
+    condvar: Condvar,
+}
+
+impl MaxConcurrency {
+    /// Creates a decorator with the specified limit on the number of concurrently running tests.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is zero.
+    pub const fn new(limit: usize) -> Self {
+        assert!(limit > 0, "concurrency limit must be positive");
+        Self {
+            limit,
+            running: Mutex::new(0),
+            condvar: Condvar::new(),
+        }
+    }
+
+    fn acquire(&self) -> ConcurrencyPermit<'_> {
+        let running = self.running.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut running = self
+            .condvar
+            .wait_while(running, |running| *running >= self.limit)
+            .unwrap_or_else(PoisonError::into_inner);
+        *running += 1;
+        ConcurrencyPermit { semaphore: self }
+    }
+}
+
+/// Releases a permit acquired from [`MaxConcurrency`] on drop, including if the test panics.
+#[derive(Debug)]
+struct ConcurrencyPermit<'a> {
+    semaphore: &'a MaxConcurrency,
+}
+
+impl Drop for ConcurrencyPermit<'_> {
+    fn drop(&mut self) {
+        let mut running = self
+            .semaphore
+            .running
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        *running -= 1;
+        self.semaphore.condvar.notify_one();
+    }
+}
+
+impl<R> DecorateTest<R> for MaxConcurrency {
+    fn decorate_and_test<F: TestFn<R>>(&'static self, test_fn: F) -> R {
+        let _permit = self.acquire();
+        test_fn()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+        thread,
+        time::Duration,
+    };
+
+    use super::*;
+
+    #[test]
+    fn resource_lock_serializes_tests() {
+        static LOCK: ResourceLock = ResourceLock::new("resource_lock_serializes_tests");
+        static OTHER_LOCK: ResourceLock = ResourceLock::new("resource_lock_serializes_tests");
+        static IS_RUNNING: AtomicBool = AtomicBool::new(false);
+
+        let test_fn: fn() = || {
+            assert!(!IS_RUNNING.swap(true, Ordering::SeqCst));
+            thread::sleep(Duration::from_millis(10));
+            IS_RUNNING.store(false, Ordering::SeqCst);
+        };
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let lock = if i % 2 == 0 { &LOCK } else { &OTHER_LOCK };
+                thread::spawn(move || lock.decorate_and_test(test_fn))
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn resource_lock_after_panic() {
+        static LOCK: ResourceLock = ResourceLock::new("resource_lock_after_panic");
+
+        let test_fn: fn() = || panic!("oops");
+        std::panic::catch_unwind(|| LOCK.decorate_and_test(test_fn)).unwrap_err();
+        LOCK.decorate_and_test(|| {});
+    }
+
+    #[test]
+    fn limiting_concurrency() {
+        static LIMIT: MaxConcurrency = MaxConcurrency::new(2);
+        static RUNNING: AtomicUsize = AtomicUsize::new(0);
+        static MAX_RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+        let test_fn: fn() = || {
+            let running = RUNNING.fetch_add(1, Ordering::SeqCst) + 1;
+            MAX_RUNNING.fetch_max(running, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(20));
+            RUNNING.fetch_sub(1, Ordering::SeqCst);
+        };
+        let handles: Vec<_> = (0..6)
+            .map(|_| thread::spawn(move || LIMIT.decorate_and_test(test_fn)))
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert!(MAX_RUNNING.load(Ordering::SeqCst) <= 2);
+        assert_eq!(*LIMIT.running.lock().unwrap(), 0);
+    }
+}
@@ -0,0 +1,216 @@
+//! Per-test log filtering for the `tracing` crate.
+
+use std::{cell::RefCell, sync::Once};
+
+use tracing_core::{span, subscriber::Interest, LevelFilter, Metadata, Subscriber};
+use tracing_subscriber::{
+    filter::{EnvFilter, Targets},
+    layer::{Context, Filter, Layer, SubscriberExt},
+    registry::LookupSpan,
+    util::SubscriberInitExt,
+};
+
+use super::{DecorateTest, TestFn};
+
+thread_local! {
+    static TARGETS_OVERRIDE: RefCell<Option<Targets>> = const { RefCell::new(None) };
+}
+
+/// Filter using the log directives from [`LogLevel`] if the current test is decorated with it,
+/// and an [`EnvFilter`] otherwise.
+#[derive(Debug)]
+struct OverridableFilter {
+    default: EnvFilter,
+}
+
+impl<S> Filter<S> for OverridableFilter
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn enabled(&self, meta: &Metadata<'_>, cx: &Context<'_, S>) -> bool {
+        TARGETS_OVERRIDE.with(|targets| match &*targets.borrow() {
+            Some(targets) => targets.would_enable(meta.target(), meta.level()),
+            None => Filter::enabled(&self.default, meta, cx),
+        })
+    }
+
+    fn callsite_enabled(&self, _meta: &'static Metadata<'static>) -> Interest {
+        // The filter is dynamic, so we cannot cache interest for callsites.
+        Interest::sometimes()
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        Some(LevelFilter::TRACE)
+    }
+
+    // Span-related hooks are forwarded to the `EnvFilter` so that it tracks span directives.
+
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        Filter::on_new_span(&self.default, attrs, id, ctx);
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        Filter::on_record(&self.default, id, values, ctx);
+    }
+
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        Filter::on_enter(&self.default, id, ctx);
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+        Filter::on_exit(&self.default, id, ctx);
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        Filter::on_close(&self.default, id, ctx);
+    }
+}
+
+/// Restores the previous log directives on drop.
+#[derive(Debug)]
+struct OverrideGuard {
+    prev_targets: Option<Targets>,
+}
+
+impl OverrideGuard {
+    fn new(targets: Targets) -> Self {
+        Self {
+            prev_targets: TARGETS_OVERRIDE.with(|cell| cell.borrow_mut().replace(targets)),
+        }
+    }
+}
+
+impl Drop for OverrideGuard {
+    fn drop(&mut self) {
+        TARGETS_OVERRIDE.with(|cell| *cell.borrow_mut() = self.prev_targets.take());
+    }
+}
+
+/// [Test decorator](DecorateTest) that overrides log directives for [`tracing`] events
+/// emitted by the wrapped test.
+///
+/// Directives have the same format as the `RUST_LOG` env variable restricted to targets
+/// and levels, e.g. `my_crate=trace,warn`; span- and field-based directives are not supported.
+/// Events emitted in tests not decorated with `LogLevel` are filtered using `RUST_LOG`
+/// (i.e., using [`EnvFilter`]).
+///
+/// On the first use, the decorator installs a global `tracing` subscriber that outputs events
+/// in a format captured by the test harness. If a global subscriber is already installed,
+/// the decorator will have no effect unless the subscriber uses [`Self::filter()`].
+///
+/// Directives are tied to the thread executing the decorator. Hence, if the test
+/// is decorated with a [`Timeout`](super::Timeout), `LogLevel` should be placed *before*
+/// the timeout in the decorator list. Likewise, events emitted on threads spawned by the test
+/// are filtered using `RUST_LOG`.
+///
+/// [`tracing`]: https://docs.rs/tracing/
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{LogLevel, Timeout}};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(LogLevel("my_crate=trace,warn"), Timeout::secs(5))]
+/// fn test_with_verbose_logs() {
+///     // test logic
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+#[derive(Debug, Clone, Copy)]
+pub struct LogLevel(pub &'static str);
+
+impl LogLevel {
+    /// Returns a per-layer filter respecting directives set by `LogLevel`. This filter can be used
+    /// in a custom subscriber if the default one installed by the decorator doesn't fit.
+    pub fn filter<S>() -> impl Filter<S>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        OverridableFilter {
+            default: EnvFilter::from_default_env(),
+        }
+    }
+
+    pub(super) fn install_subscriber() {
+        static INSTALL: Once = Once::new();
+
+        INSTALL.call_once(|| {
+            let layer = tracing_subscriber::fmt::layer()
+                .with_test_writer()
+                .with_filter(Self::filter());
+            let result = tracing_subscriber::registry().with(layer).try_init();
+            if result.is_err() {
+                eprintln!(
+                    "A global `tracing` subscriber is already installed; `LogLevel` decorators \
+                     will have no effect unless the subscriber uses `LogLevel::filter()`"
+                );
+            }
+        });
+    }
+
+    fn targets(self) -> Targets {
+        self.0.parse().unwrap_or_else(|err| {
+            panic!("Invalid log directives `{}`: {err}", self.0);
+        })
+    }
+}
+
+impl<R> DecorateTest<R> for LogLevel {
+    fn decorate_and_test<F: TestFn<R>>(&self, test_fn: F) -> R {
+        Self::install_subscriber();
+        let _guard = OverrideGuard::new(self.targets());
+        test_fn()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use tracing_core::Event;
+
+    use super::*;
+
+    #[derive(Debug, Clone, Default)]
+    struct EventCounter(Arc<AtomicUsize>);
+
+    impl<S: Subscriber> Layer<S> for EventCounter {
+        fn on_event(&self, _event: &Event<'_>, _ctx: Context<'_, S>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn overriding_log_directives() {
+        static DECORATOR: LogLevel = LogLevel("test_casing=debug,warn");
+
+        let counter = EventCounter::default();
+        let subscriber =
+            tracing_subscriber::registry().with(counter.clone().with_filter(LogLevel::filter()));
+        tracing::subscriber::with_default(subscriber, || {
+            let test_fn: fn() = || {
+                tracing::debug!("enabled");
+                tracing::trace!("disabled");
+                tracing::info!(target: "other", "disabled");
+                tracing::warn!(target: "other", "enabled");
+            };
+            DECORATOR.decorate_and_test(test_fn);
+        });
+        assert_eq!(counter.0.load(Ordering::SeqCst), 2);
+        TARGETS_OVERRIDE.with(|cell| assert!(cell.borrow().is_none()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid log directives")]
+    fn invalid_log_directives() {
+        static DECORATOR: LogLevel = LogLevel("test_casing=what");
+
+        let test_fn: fn() = || {};
+        DECORATOR.decorate_and_test(test_fn);
+    }
+}
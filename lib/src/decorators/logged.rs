@@ -0,0 +1,194 @@
+//! Logging wrapper for decorators.
+
+use std::{any, panic, thread, time::Instant};
+
+use super::{
+    async_test::CatchUnwind, extract_panic_str, AsyncTestFn, DecorateTest, DecorateTestAsync,
+    LogLevel, TestFn, TestFuture,
+};
+
+/// [Test decorator](DecorateTest) that wraps another decorator and logs its entry, exit,
+/// duration and outcome as [`tracing`] events. This is useful to debug which decorator
+/// in a stack swallowed, retried or timed out a test.
+///
+/// Events are emitted on the `INFO` level with the `test_casing::decorators::logged` target.
+/// Like [`LogLevel`], the decorator installs a global `tracing` subscriber on the first use,
+/// so the events can be enabled using the `RUST_LOG` env variable
+/// (e.g., `RUST_LOG=test_casing=info`), or by placing the `Logged` decorator before
+/// a [`LogLevel`] in the decorator list.
+///
+/// [`tracing`]: https://docs.rs/tracing/
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{LogLevel, Logged, Retry, Timeout}};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(
+///     Logged(Timeout::secs(5)),
+///     Logged(Retry::times(2)),
+///     LogLevel("test_casing=info"),
+/// )]
+/// fn test_with_logged_decorators() {
+///     // test logic
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+#[derive(Debug, Clone, Copy)]
+pub struct Logged<D>(pub D);
+
+impl<D> Logged<D> {
+    fn decorate_inner<R, F>(&'static self, test_fn: F, is_ok: fn(&R) -> bool) -> R
+    where
+        D: DecorateTest<R>,
+        F: TestFn<R>,
+    {
+        LogLevel::install_subscriber();
+        let decorator = any::type_name::<D>();
+        tracing::info!(decorator, "entering decorator");
+        let started_at = Instant::now();
+        let output = panic::catch_unwind(move || self.0.decorate_and_test(test_fn));
+        Self::log_exit(output, started_at, is_ok)
+    }
+
+    fn decorate_inner_async<R, F>(&'static self, test_fn: F, is_ok: fn(&R) -> bool) -> TestFuture<R>
+    where
+        R: 'static,
+        D: DecorateTestAsync<R>,
+        F: AsyncTestFn<R>,
+    {
+        Box::pin(async move {
+            LogLevel::install_subscriber();
+            let decorator = any::type_name::<D>();
+            tracing::info!(decorator, "entering decorator");
+            let started_at = Instant::now();
+            let output = CatchUnwind(self.0.decorate_and_test_async(test_fn)).await;
+            Self::log_exit(output, started_at, is_ok)
+        })
+    }
+
+    fn log_exit<R>(output: thread::Result<R>, started_at: Instant, is_ok: fn(&R) -> bool) -> R {
+        let decorator = any::type_name::<D>();
+        let elapsed = started_at.elapsed();
+        match output {
+            Ok(output) => {
+                let outcome = if is_ok(&output) { "passed" } else { "failed" };
+                tracing::info!(decorator, ?elapsed, outcome, "exiting decorator");
+                output
+            }
+            Err(panic_object) => {
+                let message = extract_panic_str(&*panic_object).unwrap_or("");
+                tracing::info!(
+                    decorator,
+                    ?elapsed,
+                    outcome = "panicked",
+                    message,
+                    "exiting decorator"
+                );
+                panic::resume_unwind(panic_object)
+            }
+        }
+    }
+}
+
+impl<D: DecorateTest<()>> DecorateTest<()> for Logged<D> {
+    fn decorate_and_test<F: TestFn<()>>(&'static self, test_fn: F) {
+        self.decorate_inner(test_fn, |()| true);
+    }
+
+    fn is_process_boundary(&self) -> bool {
+        self.0.is_process_boundary()
+    }
+}
+
+impl<E, D: DecorateTest<Result<(), E>>> DecorateTest<Result<(), E>> for Logged<D> {
+    fn decorate_and_test<F>(&'static self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.decorate_inner(test_fn, Result::is_ok)
+    }
+
+    fn is_process_boundary(&self) -> bool {
+        self.0.is_process_boundary()
+    }
+}
+
+impl<D: DecorateTestAsync<()>> DecorateTestAsync<()> for Logged<D> {
+    fn decorate_and_test_async<F: AsyncTestFn<()>>(&'static self, test_fn: F) -> TestFuture<()> {
+        self.decorate_inner_async(test_fn, |()| true)
+    }
+}
+
+impl<E, D> DecorateTestAsync<Result<(), E>> for Logged<D>
+where
+    E: 'static,
+    D: DecorateTestAsync<Result<(), E>>,
+{
+    fn decorate_and_test_async<F>(&'static self, test_fn: F) -> TestFuture<Result<(), E>>
+    where
+        F: AsyncTestFn<Result<(), E>>,
+    {
+        self.decorate_inner_async(test_fn, Result::is_ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex, PoisonError};
+
+    use tracing_core::{
+        field::{Field, Visit},
+        Event, Subscriber,
+    };
+    use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+    use super::*;
+    use crate::decorators::Retry;
+
+    /// Records `outcome` fields of exit events.
+    #[derive(Debug, Clone, Default)]
+    struct OutcomeRecorder(Arc<Mutex<Vec<String>>>);
+
+    impl Visit for OutcomeRecorder {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if field.name() == "outcome" {
+                let mut outcomes = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+                outcomes.push(value.to_owned());
+            }
+        }
+
+        fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {
+            // Do nothing
+        }
+    }
+
+    impl<S: Subscriber> Layer<S> for OutcomeRecorder {
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            event.record(&mut self.clone());
+        }
+    }
+
+    #[test]
+    fn logging_decorator_outcomes() {
+        static DECORATORS: (Logged<Retry>, Logged<Retry>) =
+            (Logged(Retry::times(0)), Logged(Retry::times(1)));
+
+        let recorder = OutcomeRecorder::default();
+        let subscriber = tracing_subscriber::registry().with(recorder.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            let test_fn: fn() -> Result<(), &'static str> = || Err("oops");
+            DECORATORS.decorate_and_test(test_fn).unwrap_err();
+            let test_fn: fn() = || panic!("oops");
+            panic::catch_unwind(|| DECORATORS.decorate_and_test(test_fn)).unwrap_err();
+        });
+
+        let outcomes = recorder.0.lock().unwrap().clone();
+        assert_eq!(
+            outcomes,
+            ["failed", "failed", "failed", "panicked", "panicked", "panicked"]
+        );
+    }
+}
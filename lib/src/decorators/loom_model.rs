@@ -0,0 +1,192 @@
+//! Model checking provided by the `loom` crate.
+
+use std::{fmt, time::Duration};
+
+use super::{DecorateTest, TestFn};
+
+/// [Test decorator](DecorateTest) that runs the test body under the [`loom`] model checker,
+/// i.e. executes it repeatedly, exploring possible interleavings of concurrent operations.
+///
+/// The test body must use synchronization primitives and threads from `loom` (e.g.,
+/// `loom::sync::Arc` and `loom::thread::spawn()`) instead of the standard ones;
+/// see the `loom` docs for details. Since the body is executed many times, it should
+/// be deterministic and must not depend on state preserved between executions.
+///
+/// Exploration params not specified via the decorator methods are taken from the `LOOM_*`
+/// env variables, like with [`loom::model()`]. If a test returns an error, it's converted
+/// to a panic using the error's [`Debug`](fmt::Debug) implementation, so that `loom` can report
+/// the failing interleaving.
+///
+/// [`loom`]: https://docs.rs/loom/
+/// [`loom::model()`]: https://docs.rs/loom/latest/loom/fn.model.html
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::LoomModel, test_casing};
+/// use loom::{sync::{atomic::{AtomicUsize, Ordering}, Arc}, thread};
+///
+/// #[test_casing(3, 1..=3)]
+/// #[decorate(LoomModel::new().with_preemption_bound(2))]
+/// fn concurrent_increments(thread_count: usize) {
+///     let counter = Arc::new(AtomicUsize::new(0));
+///     let handles: Vec<_> = (0..thread_count)
+///         .map(|_| {
+///             let counter = counter.clone();
+///             thread::spawn(move || counter.fetch_add(1, Ordering::SeqCst))
+///         })
+///         .collect();
+///     for handle in handles {
+///         handle.join().unwrap();
+///     }
+///     assert_eq!(counter.load(Ordering::SeqCst), thread_count);
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "loom")))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoomModel {
+    preemption_bound: Option<usize>,
+    max_branches: Option<usize>,
+    max_permutations: Option<usize>,
+    max_duration: Option<Duration>,
+}
+
+impl LoomModel {
+    /// Creates a decorator with exploration params taken from the `LOOM_*` env variables.
+    pub const fn new() -> Self {
+        Self {
+            preemption_bound: None,
+            max_branches: None,
+            max_permutations: None,
+            max_duration: None,
+        }
+    }
+
+    /// Specifies the maximum number of thread preemptions to explore in each execution.
+    /// Bounding preemptions drastically reduces the checking time, while still catching
+    /// most concurrency bugs with small bounds (2–3).
+    #[must_use]
+    pub const fn with_preemption_bound(mut self, bound: usize) -> Self {
+        self.preemption_bound = Some(bound);
+        self
+    }
+
+    /// Specifies the maximum number of thread switches in each execution.
+    #[must_use]
+    pub const fn with_max_branches(mut self, max_branches: usize) -> Self {
+        self.max_branches = Some(max_branches);
+        self
+    }
+
+    /// Specifies the maximum number of executions to explore.
+    #[must_use]
+    pub const fn with_max_permutations(mut self, max_permutations: usize) -> Self {
+        self.max_permutations = Some(max_permutations);
+        self
+    }
+
+    /// Specifies the maximum duration of the model checking.
+    #[must_use]
+    pub const fn with_max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    fn builder(self) -> loom::model::Builder {
+        let mut builder = loom::model::Builder::new();
+        if let Some(bound) = self.preemption_bound {
+            builder.preemption_bound = Some(bound);
+        }
+        if let Some(max_branches) = self.max_branches {
+            builder.max_branches = max_branches;
+        }
+        if let Some(max_permutations) = self.max_permutations {
+            builder.max_permutations = Some(max_permutations);
+        }
+        if let Some(max_duration) = self.max_duration {
+            builder.max_duration = Some(max_duration);
+        }
+        builder
+    }
+}
+
+impl DecorateTest<()> for LoomModel {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        self.builder().check(test_fn);
+    }
+}
+
+impl<E: fmt::Debug> DecorateTest<Result<(), E>> for LoomModel {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.builder().check(move || {
+            if let Err(err) = test_fn() {
+                panic!("Test returned an error: {err:?}");
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic;
+
+    use loom::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+    };
+
+    use super::*;
+
+    fn increment_concurrently(is_atomic: bool) -> Result<(), String> {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let counter = counter.clone();
+                thread::spawn(move || {
+                    if is_atomic {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                    } else {
+                        let value = counter.load(Ordering::SeqCst);
+                        counter.store(value + 1, Ordering::SeqCst);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let value = counter.load(Ordering::SeqCst);
+        if value == 2 {
+            Ok(())
+        } else {
+            Err(format!("lost update: {value}"))
+        }
+    }
+
+    #[test]
+    fn checking_correct_model() {
+        static DECORATOR: LoomModel = LoomModel::new().with_preemption_bound(2);
+
+        let test_fn: fn() -> Result<(), String> = || increment_concurrently(true);
+        DECORATOR.decorate_and_test(test_fn).unwrap();
+    }
+
+    #[test]
+    fn checking_racy_model() {
+        static DECORATOR: LoomModel = LoomModel::new().with_preemption_bound(2);
+
+        let test_fn: fn() -> Result<(), String> = || increment_concurrently(false);
+        let panic_object =
+            panic::catch_unwind(|| DECORATOR.decorate_and_test(test_fn)).unwrap_err();
+        let message = panic_object.downcast_ref::<String>().unwrap();
+        assert!(message.contains("lost update: 1"), "{message}");
+    }
+}
@@ -0,0 +1,124 @@
+//! Paused `tokio` clock for async tests.
+
+use std::time::Duration;
+
+use super::{AsyncTestFn, DecorateTestAsync, TestFuture};
+
+/// [Async test decorator](DecorateTestAsync) that pauses the `tokio` clock for the wrapped test.
+/// Timer-heavy tests (e.g., ones testing timeouts or retries with backoff) then run instantly
+/// and deterministically: while the clock is paused, `tokio` auto-advances it to the next pending
+/// timer once the runtime has no other work to do.
+///
+/// Auto-advancing is inhibited while the runtime has pending blocking tasks (e.g., spawned
+/// with `tokio::task::spawn_blocking`). For such tests, the decorator can be configured
+/// to [advance the clock](Self::with_tick()) by a fixed step regardless of other work.
+///
+/// The test must run on a current-thread `tokio` runtime (the default for `#[tokio::test]`),
+/// and the clock must not be already paused (e.g., via `#[tokio::test(start_paused = true)]`);
+/// otherwise, the decorator panics. The clock is resumed after the test completes.
+///
+/// The decorator is only available with the `tokio` crate feature. It only applies to async tests
+/// decorated natively, i.e., if the [`decorate`](crate::decorate) macro is placed *before*
+/// the async test attribute.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::MockClock, test_casing};
+/// use std::time::Duration;
+///
+/// #[test_casing(3, [1, 60, 3_600])]
+/// #[decorate(MockClock::new())]
+/// #[tokio::test]
+/// async fn test_with_paused_clock(secs: u64) {
+///     let start = tokio::time::Instant::now();
+///     tokio::time::sleep(Duration::from_secs(secs)).await;
+///     assert_eq!(start.elapsed().as_secs(), secs);
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MockClock {
+    tick: Option<Duration>,
+}
+
+impl MockClock {
+    /// Creates a decorator pausing the clock.
+    pub const fn new() -> Self {
+        Self { tick: None }
+    }
+
+    /// Advances the paused clock by the specified step each time a background ticker task
+    /// is polled by the runtime, regardless of whether the test has other work to do.
+    #[must_use]
+    pub const fn with_tick(self, tick: Duration) -> Self {
+        Self { tick: Some(tick) }
+    }
+}
+
+impl<R: 'static> DecorateTestAsync<R> for MockClock {
+    fn decorate_and_test_async<F: AsyncTestFn<R>>(&'static self, test_fn: F) -> TestFuture<R> {
+        Box::pin(async move {
+            tokio::time::pause();
+            let ticker = self.tick.map(|tick| {
+                tokio::spawn(async move {
+                    loop {
+                        // `advance()` yields to the runtime, so the ticker doesn't starve
+                        // other tasks.
+                        tokio::time::advance(tick).await;
+                    }
+                })
+            });
+
+            let output = test_fn().await;
+            if let Some(ticker) = ticker {
+                ticker.abort();
+            }
+            tokio::time::resume();
+            output
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn pausing_clock() {
+        static DECORATOR: MockClock = MockClock::new();
+
+        let test_fn: fn() -> TestFuture<()> = || {
+            Box::pin(async {
+                let start = tokio::time::Instant::now();
+                let real_start = std::time::Instant::now();
+                tokio::time::sleep(Duration::from_secs(3_600)).await;
+                assert_eq!(start.elapsed().as_secs(), 3_600);
+                assert!(real_start.elapsed() < Duration::from_secs(60));
+            })
+        };
+        DECORATOR.decorate_and_test_async(test_fn).await;
+    }
+
+    #[tokio::test]
+    async fn advancing_clock_with_blocking_task() {
+        static DECORATOR: MockClock = MockClock::new().with_tick(Duration::from_secs(1));
+        static IS_BLOCKING: AtomicBool = AtomicBool::new(true);
+
+        let test_fn: fn() -> TestFuture<()> = || {
+            Box::pin(async {
+                let blocking_task = tokio::task::spawn_blocking(|| {
+                    while IS_BLOCKING.load(Ordering::SeqCst) {
+                        std::thread::sleep(Duration::from_millis(1));
+                    }
+                });
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                IS_BLOCKING.store(false, Ordering::SeqCst);
+                blocking_task.await.unwrap();
+            })
+        };
+        DECORATOR.decorate_and_test_async(test_fn).await;
+    }
+}
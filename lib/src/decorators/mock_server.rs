@@ -0,0 +1,205 @@
+//! Mock HTTP server provided by the `mockito` crate.
+
+use std::cell::RefCell;
+
+use mockito::{Mock, Server, ServerGuard};
+
+use super::{DecorateTest, TestFn};
+
+thread_local! {
+    static CURRENT_SERVER: RefCell<Option<ServerGuard>> = const { RefCell::new(None) };
+}
+
+/// Sets the current server for the thread and restores the previous one on drop
+/// (including when the test panics).
+struct ServerScope {
+    prev_server: Option<ServerGuard>,
+}
+
+impl ServerScope {
+    fn new(server: ServerGuard) -> Self {
+        Self {
+            prev_server: CURRENT_SERVER.with(|cell| cell.borrow_mut().replace(server)),
+        }
+    }
+}
+
+impl Drop for ServerScope {
+    fn drop(&mut self) {
+        let server = CURRENT_SERVER.with(|cell| {
+            let mut cell = cell.borrow_mut();
+            let server = cell.take();
+            *cell = self.prev_server.take();
+            server
+        });
+        // Return the server to the pool outside of the borrow.
+        drop(server);
+    }
+}
+
+/// [Test decorator](DecorateTest) that starts a mock HTTP server from the [`mockito`] crate
+/// before the test and shuts it down afterwards.
+///
+/// The server is accessible from the test body via [`Self::url()`] and [`Self::with()`].
+/// Mocks can be set up either in the test body, or by the decorator itself
+/// (see [`Self::with_mocks()`]). In the latter case, the decorator asserts that
+/// the mock expectations are met after the test completes successfully.
+///
+/// A new server is started each time the decorator is invoked. Thus, if the decorator
+/// is placed before [`Retry`](super::Retry) in the decorator list, each retry attempt
+/// will get a fresh server without any mocks or recorded requests from previous attempts.
+///
+/// The server is tied to the thread executing the decorator. Hence, if the test
+/// is decorated with a [`Timeout`](super::Timeout), `MockServer` should be placed *before*
+/// the timeout in the decorator list. The server cannot be started from within an async runtime,
+/// which is not an issue for async tests since [`decorate`](crate::decorate) wraps
+/// the runtime.
+///
+/// [`mockito`]: https://docs.rs/mockito/
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{MockServer, Retry, Timeout}};
+///
+/// const SERVER: MockServer = MockServer::new().with_mocks(|server| {
+///     vec![server.mock("GET", "/health").with_status(200).create()]
+/// });
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(SERVER, Timeout::secs(5), Retry::times(2))]
+/// fn test_with_mock_server() {
+///     let url = MockServer::url();
+///     // Perform requests to `{url}/health`. The decorator will check that
+///     // the mocked endpoint was called.
+/// #   let _ = url;
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "mockito")))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MockServer {
+    setup: Option<fn(&mut ServerGuard) -> Vec<Mock>>,
+}
+
+impl MockServer {
+    /// Creates a decorator that starts a server without mocks.
+    pub const fn new() -> Self {
+        Self { setup: None }
+    }
+
+    /// Sets up mocks on the server before each test run. Expectations for the returned mocks
+    /// are asserted after the test completes successfully.
+    #[must_use]
+    pub const fn with_mocks(mut self, setup: fn(&mut ServerGuard) -> Vec<Mock>) -> Self {
+        self.setup = Some(setup);
+        self
+    }
+
+    /// Returns the base URL of the server for the current test (e.g., `http://127.0.0.1:1234`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a test decorated with `MockServer`.
+    pub fn url() -> String {
+        Self::with(|server| server.url())
+    }
+
+    /// Performs an action with the server for the current test, e.g. sets up mocks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a test decorated with `MockServer`.
+    pub fn with<T>(action: impl FnOnce(&mut ServerGuard) -> T) -> T {
+        CURRENT_SERVER.with(|cell| {
+            let mut server = cell.borrow_mut();
+            let server = server
+                .as_mut()
+                .expect("mock server is not running; decorate the test with `MockServer`");
+            action(server)
+        })
+    }
+
+    fn decorate_inner<R, F: TestFn<R>>(self, test_fn: F, is_ok: fn(&R) -> bool) -> R {
+        let mut server = Server::new();
+        let mocks = self.setup.map_or_else(Vec::new, |setup| setup(&mut server));
+        let _scope = ServerScope::new(server);
+
+        let output = test_fn();
+        if is_ok(&output) {
+            for mock in &mocks {
+                mock.assert();
+            }
+        }
+        output
+    }
+}
+
+impl DecorateTest<()> for MockServer {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        self.decorate_inner(test_fn, |()| true);
+    }
+}
+
+impl<E> DecorateTest<Result<(), E>> for MockServer {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.decorate_inner(test_fn, Result::is_ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+        panic,
+    };
+
+    use super::*;
+
+    fn get(path: &str) -> String {
+        let address = MockServer::with(|server| server.socket_address());
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "GET {path} HTTP/1.1\r\nHost: {address}\r\nConnection: close\r\n\r\n"
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn serving_mocks() {
+        static DECORATOR: MockServer = MockServer::new()
+            .with_mocks(|server| vec![server.mock("GET", "/health").with_body("ok").create()]);
+
+        let test_fn: fn() = || {
+            assert!(MockServer::url().starts_with("http://"));
+            let response = get("/health");
+            assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+            assert!(response.ends_with("ok"), "{response}");
+        };
+        DECORATOR.decorate_and_test(test_fn);
+        CURRENT_SERVER.with(|cell| assert!(cell.borrow().is_none()));
+    }
+
+    #[test]
+    fn unmet_expectations() {
+        static DECORATOR: MockServer =
+            MockServer::new().with_mocks(|server| vec![server.mock("GET", "/health").create()]);
+
+        let test_fn: fn() = || {};
+        let result = panic::catch_unwind(|| DECORATOR.decorate_and_test(test_fn));
+        result.unwrap_err();
+        CURRENT_SERVER.with(|cell| assert!(cell.borrow().is_none()));
+
+        // Expectations must not be checked if the test fails.
+        let test_fn: fn() -> Result<(), &'static str> = || Err("oops");
+        assert_eq!(DECORATOR.decorate_and_test(test_fn), Err("oops"));
+    }
+}
@@ -0,0 +1,2324 @@
+//! Test decorator trait and implementations.
+//!
+//! # Overview
+//!
+//! A [test decorator](DecorateTest) takes a [tested function](TestFn) and calls it zero or more times,
+//! perhaps with additional logic spliced between calls. Examples of decorators include [retries](Retry),
+//! [`Timeout`]s and test [`Sequence`]s.
+//!
+//! Decorators are composable: `DecorateTest` is automatically implemented for a tuple with
+//! 2..=8 elements where each element implements `DecorateTest`. The decorators in a tuple
+//! are applied in the order of their appearance in the tuple. An optional decorator
+//! (`Option<D>` where `D` implements `DecorateTest`) is a no-op if it's `None`; this allows
+//! enabling decorators conditionally, e.g. based on `cfg!` checks. Similarly, decorators
+//! of the same type can be composed in an array or a slice, which is not limited
+//! in the number of elements.
+//!
+//! Async tests can be decorated natively, with decorators wrapping the test future rather than
+//! a blocking wrapper around it; see [`DecorateTestAsync`] for details.
+//!
+//! Decorators can isolate tests in a separate process; see [`ForkedProcess`] for details
+//! on how decorators in a tuple are split between the parent and child processes.
+//!
+//! Progress messages emitted by built-in decorators (e.g., about [`Retry`] attempts) are printed
+//! to the standard output by default; this can be changed globally or for a specific decorator
+//! using an [`OutputSink`].
+//!
+//! # Examples
+//!
+//! See [`decorate`](crate::decorate) macro docs for the examples of usage.
+
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    ffi::OsStr,
+    fmt,
+    panic::{self, Location},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Arc, Mutex, OnceLock, PoisonError,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+mod adapters;
+mod async_test;
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+mod async_timeout;
+mod baseline;
+#[cfg(feature = "testcontainers")]
+mod containers;
+mod cpu;
+mod cpu_time;
+mod default_timeout;
+mod env;
+mod expect;
+mod flakes;
+#[cfg(feature = "leak-check")]
+mod leak_check;
+mod locks;
+#[cfg(feature = "tracing")]
+mod log_level;
+#[cfg(feature = "tracing")]
+mod logged;
+#[cfg(feature = "loom")]
+mod loom_model;
+#[cfg(feature = "tokio")]
+mod mock_clock;
+#[cfg(feature = "mockito")]
+mod mock_server;
+#[cfg(feature = "turmoil")]
+mod network_sim;
+mod output;
+mod panics;
+mod process;
+pub mod profiles;
+mod report;
+mod scoped;
+#[cfg(feature = "serial_test")]
+mod serial;
+#[cfg(feature = "shuttle")]
+mod shuttle_check;
+mod sink;
+mod skip;
+#[cfg(feature = "insta")]
+mod snapshots;
+mod temp_dir;
+mod timing;
+
+pub(crate) use self::async_test::CatchUnwind;
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+pub use self::async_timeout::AsyncTimeout;
+#[cfg(feature = "testcontainers")]
+pub use self::containers::{DockerContainer, SharedContainer};
+#[cfg(feature = "leak-check")]
+pub use self::leak_check::{LeakCheck, TrackingAllocator};
+#[cfg(feature = "loom")]
+pub use self::loom_model::LoomModel;
+#[cfg(feature = "tokio")]
+pub use self::mock_clock::MockClock;
+#[cfg(feature = "mockito")]
+pub use self::mock_server::MockServer;
+#[cfg(feature = "turmoil")]
+pub use self::network_sim::NetworkSim;
+#[cfg(feature = "serial_test")]
+pub use self::serial::Serial;
+#[cfg(feature = "shuttle")]
+pub use self::shuttle_check::ShuttleCheck;
+#[cfg(feature = "insta")]
+pub use self::snapshots::Snapshots;
+pub use self::{
+    adapters::FnDecorator,
+    async_test::{AsyncTestFn, DecorateTestAsync, DecorateTestAsyncFn, TestFuture},
+    baseline::PerfBaseline,
+    cpu::RequiresCpuFeatures,
+    cpu_time::CpuTime,
+    default_timeout::{ApplyDefaultTimeout, DefaultTimeoutFn, SkipDefaultTimeout},
+    env::{EnvVars, Locale, Timezone},
+    expect::{ExpectPanic, ShouldError},
+    flakes::{FlakinessProbe, Quarantine, RandomSeed, Repeat, ReproduceFlake, SeedSweep},
+    locks::{MaxConcurrency, ResourceLock},
+    output::{ExpectNoOutput, TruncateOutput},
+    panics::{Backtrace, PanicReport},
+    process::ForkedProcess,
+    report::{ErrorReport, ReportError},
+    scoped::{Scoped, TestOutcome},
+    sink::OutputSink,
+    skip::{FailFast, Platform, SkipIf},
+    temp_dir::TempDir,
+    timing::{RecordTiming, WarnIfSlow},
+};
+#[cfg(feature = "tracing")]
+pub use self::{log_level::LogLevel, logged::Logged};
+
+/// Derives [`DecorateTest`] for a struct with all fields implementing `DecorateTest`.
+///
+/// The fields are applied in the order of their declaration, just like decorators in a tuple;
+/// i.e., the first field wraps the test directly, and the last field wraps all other fields.
+/// Unlike a tuple, a derived decorator has a name and is not limited in the number of fields.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{DecorateTest, Backtrace, Retry, Timeout}};
+///
+/// #[derive(Debug, DecorateTest)]
+/// struct NetworkTest {
+///     backtrace: Backtrace,
+///     timeout: Timeout,
+///     retry: Retry,
+/// }
+///
+/// const NETWORK_TEST: NetworkTest = NetworkTest {
+///     backtrace: Backtrace::new(),
+///     timeout: Timeout::secs(5),
+///     retry: Retry::times(2),
+/// };
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(NETWORK_TEST)]
+/// fn network_test() {
+///     // test logic
+/// }
+/// ```
+pub use test_casing_macro::DecorateTest;
+
+/// Tested function or closure.
+///
+/// This trait is automatically implemented for all functions without arguments.
+pub trait TestFn<R>: Fn() -> R + panic::UnwindSafe + Send + Sync + Copy + 'static {}
+
+impl<R, F> TestFn<R> for F where F: Fn() -> R + panic::UnwindSafe + Send + Sync + Copy + 'static {}
+
+/// Test decorator.
+///
+/// See [module docs](index.html#overview) for the extended description.
+///
+/// # Examples
+///
+/// The following decorator implements a `#[should_panic]` analogue for errors. (A more complete
+/// version of this decorator is available as [`ShouldError`].)
+///
+/// ```
+/// use test_casing::decorators::{DecorateTest, TestFn};
+///
+/// #[derive(Debug, Clone, Copy)]
+/// pub struct ShouldError(pub &'static str);
+///
+/// impl<E: ToString> DecorateTest<Result<(), E>> for ShouldError {
+///     fn decorate_and_test<F: TestFn<Result<(), E>>>(
+///         &self,
+///         test_fn: F,
+///     ) -> Result<(), E> {
+///         let Err(err) = test_fn() else {
+///             panic!("Expected test to error, but it completed successfully");
+///         };
+///         let err = err.to_string();
+///         if err.contains(self.0) {
+///             Ok(())
+///         } else {
+///             panic!(
+///                 "Expected error message to contain `{}`, but it was: {err}",
+///                 self.0
+///             );
+///         }
+///     }
+/// }
+///
+/// // Usage:
+/// # use test_casing::decorate;
+/// # use std::error::Error;
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(ShouldError("oops"))]
+/// fn test_with_an_error() -> Result<(), Box<dyn Error>> {
+///     Err("oops, this test failed".into())
+/// }
+/// ```
+pub trait DecorateTest<R>: panic::RefUnwindSafe + Send + Sync + 'static {
+    /// Decorates the provided test function and runs the test.
+    fn decorate_and_test<F: TestFn<R>>(&'static self, test_fn: F) -> R;
+
+    /// Checks whether this decorator runs the test in a separate process, like [`ForkedProcess`].
+    /// Used to split decorators in a tuple between the parent and the child processes.
+    #[doc(hidden)]
+    fn is_process_boundary(&self) -> bool {
+        false
+    }
+}
+
+impl<R, T: DecorateTest<R> + ?Sized> DecorateTest<R> for &'static T {
+    fn decorate_and_test<F: TestFn<R>>(&'static self, test_fn: F) -> R {
+        (**self).decorate_and_test(test_fn)
+    }
+
+    fn is_process_boundary(&self) -> bool {
+        (**self).is_process_boundary()
+    }
+}
+
+/// Applies the decorator if it's present, and runs the test as-is otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{Retry, Timeout}};
+///
+/// const MAYBE_RETRY: Option<Retry> = if cfg!(windows) {
+///     Some(Retry::times(2))
+/// } else {
+///     None
+/// };
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(Timeout::secs(5), MAYBE_RETRY)]
+/// fn test_flaky_on_windows() {
+///     // test logic
+/// }
+/// ```
+impl<R, D: DecorateTest<R>> DecorateTest<R> for Option<D> {
+    fn decorate_and_test<F: TestFn<R>>(&'static self, test_fn: F) -> R {
+        match self {
+            Some(decorator) => decorator.decorate_and_test(test_fn),
+            None => test_fn(),
+        }
+    }
+
+    fn is_process_boundary(&self) -> bool {
+        self.as_ref()
+            .is_some_and(DecorateTest::<R>::is_process_boundary)
+    }
+}
+
+/// Applies decorators in the order of their appearance in the slice, similarly to tuples.
+impl<R, D: DecorateTest<R>> DecorateTest<R> for [D] {
+    fn decorate_and_test<F: TestFn<R>>(&'static self, test_fn: F) -> R {
+        let mut decorators = self;
+        if process::is_forked_child() {
+            // Decorators following the process boundary have been applied in the parent process.
+            if let Some(pos) = self.iter().position(DecorateTest::<R>::is_process_boundary) {
+                decorators = &self[..=pos];
+            }
+        }
+        decorate_with_slice(decorators, test_fn)
+    }
+
+    fn is_process_boundary(&self) -> bool {
+        self.iter().any(DecorateTest::<R>::is_process_boundary)
+    }
+}
+
+fn decorate_with_slice<R, D, F>(decorators: &'static [D], test_fn: F) -> R
+where
+    D: DecorateTest<R>,
+    F: TestFn<R>,
+{
+    match decorators.split_last() {
+        Some((last, rest)) => last.decorate_and_test(move || decorate_with_slice(rest, test_fn)),
+        None => test_fn(),
+    }
+}
+
+/// Applies decorators in the order of their appearance in the array, similarly to tuples.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::FnDecorator};
+///
+/// static LOGGERS: [FnDecorator<()>; 2] = [
+///     FnDecorator::new(|test_fn| {
+///         println!("Inner decorator");
+///         test_fn();
+///     }),
+///     FnDecorator::new(|test_fn| {
+///         println!("Outer decorator");
+///         test_fn();
+///     }),
+/// ];
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(&LOGGERS)]
+/// fn logged_test() {
+///     // test logic
+/// }
+/// ```
+impl<R, D: DecorateTest<R>, const N: usize> DecorateTest<R> for [D; N] {
+    fn decorate_and_test<F: TestFn<R>>(&'static self, test_fn: F) -> R {
+        self.as_slice().decorate_and_test(test_fn)
+    }
+
+    fn is_process_boundary(&self) -> bool {
+        self.as_slice().is_process_boundary()
+    }
+}
+
+/// Object-safe version of [`DecorateTest`]. This trait is automatically implemented
+/// for all `DecorateTest` implementations and is used by the [`decorate`](crate::decorate) macro.
+/// It can be used together with [`run_decorated()`] in custom test harnesses.
+pub trait DecorateTestFn<R>: panic::RefUnwindSafe + Send + Sync + 'static {
+    /// Decorates the provided test function and runs the test. The caller location
+    /// is considered to be the location of the test and is mentioned in panic messages
+    /// produced by decorators (e.g., by [`Timeout`]).
+    #[track_caller]
+    fn decorate_and_test_fn(&'static self, test_fn: fn() -> R) -> R;
+}
+
+impl<R: 'static, T: DecorateTest<R>> DecorateTestFn<R> for T {
+    fn decorate_and_test_fn(&'static self, test_fn: fn() -> R) -> R {
+        if decorators_disabled() {
+            test_fn()
+        } else {
+            let _location_guard = LocationGuard::new();
+            self.decorate_and_test(test_fn)
+        }
+    }
+}
+
+/// Name of the env variable disabling all decorators.
+const NO_DECORATORS_ENV_VAR: &str = "TEST_CASING_NO_DECORATORS";
+
+/// Checks whether the value of a boolean env variable enables the flag (i.e., is non-empty
+/// and not `0`).
+fn is_truthy_env_value(value: &OsStr) -> bool {
+    !value.is_empty() && value != "0"
+}
+
+/// Checks whether decorators are disabled via the [`NO_DECORATORS_ENV_VAR`] env variable.
+fn decorators_disabled() -> bool {
+    static DISABLED: OnceLock<bool> = OnceLock::new();
+
+    *DISABLED.get_or_init(|| {
+        let disabled = std::env::var_os(NO_DECORATORS_ENV_VAR)
+            .is_some_and(|value| is_truthy_env_value(&value));
+        if disabled {
+            OutputSink::global().emit(format_args!(
+                "Test decorators are disabled via the `{NO_DECORATORS_ENV_VAR}` env variable"
+            ));
+        }
+        disabled
+    })
+}
+
+/// Decorates the provided function or closure with one or more decorators and runs it.
+/// This allows using decorators outside of test functions annotated with
+/// [`decorate`](crate::decorate), e.g. in custom test harnesses, benchmarks or integration
+/// test drivers.
+///
+/// Since [`DecorateTest`] requires decorators to have the `'static` lifetime, the provided
+/// decorators are leaked. Thus, this function should not be called in a hot loop; if this
+/// is a concern, place decorators in a `static` and call [`DecorateTest::decorate_and_test()`]
+/// on it directly.
+///
+/// The [`decorate!`](crate::decorators::decorate!) macro provides a more concise syntax
+/// for this function.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::decorators::{self, Retry, Timeout};
+///
+/// decorators::decorate((Timeout::secs(1), Retry::times(2)), || {
+///     // test logic
+/// });
+/// let output = decorators::decorate(Timeout::secs(1), || 42);
+/// assert_eq!(output, 42);
+/// ```
+#[track_caller]
+pub fn decorate<R, D, F>(decorators: D, test_fn: F) -> R
+where
+    D: DecorateTest<R>,
+    F: TestFn<R>,
+{
+    if decorators_disabled() {
+        return test_fn();
+    }
+    let decorators: &'static D = Box::leak(Box::new(decorators));
+    let _location_guard = LocationGuard::new();
+    decorators.decorate_and_test(test_fn)
+}
+
+/// Runs the provided test function with the specified decorators. This allows reusing decorator
+/// semantics in custom test harnesses (e.g., ones based on [`libtest-mimic`]) without
+/// the [`decorate`](crate::decorate) macro.
+///
+/// [`libtest-mimic`]: https://docs.rs/libtest-mimic/
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::decorators::{run_decorated, DecorateTestFn, Retry, Timeout};
+///
+/// static DECORATORS: &dyn DecorateTestFn<()> = &(Timeout::secs(1), Retry::times(2));
+///
+/// fn test_fn() {
+///     // test logic
+/// }
+///
+/// run_decorated(DECORATORS, test_fn);
+/// ```
+#[track_caller]
+pub fn run_decorated<R>(decorators: &'static dyn DecorateTestFn<R>, test_fn: fn() -> R) -> R {
+    decorators.decorate_and_test_fn(test_fn)
+}
+
+/// Runs the provided parameterized test function with the specified decorators on each
+/// of the provided cases. Cases are tested lazily as the returned iterator is advanced;
+/// the iterator yields outputs of the test function for each case.
+///
+/// A test may be called multiple times for the same case (e.g., by a [`Retry`] decorator),
+/// hence the [`Clone`] requirement on the cases. Since the tested function passed
+/// to decorators must be `'static`, each case is leaked before testing.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::decorators::{run_decorated_cases, Timeout};
+///
+/// static TIMEOUT: Timeout = Timeout::secs(1);
+///
+/// fn test_fn(number: u32) -> Result<(), String> {
+///     if number < 10 {
+///         Ok(())
+///     } else {
+///         Err(format!("{number} is too large"))
+///     }
+/// }
+///
+/// let outputs: Vec<_> = run_decorated_cases(&TIMEOUT, [1, 5, 20], test_fn).collect();
+/// assert_eq!(outputs.iter().filter(|output| output.is_err()).count(), 1);
+/// ```
+pub fn run_decorated_cases<D, I, R: 'static>(
+    decorators: &'static D,
+    cases: I,
+    test_fn: fn(I::Item) -> R,
+) -> impl Iterator<Item = R>
+where
+    D: DecorateTest<R>,
+    I: IntoIterator,
+    I::Item: Clone + panic::RefUnwindSafe + Send + Sync + 'static,
+{
+    cases.into_iter().map(move |case| {
+        if decorators_disabled() {
+            return test_fn(case);
+        }
+        let case: &'static I::Item = Box::leak(Box::new(case));
+        decorators.decorate_and_test(move || test_fn(case.clone()))
+    })
+}
+
+/// Decorates a closure with one or more decorators and runs it. This is a function-like
+/// counterpart of the [`decorate`](crate::decorate) attribute.
+///
+/// The macro accepts 2 args: a decorator or a tuple of decorators, and a closure without args.
+/// It expands to a call to [`decorate()`](fn@decorate); see its docs for details.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::decorators::{decorate, Retry, Timeout};
+///
+/// decorate!((Timeout::secs(1), Retry::times(2)), || {
+///     // test logic
+/// });
+/// let result: Result<(), &str> = decorate!(Timeout::millis(100), || Ok(()));
+/// assert!(result.is_ok());
+/// ```
+#[doc(hidden)] // re-exported from this module
+#[macro_export]
+macro_rules! __decorate {
+    ($decorators:expr, $test_fn:expr $(,)?) => {
+        $crate::decorators::decorate($decorators, $test_fn)
+    };
+}
+
+#[doc(inline)]
+pub use crate::__decorate as decorate;
+
+thread_local! {
+    /// Set by [`Timeout`] on the thread awaiting the test before it panics; used by [`Retry`]
+    /// to distinguish expired timeouts from other panics.
+    static TIMEOUT_EXPIRED: Cell<bool> = const { Cell::new(false) };
+    /// Zero-based index of the current [`Retry`] attempt.
+    static RETRY_ATTEMPT: Cell<usize> = const { Cell::new(0) };
+    /// Location of the decorated test, mentioned in panic messages produced by decorators.
+    static TEST_LOCATION: Cell<Option<&'static Location<'static>>> = const { Cell::new(None) };
+    /// Innermost [`Timeout`] for the test running on the current thread.
+    static CURRENT_TIMEOUT: RefCell<Option<TimeoutHandle>> = const { RefCell::new(None) };
+    /// Expected substring of the panic message for a decorated test expected to panic
+    /// (e.g., one marked with `#[should_panic]`); an empty string matches any panic.
+    /// Used by [`Retry`] to not retry expected panics.
+    static EXPECTED_PANIC: Cell<Option<&'static str>> = const { Cell::new(None) };
+}
+
+/// Sets the location of the decorated test to the caller location and restores
+/// the previous location on drop.
+#[derive(Debug)]
+struct LocationGuard {
+    prev_location: Option<&'static Location<'static>>,
+}
+
+impl LocationGuard {
+    #[track_caller]
+    fn new() -> Self {
+        Self::at(Location::caller())
+    }
+
+    fn at(location: &'static Location<'static>) -> Self {
+        Self {
+            prev_location: TEST_LOCATION.with(|cell| cell.replace(Some(location))),
+        }
+    }
+}
+
+impl Drop for LocationGuard {
+    fn drop(&mut self) {
+        TEST_LOCATION.with(|cell| cell.set(self.prev_location));
+    }
+}
+
+/// Describes the location of the decorated test (if known) for use in messages.
+fn test_location_suffix() -> String {
+    TEST_LOCATION
+        .with(Cell::get)
+        .map_or_else(String::new, |location| format!(" (test at {location})"))
+}
+
+/// Marks the test running on the current thread as expected to panic until dropped.
+#[doc(hidden)] // used by the `decorate` macro for tests marked with `#[should_panic]`; not public API
+#[derive(Debug)]
+pub struct ExpectedPanicGuard {
+    prev_value: Option<&'static str>,
+}
+
+impl ExpectedPanicGuard {
+    /// Creates a guard expecting a panic with a message containing `expected` (or any panic
+    /// if `expected` is `None`), mirroring the semantics of `#[should_panic]`.
+    pub fn new(expected: Option<&'static str>) -> Self {
+        Self {
+            prev_value: EXPECTED_PANIC.with(|cell| cell.replace(Some(expected.unwrap_or("")))),
+        }
+    }
+
+    fn expects_panic() -> bool {
+        EXPECTED_PANIC.with(Cell::get).is_some()
+    }
+
+    /// Checks whether the panic matches the expectation for the test on the current thread.
+    fn is_expected(panic_object: &(dyn Any + Send)) -> bool {
+        match EXPECTED_PANIC.with(Cell::get) {
+            None => false,
+            Some("") => true,
+            Some(expected) => {
+                extract_panic_str(panic_object).is_some_and(|message| message.contains(expected))
+            }
+        }
+    }
+}
+
+impl Drop for ExpectedPanicGuard {
+    fn drop(&mut self) {
+        EXPECTED_PANIC.with(|cell| cell.set(self.prev_value));
+    }
+}
+
+/// Sets the current retry attempt and restores the previous one on drop.
+#[derive(Debug)]
+struct AttemptGuard {
+    prev_attempt: usize,
+}
+
+impl AttemptGuard {
+    fn new(attempt: usize) -> Self {
+        Self {
+            prev_attempt: RETRY_ATTEMPT.with(|cell| cell.replace(attempt)),
+        }
+    }
+}
+
+impl Drop for AttemptGuard {
+    fn drop(&mut self) {
+        RETRY_ATTEMPT.with(|cell| cell.set(self.prev_attempt));
+    }
+}
+
+/// [Test decorator](DecorateTest) that fails a wrapped test if it doesn't complete
+/// in the specified [`Duration`].
+///
+/// The test can extend or disarm the timeout while running via [`Timeout::extend()`]
+/// or [`Timeout::current()`]; this is useful if the test detects a known slow path
+/// (e.g., a cold cache).
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::Timeout};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(Timeout::secs(5))]
+/// fn test_with_timeout() {
+///     // test logic
+/// }
+/// ```
+///
+/// ## Extending timeout
+///
+/// ```
+/// # use std::{path::Path, time::Duration};
+/// use test_casing::{decorate, decorators::Timeout};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(Timeout::secs(5))]
+/// fn test_with_cache() {
+///     if !Path::new("target/test-cache").exists() {
+///         // Populating the cache is slow; grant the test more time.
+///         Timeout::extend(Duration::from_secs(30));
+///     }
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Timeout(pub Duration);
+
+impl Timeout {
+    /// Defines a timeout with the specified number of seconds.
+    pub const fn secs(secs: u64) -> Self {
+        Self(Duration::from_secs(secs))
+    }
+
+    /// Defines a timeout with the specified number of milliseconds.
+    pub const fn millis(millis: u64) -> Self {
+        Self(Duration::from_millis(millis))
+    }
+
+    /// Multiplies the timeout by `factor` for each subsequent [`Retry`] attempt. That is,
+    /// the first attempt gets the original timeout, the second one gets `factor` times more,
+    /// etc. The `factor` must be at least 1.
+    pub const fn escalate(self, factor: f64) -> EscalatingTimeout {
+        EscalatingTimeout {
+            inner: self,
+            factor,
+        }
+    }
+
+    /// Returns a handle to the innermost timeout of the test running on the current thread,
+    /// or `None` if the test is not decorated with a timeout. Since the handle is tracked
+    /// per thread, it's not available in threads spawned by the test.
+    pub fn current() -> Option<TimeoutHandle> {
+        CURRENT_TIMEOUT.with(|cell| cell.borrow().clone())
+    }
+
+    /// Extends the innermost timeout of the test running on the current thread
+    /// by the specified duration. Returns `false` if the test is not decorated with a timeout.
+    /// This is a shorthand for calling [`TimeoutHandle::extend()`] on [`Self::current()`].
+    pub fn extend(extra: Duration) -> bool {
+        Self::current().map(|handle| handle.extend(extra)).is_some()
+    }
+
+    /// Sets the default timeout for tests generated by the [`decorate`](crate::decorate)
+    /// and [`test_casing`](crate::test_casing) macros that don't specify a timeout themselves.
+    /// `None` disables the default timeout. If this method is not called, the default timeout
+    /// is taken from the `TEST_CASING_DEFAULT_TIMEOUT` env variable (e.g., `60s`, `500ms`
+    /// or `5m`; a value without a unit is in seconds).
+    ///
+    /// A test exceeding the default timeout fails in the same way as with the `Timeout`
+    /// decorator; other tests are not affected. Since the test is run on a separate thread,
+    /// the default timeout only applies to tests with output that can be sent across threads
+    /// (e.g., not to tests returning `Result<(), Box<dyn Error>>`). It also doesn't apply
+    /// to async tests and to tests run via [`run_decorated()`] or [`decorate()`] in custom
+    /// test harnesses.
+    ///
+    /// The default timeout is disarmed once the test is run by a `Timeout` decorator, so tests
+    /// specifying a timeout explicitly are not affected. The default timeout is not accessible
+    /// via [`Self::current()`].
+    pub fn set_default(timeout: Option<Duration>) {
+        default_timeout::set_default_timeout(timeout);
+    }
+
+    fn run<R: Send + 'static, F: TestFn<R>>(timeout: Duration, test_fn: F) -> R {
+        default_timeout::disarm();
+        Self::run_with_handle(&TimeoutHandle::new(timeout), false, test_fn)
+    }
+
+    /// Runs the test with the default timeout. Unlike with [`Self::run()`], the timeout handle
+    /// is not exposed via [`Self::current()`].
+    fn run_default<R: Send + 'static, F: TestFn<R>>(timeout: Duration, test_fn: F) -> R {
+        Self::run_with_handle(&TimeoutHandle::new(timeout), true, test_fn)
+    }
+
+    #[allow(clippy::similar_names)]
+    fn run_with_handle<R: Send + 'static, F: TestFn<R>>(
+        timeout_handle: &TimeoutHandle,
+        is_default: bool,
+        test_fn: F,
+    ) -> R {
+        let (output_sx, output_rx) = mpsc::channel();
+        let test_timeout_handle = timeout_handle.clone();
+        let expected_panic = EXPECTED_PANIC.with(Cell::get);
+        // Retain the thread name so that it's possible to determine the test name
+        // from the spawned thread.
+        let mut builder = thread::Builder::new();
+        if let Some(name) = thread::current().name() {
+            builder = builder.name(name.to_owned());
+        }
+        let handle = builder
+            .spawn(move || {
+                if is_default {
+                    default_timeout::set_handle(test_timeout_handle);
+                } else {
+                    CURRENT_TIMEOUT.with(|cell| *cell.borrow_mut() = Some(test_timeout_handle));
+                }
+                EXPECTED_PANIC.with(|cell| cell.set(expected_panic));
+                output_sx.send(test_fn()).ok();
+            })
+            .expect("failed spawning test thread");
+
+        let output = loop {
+            let state = timeout_handle.state();
+            let output = match state.deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    output_rx.recv_timeout(remaining)
+                }
+                None => output_rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            // The timeout may have been extended or disarmed while waiting.
+            if matches!(output, Err(RecvTimeoutError::Timeout)) && timeout_handle.state() != state {
+                continue;
+            }
+            break output;
+        };
+
+        match output {
+            Ok(output) => {
+                handle.join().unwrap();
+                // ^ `unwrap()` is safe; the thread didn't panic before `send`ing the output,
+                // and there's nowhere to panic after that.
+                output
+            }
+            Err(RecvTimeoutError::Timeout) if is_default => {
+                let timeout = timeout_handle.state().total;
+                panic!(
+                    "Default timeout {timeout:?} expired for the test{} (see `{}`)",
+                    test_location_suffix(),
+                    default_timeout::DEFAULT_TIMEOUT_ENV_VAR
+                );
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                TIMEOUT_EXPIRED.with(|cell| cell.set(true));
+                let timeout = timeout_handle.state().total;
+                panic!(
+                    "Timeout {timeout:?} expired for the test{}",
+                    test_location_suffix()
+                );
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                let panic_object = handle.join().unwrap_err();
+                panic::resume_unwind(panic_object)
+            }
+        }
+    }
+}
+
+impl<R: Send + 'static> DecorateTest<R> for Timeout {
+    fn decorate_and_test<F: TestFn<R>>(&self, test_fn: F) -> R {
+        Timeout::run(self.0, test_fn)
+    }
+}
+
+/// State of a [`Timeout`] shared between the test thread and the thread awaiting the test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TimeoutState {
+    /// `None` if the timeout is disarmed.
+    deadline: Option<Instant>,
+    /// Total timeout including extensions.
+    total: Duration,
+}
+
+/// Handle to the [`Timeout`] of a running test allowing to extend or disarm the timeout.
+///
+/// Obtained using [`Timeout::current()`] from inside the test.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// use test_casing::{decorate, decorators::Timeout};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(Timeout::secs(5))]
+/// fn test_with_debugging() {
+///     let timeout = Timeout::current().unwrap();
+///     assert!(timeout.remaining().unwrap() <= Duration::from_secs(5));
+///     if std::env::var_os("DEBUG_TEST").is_some() {
+///         // Allow to step through the test in a debugger.
+///         timeout.disarm();
+///     }
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TimeoutHandle {
+    state: Arc<Mutex<TimeoutState>>,
+}
+
+impl TimeoutHandle {
+    fn new(timeout: Duration) -> Self {
+        let state = TimeoutState {
+            deadline: Some(Instant::now() + timeout),
+            total: timeout,
+        };
+        Self {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    fn state(&self) -> TimeoutState {
+        *self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Extends the timeout by the specified duration. Has no effect if the timeout
+    /// is disarmed or has already expired.
+    pub fn extend(&self, extra: Duration) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(deadline) = &mut state.deadline {
+            *deadline += extra;
+            state.total += extra;
+        }
+    }
+
+    /// Disarms the timeout, so that the test can run indefinitely.
+    pub fn disarm(&self) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.deadline = None;
+    }
+
+    /// Returns the remaining time until the timeout expires, or `None` if the timeout is disarmed.
+    pub fn remaining(&self) -> Option<Duration> {
+        let deadline = self.state().deadline?;
+        Some(deadline.saturating_duration_since(Instant::now()))
+    }
+}
+
+/// [Test decorator](DecorateTest) that fails a wrapped test if it doesn't complete
+/// in a timeout growing with each [`Retry`] attempt. This is useful if the first attempt
+/// contends with the load on test suite startup, while later attempts should get more headroom.
+///
+/// Constructed using [`Timeout::escalate()`]. The decorator must precede `Retry`
+/// in the decorator list; otherwise, the timeout is not escalated.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{Retry, Timeout}};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(Timeout::secs(5).escalate(2.0), Retry::times(2))]
+/// fn test_with_escalating_timeout() {
+///     // Attempts get 5s, 10s and 20s to complete, respectively.
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct EscalatingTimeout {
+    inner: Timeout,
+    factor: f64,
+}
+
+impl EscalatingTimeout {
+    fn current_timeout(&self) -> Duration {
+        assert!(
+            self.factor >= 1.0,
+            "Timeout escalation factor must be at least 1, got {}",
+            self.factor
+        );
+        let attempt = RETRY_ATTEMPT.with(Cell::get);
+        let attempt = i32::try_from(attempt).unwrap_or(i32::MAX);
+        self.inner.0.mul_f64(self.factor.powi(attempt))
+    }
+}
+
+impl<R: Send + 'static> DecorateTest<R> for EscalatingTimeout {
+    fn decorate_and_test<F: TestFn<R>>(&self, test_fn: F) -> R {
+        Timeout::run(self.current_timeout(), test_fn)
+    }
+}
+
+/// [Test decorator](DecorateTest) that retries a wrapped test the specified number of times,
+/// potentially with a delay between retries.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::Retry};
+/// use std::time::Duration;
+///
+/// const RETRY_DELAY: Duration = Duration::from_millis(200);
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(Retry::times(3).with_delay(RETRY_DELAY))]
+/// fn test_with_retries() {
+///     // test logic
+/// }
+/// ```
+///
+/// Retrying with an exponential [`Backoff`] between attempts:
+///
+/// ```
+/// use test_casing::{decorate, decorators::{Backoff, Retry}};
+/// use std::time::Duration;
+///
+/// const BACKOFF: Backoff =
+///     Backoff::exponential(Duration::from_millis(100), 2.0, Duration::from_secs(5));
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(Retry::times(5).with_backoff(BACKOFF))]
+/// fn test_with_flaky_service() {
+///     // Delays between attempts are 100ms, 200ms, 400ms, 800ms and 1.6s.
+/// }
+/// ```
+///
+/// Retrying only if the test times out:
+///
+/// ```
+/// use test_casing::{decorate, decorators::{Retry, Timeout}};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(Timeout::secs(5), Retry::times(2).only_on_timeout())]
+/// fn test_with_slow_infra() {
+///     // test logic
+/// }
+/// ```
+///
+/// # Tests expected to panic
+///
+/// If a decorated test is marked with `#[should_panic]`, the expected panic (i.e., one
+/// with the message containing the `expected` substring, if it is specified) is considered
+/// a success and is propagated immediately. Conversely, unexpected panics, [`Timeout`]s
+/// and the test completing without a panic are retried.
+///
+/// ```
+/// use test_casing::{decorate, decorators::Retry};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[should_panic(expected = "division by zero")]
+/// #[decorate(Retry::times(2))]
+/// fn test_expecting_panic() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Retry {
+    times: usize,
+    delay: Duration,
+    backoff: Option<Backoff>,
+    only_on_timeout: bool,
+    sink: Option<OutputSink>,
+}
+
+impl Retry {
+    /// Specified the number of retries. The delay between retries is zero.
+    pub const fn times(times: usize) -> Self {
+        Self {
+            times,
+            delay: Duration::ZERO,
+            backoff: None,
+            only_on_timeout: false,
+            sink: None,
+        }
+    }
+
+    /// Specifies the delay between retries. Overrides the [backoff](Self::with_backoff())
+    /// if one was specified.
+    #[must_use]
+    pub const fn with_delay(self, delay: Duration) -> Self {
+        Self {
+            delay,
+            backoff: None,
+            ..self
+        }
+    }
+
+    /// Specifies the backoff strategy for delays between retries. Overrides the
+    /// [fixed delay](Self::with_delay()) if one was specified.
+    #[must_use]
+    pub const fn with_backoff(self, backoff: Backoff) -> Self {
+        Self {
+            backoff: Some(backoff),
+            ..self
+        }
+    }
+
+    /// Only retries the test if it was aborted by a [`Timeout`]. Other panics and errors
+    /// returned by the test are propagated immediately, so that genuine regressions are
+    /// not masked by retries.
+    ///
+    /// The `Timeout` must precede `Retry` in the decorator list, with no
+    /// [`ForkedProcess`] between them.
+    #[must_use]
+    pub const fn only_on_timeout(self) -> Self {
+        Self {
+            only_on_timeout: true,
+            ..self
+        }
+    }
+
+    /// Specifies the sink for progress messages (e.g., about failed attempts). If not specified,
+    /// the [global sink](OutputSink::global()) is used.
+    #[must_use]
+    pub const fn with_sink(self, sink: OutputSink) -> Self {
+        Self {
+            sink: Some(sink),
+            ..self
+        }
+    }
+
+    /// Converts this retry specification to draw retries from a pool of `budget` retries
+    /// shared by all tests decorated with the returned decorator. See [`RetryBudget`]
+    /// for details.
+    pub const fn with_budget(self, budget: usize) -> RetryBudget {
+        RetryBudget {
+            inner: self,
+            remaining: AtomicUsize::new(budget),
+        }
+    }
+
+    /// Converts this retry specification to only retry specific errors.
+    pub const fn on_error<E>(self, matcher: fn(&E) -> bool) -> RetryErrors<E> {
+        RetryErrors {
+            inner: self,
+            matcher,
+        }
+    }
+
+    /// Converts this retry specification to only retry panics with a message matching
+    /// the specified predicate. Other panics are propagated immediately.
+    pub const fn on_panic(self, matcher: fn(&str) -> bool) -> RetryPanics {
+        RetryPanics {
+            inner: self,
+            matcher,
+        }
+    }
+
+    fn emit(&self, message: fmt::Arguments<'_>) {
+        OutputSink::resolve(self.sink).emit(message);
+    }
+
+    /// Returns the delay after the failed `attempt`.
+    fn delay(&self, attempt: usize) -> Duration {
+        self.backoff
+            .map_or(self.delay, |backoff| backoff.delay(attempt))
+    }
+
+    fn report_exhaustion(&self, attempt: usize) {
+        let attempts = attempt + 1;
+        self.emit(format_args!(
+            "Test failed after {attempts} attempts{}",
+            test_location_suffix()
+        ));
+    }
+
+    /// Checks whether a retry can be taken from the shared `budget` (if any), and takes it if so.
+    fn take_from_budget(&self, budget: Option<&AtomicUsize>) -> bool {
+        let Some(budget) = budget else {
+            return true;
+        };
+        let has_budget = budget
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |remaining| {
+                remaining.checked_sub(1)
+            })
+            .is_ok();
+        if !has_budget {
+            self.emit(format_args!(
+                "Shared retry budget is exhausted; not retrying the test"
+            ));
+        }
+        has_budget
+    }
+
+    fn handle_panic(
+        &self,
+        attempt: usize,
+        panic_object: Box<dyn Any + Send>,
+        should_retry: fn(&str) -> bool,
+        budget: Option<&AtomicUsize>,
+    ) {
+        let is_timeout = TIMEOUT_EXPIRED.with(|cell| cell.replace(false));
+        if !is_timeout && ExpectedPanicGuard::is_expected(&*panic_object) {
+            // The panic is the desired outcome of the test, so there's no point retrying it.
+            panic::resume_unwind(panic_object);
+        }
+        let panic_str = extract_panic_str(&*panic_object).unwrap_or("");
+        if attempt < self.times
+            && (is_timeout || !self.only_on_timeout)
+            && should_retry(panic_str)
+            && self.take_from_budget(budget)
+        {
+            let punctuation = if panic_str.is_empty() { "" } else { ": " };
+            self.emit(format_args!(
+                "Test attempt #{attempt} panicked{punctuation}{panic_str}"
+            ));
+        } else {
+            if attempt > 0 {
+                self.report_exhaustion(attempt);
+            }
+            panic::resume_unwind(panic_object);
+        }
+    }
+
+    /// Returns `Ok(())` if the test should be retried after the error.
+    fn handle_error<E: fmt::Debug>(
+        &self,
+        attempt: usize,
+        err: E,
+        should_retry: fn(&E) -> bool,
+        budget: Option<&AtomicUsize>,
+    ) -> Result<(), E> {
+        if attempt < self.times
+            && !self.only_on_timeout
+            && should_retry(&err)
+            && self.take_from_budget(budget)
+        {
+            self.emit(format_args!("Test attempt #{attempt} errored: {err:?}"));
+            Ok(())
+        } else {
+            if attempt > 0 {
+                self.report_exhaustion(attempt);
+            }
+            Err(err)
+        }
+    }
+
+    fn run_with_retries<E: fmt::Debug>(
+        &self,
+        test_fn: impl TestFn<Result<(), E>>,
+        should_retry: fn(&E) -> bool,
+        should_retry_panic: fn(&str) -> bool,
+        budget: Option<&AtomicUsize>,
+    ) -> Result<(), E> {
+        for attempt in 0..=self.times {
+            self.emit(format_args!("Test attempt #{attempt}"));
+            let attempt_guard = AttemptGuard::new(attempt);
+            let output = panic::catch_unwind(test_fn);
+            drop(attempt_guard);
+            match output {
+                Ok(Ok(())) => return Ok(()),
+                Ok(Err(err)) => self.handle_error(attempt, err, should_retry, budget)?,
+                Err(panic_object) => {
+                    self.handle_panic(attempt, panic_object, should_retry_panic, budget);
+                }
+            }
+            let delay = self.delay(attempt);
+            if delay > Duration::ZERO {
+                thread::sleep(delay);
+            }
+        }
+        Ok(())
+    }
+
+    fn run(
+        &self,
+        test_fn: impl TestFn<()>,
+        should_retry_panic: fn(&str) -> bool,
+        budget: Option<&AtomicUsize>,
+    ) {
+        for attempt in 0..=self.times {
+            self.emit(format_args!("Test attempt #{attempt}"));
+            let attempt_guard = AttemptGuard::new(attempt);
+            let output = panic::catch_unwind(test_fn);
+            drop(attempt_guard);
+            match output {
+                Ok(()) if ExpectedPanicGuard::expects_panic() => {
+                    if attempt < self.times
+                        && !self.only_on_timeout
+                        && self.take_from_budget(budget)
+                    {
+                        self.emit(format_args!("Test attempt #{attempt} did not panic"));
+                    } else {
+                        if attempt > 0 {
+                            self.report_exhaustion(attempt);
+                        }
+                        break;
+                    }
+                }
+                Ok(()) => break,
+                Err(panic_object) => {
+                    self.handle_panic(attempt, panic_object, should_retry_panic, budget);
+                }
+            }
+            let delay = self.delay(attempt);
+            if delay > Duration::ZERO {
+                thread::sleep(delay);
+            }
+        }
+    }
+}
+
+impl DecorateTest<()> for Retry {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        self.run(test_fn, |_| true, None);
+    }
+}
+
+impl<E: fmt::Debug> DecorateTest<Result<(), E>> for Retry {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.run_with_retries(test_fn, |_| true, |_| true, None)
+    }
+}
+
+/// Backoff strategy for delays between [`Retry`] attempts.
+///
+/// # Examples
+///
+/// See [`Retry`] docs for an example of usage.
+#[derive(Debug, Clone, Copy)]
+pub struct Backoff {
+    base: Duration,
+    factor: f64,
+    max: Duration,
+}
+
+impl Backoff {
+    /// Creates an exponential backoff. The delay after the first failed attempt is `base`;
+    /// each following delay is `factor` times longer than the previous one, but is capped
+    /// at `max`. The `factor` must be at least 1.
+    pub const fn exponential(base: Duration, factor: f64, max: Duration) -> Self {
+        Self { base, factor, max }
+    }
+
+    /// Returns the delay after the specified zero-based `attempt`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the backoff factor is less than 1.
+    pub fn delay(&self, attempt: usize) -> Duration {
+        assert!(
+            self.factor >= 1.0,
+            "Backoff factor must be at least 1, got {}",
+            self.factor
+        );
+        let attempt = i32::try_from(attempt).unwrap_or(i32::MAX);
+        let delay = self.base.as_secs_f64() * self.factor.powi(attempt);
+        Duration::from_secs_f64(delay.min(self.max.as_secs_f64()))
+    }
+}
+
+fn extract_panic_str(panic_object: &(dyn Any + Send)) -> Option<&str> {
+    if let Some(panic_str) = panic_object.downcast_ref::<&'static str>() {
+        Some(panic_str)
+    } else if let Some(panic_string) = panic_object.downcast_ref::<String>() {
+        Some(panic_string.as_str())
+    } else {
+        None
+    }
+}
+
+/// [Test decorator](DecorateTest) that retries a wrapped test a certain number of times
+/// only if an error matches the specified predicate.
+///
+/// Constructed using [`Retry::on_error()`].
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{Retry, RetryErrors}};
+/// use std::error::Error;
+///
+/// const RETRY: RetryErrors<Box<dyn Error>> = Retry::times(3)
+///     .on_error(|err| err.to_string().contains("retry please"));
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(RETRY)]
+/// fn test_with_retries() -> Result<(), Box<dyn Error>> {
+///     // test logic
+/// #    Ok(())
+/// }
+/// ```
+pub struct RetryErrors<E> {
+    inner: Retry,
+    matcher: fn(&E) -> bool,
+}
+
+impl<E> fmt::Debug for RetryErrors<E> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("RetryErrors")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<E: fmt::Debug + 'static> DecorateTest<Result<(), E>> for RetryErrors<E> {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.inner
+            .run_with_retries(test_fn, self.matcher, |_| true, None)
+    }
+}
+
+/// [Test decorator](DecorateTest) that retries a wrapped test a certain number of times
+/// only if it panics with a message matching the specified predicate.
+///
+/// The predicate receives the panic message, or an empty string if the panic payload
+/// is not a string. Panics not matching the predicate are propagated immediately. If the test
+/// returns a `Result`, errors are retried regardless of the predicate.
+///
+/// Constructed using [`Retry::on_panic()`].
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{Retry, RetryPanics}};
+///
+/// const RETRY: RetryPanics = Retry::times(3)
+///     .on_panic(|message| message.contains("connection reset"));
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(RETRY)]
+/// fn test_with_retries() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug)]
+pub struct RetryPanics {
+    inner: Retry,
+    matcher: fn(&str) -> bool,
+}
+
+impl DecorateTest<()> for RetryPanics {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        self.inner.run(test_fn, self.matcher, None);
+    }
+}
+
+impl<E: fmt::Debug> DecorateTest<Result<(), E>> for RetryPanics {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.inner
+            .run_with_retries(test_fn, |_| true, self.matcher, None)
+    }
+}
+
+/// [Test decorator](DecorateTest) that retries a wrapped test, drawing retries from a pool
+/// shared by all tests decorated with the same decorator instance.
+///
+/// All cases generated by a single [`test_casing`](crate::test_casing) attribute are decorated
+/// with the same instance, so the budget limits the total number of retries across the cases.
+/// This prevents a systemically broken dependency (e.g., a database that is down) from multiplying
+/// the test suite duration by the number of per-test retries. To share a budget among
+/// multiple test functions, place the decorator into a `static` and reference it
+/// in the [`decorate`](crate::decorate) attributes.
+///
+/// Constructed using [`Retry::with_budget()`].
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::Retry, test_casing};
+///
+/// // At most 5 retries across all 20 cases; each case is retried at most twice.
+/// #[test_casing(20, 0..20)]
+/// #[decorate(Retry::times(2).with_budget(5))]
+/// fn test_with_shared_retries(number: u64) {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug)]
+pub struct RetryBudget {
+    inner: Retry,
+    remaining: AtomicUsize,
+}
+
+impl RetryBudget {
+    /// Returns the number of retries remaining in the budget.
+    pub fn remaining(&self) -> usize {
+        self.remaining.load(Ordering::SeqCst)
+    }
+}
+
+impl DecorateTest<()> for RetryBudget {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        self.inner.run(test_fn, |_| true, Some(&self.remaining));
+    }
+}
+
+impl<E: fmt::Debug> DecorateTest<Result<(), E>> for RetryBudget {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.inner
+            .run_with_retries(test_fn, |_| true, |_| true, Some(&self.remaining))
+    }
+}
+
+/// [Test decorator](DecorateTest) that makes runs of decorated tests sequential. The sequence
+/// can optionally be aborted if a test in it fails.
+///
+/// The run ordering of tests in the sequence is not deterministic. This is because depending
+/// on the command-line args that the test was launched with, not all tests in the sequence may run
+/// at all.
+///
+/// The sequence can be limited in the total wall-clock time via [`Self::with_total_timeout()`].
+/// Once tests in the sequence have consumed this time, the remaining tests are skipped.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{Sequence, Timeout}};
+///
+/// static SEQUENCE: Sequence = Sequence::new().abort_on_failure();
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(&SEQUENCE)]
+/// fn sequential_test() {
+///     // test logic
+/// }
+///
+/// #[test]
+/// # fn eat_test_attribute2() {}
+/// #[decorate(Timeout::secs(1), &SEQUENCE)]
+/// fn other_sequential_test() {
+///     // test logic
+/// }
+/// ```
+///
+/// Collecting statistics:
+///
+/// ```
+/// # use test_casing::decorators::Sequence;
+/// static SEQUENCE: Sequence = Sequence::new().with_summary("database tests");
+/// // ^ Prints a summary like `Sequence `database tests`: 3 test(s) run, 1 failed, 0 skipped`
+/// // on process exit.
+///
+/// // ...tests decorated with `SEQUENCE`...
+///
+/// # fn check_stats() {
+/// let stats = SEQUENCE.stats();
+/// assert_eq!(stats.failed, 0);
+/// # }
+/// ```
+///
+/// Limiting the total duration of the sequence:
+///
+/// ```
+/// use test_casing::{decorate, decorators::{Sequence, Timeout}};
+/// use std::time::Duration;
+///
+/// static SEQUENCE: Sequence = Sequence::new().with_total_timeout(Duration::from_secs(60));
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(Timeout::secs(10), &SEQUENCE)]
+/// // ^ The timeout bounds the duration of an individual test, which is not interrupted
+/// // if the sequence runs out of time.
+/// fn sequential_test() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct Sequence {
+    state: Mutex<SequenceState>,
+    abort_on_failure: bool,
+    total_timeout: Option<Duration>,
+    sink: Option<OutputSink>,
+    summary_name: Option<&'static str>,
+    is_summary_registered: AtomicBool,
+    run_count: AtomicUsize,
+    failed_count: AtomicUsize,
+    skipped_count: AtomicUsize,
+}
+
+/// Runtime statistics of a [`Sequence`] returned by [`Sequence::stats()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SequenceStats {
+    /// Number of tests that were run, including failed ones.
+    pub run: usize,
+    /// Number of tests that failed (i.e., panicked or returned an error).
+    pub failed: usize,
+    /// Number of tests skipped because a previous test in the sequence has failed,
+    /// or because the sequence has exhausted its [total timeout](Sequence::with_total_timeout()).
+    /// Always zero unless either of these options is specified.
+    pub skipped: usize,
+}
+
+/// Mutable state of a [`Sequence`] guarded by a mutex, which is held while running a test.
+#[derive(Debug, Default)]
+struct SequenceState {
+    failed: bool,
+    /// Total duration of tests run in the sequence.
+    elapsed: Duration,
+}
+
+impl fmt::Display for SequenceStats {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "{} test(s) run, {} failed, {} skipped",
+            self.run, self.failed, self.skipped
+        )
+    }
+}
+
+impl Sequence {
+    /// Creates a new test sequence.
+    pub const fn new() -> Self {
+        Self {
+            state: Mutex::new(SequenceState {
+                failed: false,
+                elapsed: Duration::ZERO,
+            }),
+            abort_on_failure: false,
+            total_timeout: None,
+            sink: None,
+            summary_name: None,
+            is_summary_registered: AtomicBool::new(false),
+            run_count: AtomicUsize::new(0),
+            failed_count: AtomicUsize::new(0),
+            skipped_count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Makes the decorated tests abort immediately if one test from the sequence fails.
+    #[must_use]
+    pub const fn abort_on_failure(mut self) -> Self {
+        self.abort_on_failure = true;
+        self
+    }
+
+    /// Limits the total wall-clock time of tests in the sequence. Once the tests run
+    /// in the sequence have consumed this time, the remaining tests are skipped (i.e., pass
+    /// without running) with a message to the [output sink](Self::with_sink()).
+    ///
+    /// A running test is not interrupted if the sequence runs out of time; use a [`Timeout`]
+    /// to bound the duration of individual tests.
+    #[must_use]
+    pub const fn with_total_timeout(mut self, timeout: Duration) -> Self {
+        self.total_timeout = Some(timeout);
+        self
+    }
+
+    /// Specifies the sink for progress messages (e.g., about skipped tests). If not specified,
+    /// the [global sink](OutputSink::global()) is used.
+    #[must_use]
+    pub const fn with_sink(mut self, sink: OutputSink) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
+    /// Prints [statistics](Self::stats()) for the sequence with the specified name
+    /// to the [output sink](Self::with_sink()) on process exit, provided that at least one test
+    /// in the sequence was started. The summary is only supported on Unix and Windows targets.
+    #[must_use]
+    pub const fn with_summary(mut self, name: &'static str) -> Self {
+        self.summary_name = Some(name);
+        self
+    }
+
+    /// Returns statistics for tests in this sequence that have completed so far.
+    pub fn stats(&self) -> SequenceStats {
+        SequenceStats {
+            run: self.run_count.load(Ordering::SeqCst),
+            failed: self.failed_count.load(Ordering::SeqCst),
+            skipped: self.skipped_count.load(Ordering::SeqCst),
+        }
+    }
+
+    fn register_summary(&'static self) {
+        if self.summary_name.is_some() && !self.is_summary_registered.swap(true, Ordering::SeqCst) {
+            summary::register(self);
+        }
+    }
+
+    fn decorate_inner<R, F: TestFn<R>>(
+        &'static self,
+        test_fn: F,
+        ok_value: R,
+        match_failure: fn(&R) -> bool,
+    ) -> R {
+        self.register_summary();
+        let mut guard = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if guard.failed && self.abort_on_failure {
+            self.skipped_count.fetch_add(1, Ordering::SeqCst);
+            OutputSink::resolve(self.sink).emit(format_args!(
+                "Skipping test because a previous test in the same sequence has failed"
+            ));
+            return ok_value;
+        }
+        if let Some(total_timeout) = self.total_timeout {
+            if guard.elapsed >= total_timeout {
+                self.skipped_count.fetch_add(1, Ordering::SeqCst);
+                let elapsed = guard.elapsed;
+                OutputSink::resolve(self.sink).emit(format_args!(
+                    "Skipping test{} because the sequence has exhausted its total timeout \
+                     {total_timeout:?} ({elapsed:?} consumed by previous tests)",
+                    test_location_suffix()
+                ));
+                return ok_value;
+            }
+        }
+
+        let started_at = Instant::now();
+        let output = panic::catch_unwind(test_fn);
+        guard.elapsed += started_at.elapsed();
+        guard.failed = output.as_ref().map_or(true, match_failure);
+        self.run_count.fetch_add(1, Ordering::SeqCst);
+        if guard.failed {
+            self.failed_count.fetch_add(1, Ordering::SeqCst);
+        }
+        drop(guard);
+        output.unwrap_or_else(|panic_object| {
+            panic::resume_unwind(panic_object);
+        })
+    }
+}
+
+impl summary::PrintSummary for Sequence {
+    fn print_summary(&self) {
+        if let Some(name) = self.summary_name {
+            let stats = self.stats();
+            OutputSink::resolve(self.sink).emit(format_args!("Sequence `{name}`: {stats}"));
+        }
+    }
+}
+
+impl DecorateTest<()> for Sequence {
+    fn decorate_and_test<F: TestFn<()>>(&'static self, test_fn: F) {
+        self.decorate_inner(test_fn, (), |()| false);
+    }
+}
+
+impl<E: 'static> DecorateTest<Result<(), E>> for Sequence {
+    fn decorate_and_test<F>(&'static self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.decorate_inner(test_fn, Ok(()), Result::is_err)
+    }
+}
+
+/// Printing summaries (e.g., for [`Sequence`]s or failed test cases) on process exit.
+pub(crate) mod summary {
+    use std::sync::{Mutex, Once, PoisonError};
+
+    /// Component (e.g., a decorator) printing a summary on process exit.
+    pub(crate) trait PrintSummary: Send + Sync {
+        fn print_summary(&self);
+    }
+
+    static SUMMARIES: Mutex<Vec<&'static dyn PrintSummary>> = Mutex::new(Vec::new());
+
+    #[cfg(any(unix, windows))]
+    extern "C" {
+        fn atexit(callback: extern "C" fn()) -> std::os::raw::c_int;
+    }
+
+    extern "C" fn print_summaries() {
+        let summaries = SUMMARIES.lock().unwrap_or_else(PoisonError::into_inner);
+        for summary in summaries.iter() {
+            summary.print_summary();
+        }
+    }
+
+    pub(crate) fn register(summary: &'static dyn PrintSummary) {
+        static INSTALL_HOOK: Once = Once::new();
+
+        SUMMARIES
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(summary);
+        INSTALL_HOOK.call_once(|| {
+            // SAFETY: `atexit` is safe to call with an `extern "C"` function that doesn't unwind.
+            // `print_summaries()` doesn't panic under normal circumstances; if it does, unwinding
+            // through FFI aborts the process, which is acceptable on exit.
+            #[cfg(any(unix, windows))]
+            unsafe {
+                atexit(print_summaries);
+            }
+        });
+    }
+}
+
+/// Determines which decorators in a tuple should be skipped. In a child process spawned
+/// by [`ForkedProcess`], decorators following the process boundary (i.e., wrapping it)
+/// have already been applied in the parent process.
+#[doc(hidden)] // used by the `DecorateTest` derive macro; not public API
+pub fn decorators_to_skip<const N: usize>(process_boundaries: [bool; N]) -> [bool; N] {
+    let mut skipped = [false; N];
+    if process::is_forked_child() {
+        if let Some(pos) = process_boundaries.iter().position(|&flag| flag) {
+            skipped[pos + 1..].fill(true);
+        }
+    }
+    skipped
+}
+
+macro_rules! impl_decorate_test_for_tuple {
+    ($($field:ident : $ty:ident),* => $last_field:ident : $last_ty:ident) => {
+        impl<R, $($ty,)* $last_ty> DecorateTest<R> for ($($ty,)* $last_ty,)
+        where
+            $($ty: DecorateTest<R>,)*
+            $last_ty: DecorateTest<R>,
+        {
+            fn decorate_and_test<Fn: TestFn<R>>(&'static self, test_fn: Fn) -> R {
+                let ($($field,)* $last_field,) = self;
+                let process_boundaries = [
+                    $($field.is_process_boundary(),)*
+                    $last_field.is_process_boundary(),
+                ];
+                let mut skipped = decorators_to_skip(process_boundaries).into_iter();
+                $(
+                let is_skipped = skipped.next().unwrap();
+                let test_fn = move || {
+                    if is_skipped {
+                        test_fn()
+                    } else {
+                        $field.decorate_and_test(test_fn)
+                    }
+                };
+                )*
+                if skipped.next().unwrap() {
+                    test_fn()
+                } else {
+                    $last_field.decorate_and_test(test_fn)
+                }
+            }
+
+            fn is_process_boundary(&self) -> bool {
+                let ($($field,)* $last_field,) = self;
+                $($field.is_process_boundary() ||)* $last_field.is_process_boundary()
+            }
+        }
+    };
+}
+
+impl_decorate_test_for_tuple!(=> a: A);
+impl_decorate_test_for_tuple!(a: A => b: B);
+impl_decorate_test_for_tuple!(a: A, b: B => c: C);
+impl_decorate_test_for_tuple!(a: A, b: B, c: C => d: D);
+impl_decorate_test_for_tuple!(a: A, b: B, c: C, d: D => e: E);
+impl_decorate_test_for_tuple!(a: A, b: B, c: C, d: D, e: E => f: F);
+impl_decorate_test_for_tuple!(a: A, b: B, c: C, d: D, e: E, f: F => g: G);
+impl_decorate_test_for_tuple!(a: A, b: B, c: C, d: D, e: E, f: F, g: G => h: H);
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::{
+            atomic::{AtomicU32, Ordering},
+            Mutex,
+        },
+        time::Instant,
+    };
+
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "Timeout 100ms expired")]
+    fn timeouts() {
+        const TIMEOUT: Timeout = Timeout(Duration::from_millis(100));
+
+        let test_fn: fn() = || thread::sleep(Duration::from_secs(1));
+        TIMEOUT.decorate_and_test(test_fn);
+    }
+
+    #[test]
+    fn extending_timeout() {
+        const TIMEOUT: Timeout = Timeout(Duration::from_millis(100));
+
+        let test_fn: fn() -> bool = || {
+            let remaining = Timeout::current().unwrap().remaining().unwrap();
+            assert!(remaining <= Duration::from_millis(100), "{remaining:?}");
+            thread::sleep(Duration::from_millis(50));
+            let is_extended = Timeout::extend(Duration::from_millis(500));
+            thread::sleep(Duration::from_millis(200));
+            is_extended
+        };
+        assert!(TIMEOUT.decorate_and_test(test_fn));
+        assert!(Timeout::current().is_none());
+        assert!(!Timeout::extend(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn disarming_timeout() {
+        const TIMEOUT: Timeout = Timeout(Duration::from_millis(100));
+
+        let test_fn: fn() = || {
+            let timeout = Timeout::current().unwrap();
+            timeout.disarm();
+            assert_eq!(timeout.remaining(), None);
+            thread::sleep(Duration::from_millis(300));
+        };
+        TIMEOUT.decorate_and_test(test_fn);
+    }
+
+    #[test]
+    #[should_panic(expected = "Timeout 300ms expired")]
+    fn extended_timeout_expiring() {
+        const TIMEOUT: Timeout = Timeout(Duration::from_millis(100));
+
+        let test_fn: fn() = || {
+            Timeout::extend(Duration::from_millis(200));
+            thread::sleep(Duration::from_secs(1));
+        };
+        TIMEOUT.decorate_and_test(test_fn);
+    }
+
+    #[test]
+    fn retrying_with_delay() {
+        const RETRY: Retry = Retry::times(1).with_delay(Duration::from_millis(100));
+
+        fn test_fn() -> Result<(), &'static str> {
+            static TEST_START: Mutex<Option<Instant>> = Mutex::new(None);
+
+            let mut test_start = TEST_START.lock().unwrap();
+            if let Some(test_start) = *test_start {
+                assert!(test_start.elapsed() > RETRY.delay);
+                Ok(())
+            } else {
+                *test_start = Some(Instant::now());
+                Err("come again?")
+            }
+        }
+
+        RETRY.decorate_and_test(test_fn).unwrap();
+    }
+
+    #[test]
+    fn exponential_backoff() {
+        let backoff = Backoff::exponential(Duration::from_millis(100), 2.0, Duration::from_secs(1));
+        let delays: Vec<_> = (0..6).map(|attempt| backoff.delay(attempt)).collect();
+        let expected_delays = [100, 200, 400, 800, 1_000, 1_000].map(Duration::from_millis);
+        assert_eq!(delays, expected_delays);
+        assert_eq!(backoff.delay(usize::MAX), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn retrying_with_backoff() {
+        const RETRY: Retry = Retry::times(2).with_backoff(Backoff::exponential(
+            Duration::from_millis(50),
+            2.0,
+            Duration::from_secs(1),
+        ));
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        let started_at = Instant::now();
+        RETRY.decorate_and_test(|| {
+            assert!(COUNTER.fetch_add(1, Ordering::SeqCst) == 2, "come again?");
+        });
+        assert_eq!(COUNTER.load(Ordering::SeqCst), 3);
+        assert!(started_at.elapsed() >= Duration::from_millis(150));
+    }
+
+    const RETRY: RetryErrors<io::Error> =
+        Retry::times(2).on_error(|err| matches!(err.kind(), io::ErrorKind::AddrInUse));
+
+    #[test]
+    fn retrying_on_error() {
+        static TEST_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        fn test_fn() -> io::Result<()> {
+            if TEST_COUNTER.fetch_add(1, Ordering::Relaxed) == 2 {
+                Ok(())
+            } else {
+                Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    "please retry later",
+                ))
+            }
+        }
+
+        let test_fn: fn() -> _ = test_fn;
+        RETRY.decorate_and_test(test_fn).unwrap();
+        assert_eq!(TEST_COUNTER.load(Ordering::Relaxed), 3);
+
+        let err = RETRY.decorate_and_test(test_fn).unwrap_err();
+        assert!(err.to_string().contains("please retry later"));
+        assert_eq!(TEST_COUNTER.load(Ordering::Relaxed), 6);
+    }
+
+    #[test]
+    fn retrying_on_error_failure() {
+        static TEST_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        fn test_fn() -> io::Result<()> {
+            if TEST_COUNTER.fetch_add(1, Ordering::Relaxed) == 0 {
+                Err(io::Error::new(io::ErrorKind::BrokenPipe, "oops"))
+            } else {
+                Ok(())
+            }
+        }
+
+        let err = RETRY.decorate_and_test(test_fn).unwrap_err();
+        assert!(err.to_string().contains("oops"));
+        assert_eq!(TEST_COUNTER.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn retrying_on_panic() {
+        const RETRY: RetryPanics = Retry::times(2).on_panic(|message| message.contains("retry"));
+        static TEST_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        let test_fn: fn() = || {
+            assert!(
+                TEST_COUNTER.fetch_add(1, Ordering::Relaxed) >= 2,
+                "please retry later"
+            );
+        };
+        RETRY.decorate_and_test(test_fn);
+        assert_eq!(TEST_COUNTER.load(Ordering::Relaxed), 3);
+
+        let test_fn: fn() = || {
+            TEST_COUNTER.fetch_add(1, Ordering::Relaxed);
+            panic!("oops");
+        };
+        let panic_object = panic::catch_unwind(|| RETRY.decorate_and_test(test_fn)).unwrap_err();
+        assert_eq!(extract_panic_str(&*panic_object), Some("oops"));
+        assert_eq!(TEST_COUNTER.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn retrying_with_budget() {
+        static RETRY: RetryBudget = Retry::times(2).with_budget(3);
+        static TEST_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        let test_fn: fn() -> Result<(), &'static str> = || {
+            TEST_COUNTER.fetch_add(1, Ordering::Relaxed);
+            Err("oops")
+        };
+        // The first test consumes 2 retries, the second one the remaining retry.
+        for expected_count in [3, 5, 6] {
+            RETRY.decorate_and_test(test_fn).unwrap_err();
+            assert_eq!(TEST_COUNTER.load(Ordering::Relaxed), expected_count);
+        }
+        assert_eq!(RETRY.remaining(), 0);
+
+        let test_fn: fn() = || {
+            TEST_COUNTER.fetch_add(1, Ordering::Relaxed);
+            panic!("oops");
+        };
+        panic::catch_unwind(|| RETRY.decorate_and_test(test_fn)).unwrap_err();
+        assert_eq!(TEST_COUNTER.load(Ordering::Relaxed), 7);
+    }
+
+    #[test]
+    fn retrying_with_custom_sink() {
+        static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+        static RETRY: Retry = Retry::times(1).with_sink(OutputSink::Custom(|message| {
+            MESSAGES.lock().unwrap().push(message.to_owned());
+        }));
+
+        let test_fn: fn() -> Result<(), &'static str> = || Err("oops");
+        RETRY.decorate_and_test(test_fn).unwrap_err();
+        let messages = MESSAGES.lock().unwrap();
+        assert_eq!(messages.len(), 4, "{messages:?}");
+        assert_eq!(messages[0], "Test attempt #0");
+        assert_eq!(messages[1], "Test attempt #0 errored: \"oops\"");
+        assert_eq!(messages[2], "Test attempt #1");
+        assert!(
+            messages[3].starts_with("Test failed after 2 attempts"),
+            "{messages:?}"
+        );
+    }
+
+    #[test]
+    fn sequential_tests() {
+        static SEQUENCE: Sequence = Sequence::new();
+        static ENTRY_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        let first_test: fn() = || {
+            let counter = ENTRY_COUNTER.fetch_add(1, Ordering::Relaxed);
+            assert_eq!(counter, 0);
+            thread::sleep(Duration::from_millis(10));
+            ENTRY_COUNTER.store(0, Ordering::Relaxed);
+            panic!("oops");
+        };
+        let second_test = || {
+            let counter = ENTRY_COUNTER.fetch_add(1, Ordering::Relaxed);
+            assert_eq!(counter, 0);
+            thread::sleep(Duration::from_millis(20));
+            ENTRY_COUNTER.store(0, Ordering::Relaxed);
+            Ok::<_, io::Error>(())
+        };
+
+        let first_test_handle = thread::spawn(move || SEQUENCE.decorate_and_test(first_test));
+        SEQUENCE.decorate_and_test(second_test).unwrap();
+        first_test_handle.join().unwrap_err();
+        let stats = SEQUENCE.stats();
+        assert_eq!((stats.run, stats.failed, stats.skipped), (2, 1, 0));
+    }
+
+    #[test]
+    fn sequential_tests_with_abort() {
+        static SEQUENCE: Sequence = Sequence::new().abort_on_failure();
+
+        let failing_test =
+            || Err::<(), _>(io::Error::new(io::ErrorKind::AddrInUse, "please try later"));
+        let second_test: fn() = || unreachable!("Second test should not be called!");
+
+        SEQUENCE.decorate_and_test(failing_test).unwrap_err();
+        SEQUENCE.decorate_and_test(second_test);
+        let expected_stats = SequenceStats {
+            run: 1,
+            failed: 1,
+            skipped: 1,
+        };
+        assert_eq!(SEQUENCE.stats(), expected_stats);
+        assert_eq!(
+            expected_stats.to_string(),
+            "1 test(s) run, 1 failed, 1 skipped"
+        );
+    }
+
+    #[test]
+    fn sequential_tests_with_total_timeout() {
+        static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+        static SEQUENCE: Sequence = Sequence::new()
+            .with_total_timeout(Duration::from_millis(50))
+            .with_sink(OutputSink::Custom(|message| {
+                MESSAGES.lock().unwrap().push(message.to_owned());
+            }));
+        static TEST_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        let test_fn: fn() = || {
+            TEST_COUNTER.fetch_add(1, Ordering::Relaxed);
+            thread::sleep(Duration::from_millis(30));
+        };
+        for _ in 0..3 {
+            SEQUENCE.decorate_and_test(test_fn);
+        }
+        assert_eq!(TEST_COUNTER.load(Ordering::Relaxed), 2);
+        let expected_stats = SequenceStats {
+            run: 2,
+            failed: 0,
+            skipped: 1,
+        };
+        assert_eq!(SEQUENCE.stats(), expected_stats);
+
+        let messages = MESSAGES.lock().unwrap();
+        assert_eq!(messages.len(), 1, "{messages:?}");
+        assert!(
+            messages[0].contains("exhausted its total timeout 50ms"),
+            "{messages:?}"
+        );
+        assert!(
+            messages[0].contains("consumed by previous tests"),
+            "{messages:?}"
+        );
+    }
+
+    // We need independent test counters for different tests, hence defining a function
+    // via a macro.
+    macro_rules! define_test_fn {
+        () => {
+            fn test_fn() -> Result<(), &'static str> {
+                static TEST_COUNTER: AtomicU32 = AtomicU32::new(0);
+                match TEST_COUNTER.fetch_add(1, Ordering::Relaxed) {
+                    0 => {
+                        thread::sleep(Duration::from_secs(1));
+                        Ok(())
+                    }
+                    1 => Err("oops"),
+                    2 => Ok(()),
+                    _ => unreachable!(),
+                }
+            }
+        };
+    }
+
+    #[test]
+    fn composing_decorators() {
+        define_test_fn!();
+
+        const DECORATORS: (Timeout, Retry) = (Timeout(Duration::from_millis(100)), Retry::times(2));
+
+        DECORATORS.decorate_and_test(test_fn).unwrap();
+    }
+
+    #[test]
+    fn retrying_only_on_timeout() {
+        define_test_fn!();
+
+        const DECORATORS: (Timeout, Retry) = (
+            Timeout(Duration::from_millis(100)),
+            Retry::times(2).only_on_timeout(),
+        );
+
+        // The first attempt times out and is retried; the second one errors and is not.
+        let err = DECORATORS.decorate_and_test(test_fn).unwrap_err();
+        assert_eq!(err, "oops");
+
+        let test_fn: fn() = || panic!("oops");
+        panic::catch_unwind(|| DECORATORS.decorate_and_test(test_fn)).unwrap_err();
+        assert!(!TIMEOUT_EXPIRED.with(Cell::get));
+    }
+
+    #[test]
+    fn retrying_with_expected_panic() {
+        static RETRY: Retry = Retry::times(2);
+        static TEST_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        let _guard = ExpectedPanicGuard::new(Some("expected"));
+        // The expected panic is propagated without retries.
+        let test_fn: fn() = || {
+            TEST_COUNTER.fetch_add(1, Ordering::Relaxed);
+            panic!("expected panic");
+        };
+        panic::catch_unwind(|| RETRY.decorate_and_test(test_fn)).unwrap_err();
+        assert_eq!(TEST_COUNTER.swap(0, Ordering::Relaxed), 1);
+
+        // Unexpected panics and completing without a panic are retried.
+        let test_fn: fn() = || match TEST_COUNTER.fetch_add(1, Ordering::Relaxed) {
+            0 => panic!("oops"),
+            1 => { /* do not panic */ }
+            _ => panic!("expected panic"),
+        };
+        panic::catch_unwind(|| RETRY.decorate_and_test(test_fn)).unwrap_err();
+        assert_eq!(TEST_COUNTER.swap(0, Ordering::Relaxed), 3);
+
+        // If all attempts complete without a panic, the test returns normally.
+        let test_fn: fn() = || {
+            TEST_COUNTER.fetch_add(1, Ordering::Relaxed);
+        };
+        RETRY.decorate_and_test(test_fn);
+        assert_eq!(TEST_COUNTER.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn timeout_panic_mentions_test_location() {
+        static DECORATORS: &dyn DecorateTestFn<()> = &Timeout(Duration::from_millis(50));
+
+        let test_fn: fn() = || thread::sleep(Duration::from_millis(500));
+        let expected_line = line!() + 1;
+        let err = panic::catch_unwind(|| DECORATORS.decorate_and_test_fn(test_fn)).unwrap_err();
+        let message = extract_panic_str(&*err).unwrap();
+        let expected_location = format!("{}:{expected_line}:", file!());
+        assert!(message.contains(&expected_location), "{message}");
+        assert!(TEST_LOCATION.with(Cell::get).is_none());
+    }
+
+    #[test]
+    fn escalating_timeout() {
+        static DECORATORS: (EscalatingTimeout, Retry) = (
+            Timeout(Duration::from_millis(100)).escalate(4.0),
+            Retry::times(2),
+        );
+        static TEST_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        // The first attempt times out, while the second one completes within the escalated timeout.
+        let test_fn: fn() = || {
+            TEST_COUNTER.fetch_add(1, Ordering::Relaxed);
+            thread::sleep(Duration::from_millis(200));
+        };
+        DECORATORS.decorate_and_test(test_fn);
+        assert_eq!(TEST_COUNTER.load(Ordering::Relaxed), 2);
+        assert_eq!(RETRY_ATTEMPT.with(Cell::get), 0);
+    }
+
+    #[test]
+    fn optional_decorators() {
+        static NO_RETRY: (Timeout, Option<Retry>) = (Timeout(Duration::from_millis(100)), None);
+        static RETRY: (Timeout, Option<Retry>) =
+            (Timeout(Duration::from_millis(100)), Some(Retry::times(1)));
+        static TEST_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        let test_fn: fn() -> Result<(), &'static str> = || {
+            if TEST_COUNTER.fetch_add(1, Ordering::Relaxed) % 2 == 0 {
+                Err("oops")
+            } else {
+                Ok(())
+            }
+        };
+        NO_RETRY.decorate_and_test(test_fn).unwrap_err();
+        NO_RETRY.decorate_and_test(test_fn).unwrap();
+        RETRY.decorate_and_test(test_fn).unwrap();
+        assert_eq!(TEST_COUNTER.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn decorator_arrays_and_slices() {
+        static ORDER: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+        static DECORATORS: [FnDecorator<()>; 3] = [
+            FnDecorator::new(|test_fn| {
+                ORDER.lock().unwrap().push(0);
+                test_fn();
+            }),
+            FnDecorator::new(|test_fn| {
+                ORDER.lock().unwrap().push(1);
+                test_fn();
+            }),
+            FnDecorator::new(|test_fn| {
+                ORDER.lock().unwrap().push(2);
+                test_fn();
+            }),
+        ];
+        static SLICE: &[FnDecorator<()>] = &DECORATORS;
+
+        DECORATORS.decorate_and_test(|| ORDER.lock().unwrap().push(u32::MAX));
+        assert_eq!(*ORDER.lock().unwrap(), [2, 1, 0, u32::MAX]);
+
+        ORDER.lock().unwrap().clear();
+        SLICE.decorate_and_test(|| ORDER.lock().unwrap().push(u32::MAX));
+        assert_eq!(*ORDER.lock().unwrap(), [2, 1, 0, u32::MAX]);
+    }
+
+    #[test]
+    fn making_decorator_into_trait_object() {
+        define_test_fn!();
+
+        static DECORATORS: &dyn DecorateTestFn<Result<(), &'static str>> =
+            &(Timeout(Duration::from_millis(100)), Retry::times(2));
+
+        DECORATORS.decorate_and_test_fn(test_fn).unwrap();
+    }
+
+    #[test]
+    fn decorating_closure() {
+        define_test_fn!();
+
+        decorate!(
+            (Timeout(Duration::from_millis(100)), Retry::times(2)),
+            test_fn
+        )
+        .unwrap();
+        let output = decorate(Timeout(Duration::from_millis(100)), || 5);
+        assert_eq!(output, 5);
+    }
+
+    #[test]
+    fn parsing_truthy_env_values() {
+        assert!(is_truthy_env_value(OsStr::new("1")));
+        assert!(is_truthy_env_value(OsStr::new("true")));
+        assert!(!is_truthy_env_value(OsStr::new("0")));
+        assert!(!is_truthy_env_value(OsStr::new("")));
+    }
+
+    #[test]
+    fn running_decorated_cases() {
+        static DECORATORS: (Timeout, Retry) =
+            (Timeout(Duration::from_millis(100)), Retry::times(2));
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        let outputs = run_decorated_cases(&DECORATORS, ["1", "2", "what"], |s| {
+            COUNTER.fetch_add(1, Ordering::Relaxed);
+            s.parse::<u32>().map(drop)
+        });
+        let outputs: Vec<_> = outputs.collect();
+        assert!(outputs[0].is_ok() && outputs[1].is_ok());
+        assert!(outputs[2].is_err());
+        // The last case should be retried.
+        assert_eq!(COUNTER.load(Ordering::Relaxed), 5);
+    }
+
+    #[test]
+    fn making_sequence_into_trait_object() {
+        static SEQUENCE: Sequence = Sequence::new();
+        static DECORATORS: &dyn DecorateTestFn<()> = &(&SEQUENCE,);
+
+        run_decorated(DECORATORS, || {});
+    }
+}
@@ -0,0 +1,344 @@
+//! Simulated networks provided by the `turmoil` crate.
+
+use std::{
+    cell::RefCell,
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+use turmoil::{Builder, Sim};
+
+use super::{test_location_suffix, DecorateTest, OutputSink, ReproduceFlake, TestFn};
+
+thread_local! {
+    static CURRENT_SIM: RefCell<Option<Sim<'static>>> = const { RefCell::new(None) };
+}
+
+/// Sets the current simulation for the thread and restores the previous one on drop
+/// (including when the test panics).
+struct SimScope {
+    prev_sim: Option<Sim<'static>>,
+}
+
+impl SimScope {
+    fn new(sim: Sim<'static>) -> Self {
+        Self {
+            prev_sim: CURRENT_SIM.with(|cell| cell.borrow_mut().replace(sim)),
+        }
+    }
+
+    fn take_sim() -> Option<Sim<'static>> {
+        CURRENT_SIM.with(|cell| cell.borrow_mut().take())
+    }
+}
+
+impl Drop for SimScope {
+    fn drop(&mut self) {
+        let sim = CURRENT_SIM.with(|cell| {
+            let mut cell = cell.borrow_mut();
+            let sim = cell.take();
+            *cell = self.prev_sim.take();
+            sim
+        });
+        // Drop the simulation outside of the borrow.
+        drop(sim);
+    }
+}
+
+/// [Test decorator](DecorateTest) that runs a test in a network simulated by the [`turmoil`] crate.
+///
+/// The decorator creates a simulation configured according to the decorator params
+/// (latency, message loss, partitions, etc.). The test body registers simulated hosts and clients
+/// via [`Self::with()`]; after the test body completes successfully, the decorator runs
+/// the simulation until all clients complete, and panics if the simulation fails.
+/// Partitions specified via [`Self::with_partitions()`] are applied right before
+/// the simulation is run.
+///
+/// The simulation is seeded with the seed specified via [`Self::with_seed()`]. If it's not
+/// specified, the seed is taken from [`ReproduceFlake`] (if the decorator precedes
+/// `ReproduceFlake` in the decorator list), or is chosen randomly. The seed is printed
+/// before running the simulation and is included into the panic message on failure, so that
+/// a failing simulation can be reproduced.
+///
+/// A decorator instance is static, so it cannot be parameterized by test cases directly.
+/// To test against multiple network conditions, `NetworkSim` can be used as a case arg;
+/// in this case, the simulation is run with [`Self::run()`].
+///
+/// [`turmoil`]: https://docs.rs/turmoil/
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::NetworkSim};
+/// use std::{net::Ipv4Addr, time::Duration};
+/// use turmoil::net::UdpSocket;
+///
+/// const NETWORK: NetworkSim = NetworkSim::new()
+///     .with_latency(Duration::from_millis(5), Duration::from_millis(50))
+///     .with_seed(42);
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(NETWORK)]
+/// fn echo_server_works() {
+///     NetworkSim::with(|sim| {
+///         sim.host("server", || async {
+///             let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 9000)).await?;
+///             let mut buffer = [0; 64];
+///             loop {
+///                 let (len, addr) = socket.recv_from(&mut buffer).await?;
+///                 socket.send_to(&buffer[..len], addr).await?;
+///             }
+///         });
+///         sim.client("client", async {
+///             let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+///             socket.send_to(b"ping", ("server", 9000)).await?;
+///             let mut buffer = [0; 64];
+///             let (len, _) = socket.recv_from(&mut buffer).await?;
+///             assert_eq!(&buffer[..len], b"ping");
+///             Ok(())
+///         });
+///     });
+/// }
+/// ```
+///
+/// ## Parameterizing network conditions
+///
+/// ```
+/// use test_casing::{decorators::NetworkSim, test_casing};
+/// use std::time::Duration;
+///
+/// const NETWORKS: [NetworkSim; 2] = [
+///     NetworkSim::new(),
+///     NetworkSim::new().with_latency(Duration::from_millis(100), Duration::from_millis(500)),
+/// ];
+///
+/// #[test_casing(2, NETWORKS)]
+/// fn consensus_under_latency(network: NetworkSim) {
+///     network.run(|sim| {
+///         // Register hosts and clients
+/// #       let _ = sim;
+///     });
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "turmoil")))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NetworkSim {
+    duration: Option<Duration>,
+    latency: Option<(Duration, Duration)>,
+    fail_rate: Option<f64>,
+    seed: Option<u64>,
+    partitions: &'static [(&'static str, &'static str)],
+}
+
+impl NetworkSim {
+    /// Creates a decorator with the default `turmoil` simulation params.
+    pub const fn new() -> Self {
+        Self {
+            duration: None,
+            latency: None,
+            fail_rate: None,
+            seed: None,
+            partitions: &[],
+        }
+    }
+
+    /// Specifies the maximum duration of the simulation in simulated time.
+    #[must_use]
+    pub const fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Specifies the range of message latencies.
+    #[must_use]
+    pub const fn with_latency(mut self, min: Duration, max: Duration) -> Self {
+        self.latency = Some((min, max));
+        self
+    }
+
+    /// Specifies the probability of a network link failing on each simulation step.
+    #[must_use]
+    pub const fn with_fail_rate(mut self, fail_rate: f64) -> Self {
+        self.fail_rate = Some(fail_rate);
+        self
+    }
+
+    /// Specifies the seed for the simulation.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Specifies pairs of hosts between which the network is partitioned.
+    #[must_use]
+    pub const fn with_partitions(
+        mut self,
+        partitions: &'static [(&'static str, &'static str)],
+    ) -> Self {
+        self.partitions = partitions;
+        self
+    }
+
+    /// Performs an action with the simulation for the current test, e.g. registers hosts
+    /// and clients.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a test decorated with `NetworkSim`, or from within
+    /// the simulation.
+    pub fn with<T>(action: impl FnOnce(&mut Sim<'static>) -> T) -> T {
+        CURRENT_SIM.with(|cell| {
+            let mut sim = cell.borrow_mut();
+            let sim = sim
+                .as_mut()
+                .expect("network simulation is not set up; decorate the test with `NetworkSim`");
+            action(sim)
+        })
+    }
+
+    /// Creates a simulation, sets it up using the provided closure and runs it until
+    /// all clients complete.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the simulation fails.
+    #[track_caller]
+    pub fn run(&self, setup: impl FnOnce(&mut Sim<'_>)) {
+        let seed = self.resolve_seed();
+        let mut sim = self.build(seed);
+        setup(&mut sim);
+        self.run_sim(&mut sim, seed);
+    }
+
+    fn resolve_seed(&self) -> u64 {
+        self.seed
+            .or_else(ReproduceFlake::seed)
+            .unwrap_or_else(|| RandomState::new().build_hasher().finish())
+    }
+
+    fn build(&self, seed: u64) -> Sim<'static> {
+        let mut builder = Builder::new();
+        builder.rng_seed(seed);
+        if let Some(duration) = self.duration {
+            builder.simulation_duration(duration);
+        }
+        if let Some((min, max)) = self.latency {
+            builder.min_message_latency(min).max_message_latency(max);
+        }
+        if let Some(fail_rate) = self.fail_rate {
+            builder.fail_rate(fail_rate);
+        }
+        builder.build()
+    }
+
+    #[track_caller]
+    fn run_sim(&self, sim: &mut Sim<'_>, seed: u64) {
+        for &(first, second) in self.partitions {
+            sim.partition(first, second);
+        }
+        OutputSink::global().emit(format_args!("Running network simulation with seed {seed}"));
+        if let Err(err) = sim.run() {
+            panic!(
+                "Network simulation with seed {seed} failed{}: {err}",
+                test_location_suffix()
+            );
+        }
+    }
+
+    fn decorate_inner<R, F: TestFn<R>>(self, test_fn: F, is_ok: fn(&R) -> bool) -> R {
+        let seed = self.resolve_seed();
+        let scope = SimScope::new(self.build(seed));
+        let output = test_fn();
+        if is_ok(&output) {
+            // Take the simulation out of the thread-local cell, so that it can be accessed
+            // by the simulated hosts.
+            if let Some(mut sim) = SimScope::take_sim() {
+                self.run_sim(&mut sim, seed);
+            }
+        }
+        drop(scope);
+        output
+    }
+}
+
+impl DecorateTest<()> for NetworkSim {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        self.decorate_inner(test_fn, |()| true);
+    }
+}
+
+impl<E> DecorateTest<Result<(), E>> for NetworkSim {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.decorate_inner(test_fn, Result::is_ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::Ipv4Addr, panic};
+
+    use turmoil::net::UdpSocket;
+
+    use super::*;
+    use crate::decorators::extract_panic_str;
+
+    fn echo_server_and_client(sim: &mut Sim<'_>) {
+        sim.host("server", || async {
+            let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 9000)).await?;
+            let mut buffer = [0; 64];
+            loop {
+                let (len, addr) = socket.recv_from(&mut buffer).await?;
+                socket.send_to(&buffer[..len], addr).await?;
+            }
+        });
+        sim.client("client", async {
+            let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+            socket.send_to(b"ping", ("server", 9000)).await?;
+            let mut buffer = [0; 64];
+            let (len, _) = socket.recv_from(&mut buffer).await?;
+            assert_eq!(&buffer[..len], b"ping");
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn running_simulation() {
+        static DECORATOR: NetworkSim = NetworkSim::new()
+            .with_latency(Duration::from_millis(1), Duration::from_millis(10))
+            .with_seed(123);
+
+        let test_fn: fn() = || NetworkSim::with(echo_server_and_client);
+        DECORATOR.decorate_and_test(test_fn);
+        CURRENT_SIM.with(|cell| assert!(cell.borrow().is_none()));
+    }
+
+    #[test]
+    fn failing_simulation_with_partition() {
+        static DECORATOR: NetworkSim = NetworkSim::new()
+            .with_duration(Duration::from_secs(1))
+            .with_partitions(&[("client", "server")])
+            .with_seed(123);
+
+        let test_fn: fn() = || NetworkSim::with(echo_server_and_client);
+        let panic_object =
+            panic::catch_unwind(|| DECORATOR.decorate_and_test(test_fn)).unwrap_err();
+        let message = extract_panic_str(&*panic_object).unwrap();
+        assert!(
+            message.starts_with("Network simulation with seed 123 failed"),
+            "{message}"
+        );
+        CURRENT_SIM.with(|cell| assert!(cell.borrow().is_none()));
+    }
+
+    #[test]
+    fn running_simulation_as_case_arg() {
+        let network = NetworkSim::new().with_seed(1);
+        network.run(echo_server_and_client);
+    }
+}
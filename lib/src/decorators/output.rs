@@ -0,0 +1,392 @@
+//! Limiting and checking output of decorated tests.
+
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    env,
+    fmt::Write as _,
+    io::{self, Write as _},
+};
+
+use super::{test_location_suffix, DecorateTest, TestFn};
+
+/// Name of the env variable instructing a child process spawned by
+/// [`ForkedProcess`](super::ForkedProcess) to mark the output produced by the test.
+pub(crate) const MARK_OUTPUT_ENV_VAR: &str = "TEST_CASING_MARK_OUTPUT";
+const OUTPUT_START_MARKER: &str = "<test-casing: output start>";
+const OUTPUT_END_MARKER: &str = "<test-casing: output end>";
+
+thread_local! {
+    static OUTPUT_BUDGET: Cell<Option<OutputBudget>> = const { Cell::new(None) };
+    static TEST_OUTPUT: RefCell<Option<TestOutput>> = const { RefCell::new(None) };
+}
+
+/// Remaining number of output bytes for the current test.
+#[derive(Debug, Clone, Copy)]
+struct OutputBudget {
+    remaining: usize,
+    is_used: bool,
+}
+
+impl OutputBudget {
+    fn take<'a>(&mut self, output: &'a [u8]) -> Cow<'a, str> {
+        self.is_used = true;
+        let output = String::from_utf8_lossy(output);
+        if output.len() <= self.remaining {
+            self.remaining -= output.len();
+            return output;
+        }
+
+        let mut cut_at = self.remaining;
+        while !output.is_char_boundary(cut_at) {
+            cut_at -= 1;
+        }
+        let truncated_len = output.len() - cut_at;
+        self.remaining = 0;
+        let notice = format!("\n[{truncated_len} bytes truncated by `TruncateOutput`]\n");
+        Cow::Owned(output[..cut_at].to_owned() + &notice)
+    }
+}
+
+/// Sets the output budget for the current thread and restores the previous one on drop.
+#[derive(Debug)]
+struct BudgetGuard {
+    prev_budget: Option<OutputBudget>,
+}
+
+impl BudgetGuard {
+    fn new(max_bytes: usize) -> Self {
+        let budget = OutputBudget {
+            remaining: max_bytes,
+            is_used: false,
+        };
+        Self {
+            prev_budget: OUTPUT_BUDGET.with(|cell| cell.replace(Some(budget))),
+        }
+    }
+}
+
+impl Drop for BudgetGuard {
+    fn drop(&mut self) {
+        OUTPUT_BUDGET.with(|cell| cell.set(self.prev_budget));
+    }
+}
+
+fn is_budget_used() -> bool {
+    OUTPUT_BUDGET.with(|cell| cell.get().is_some_and(|budget| budget.is_used))
+}
+
+/// Output produced by the test in a child process, recorded for [`ExpectNoOutput`].
+#[derive(Debug, Default)]
+struct TestOutput {
+    is_used: bool,
+    stdout: String,
+    stderr: String,
+}
+
+/// Starts recording test output for the current thread and restores the previous state on drop.
+#[derive(Debug)]
+struct TestOutputGuard {
+    prev_output: Option<TestOutput>,
+}
+
+impl TestOutputGuard {
+    fn new() -> Self {
+        Self {
+            prev_output: TEST_OUTPUT.with(|cell| cell.replace(Some(TestOutput::default()))),
+        }
+    }
+
+    fn take_output() -> TestOutput {
+        TEST_OUTPUT.with(|cell| cell.borrow_mut().take().unwrap_or_default())
+    }
+}
+
+impl Drop for TestOutputGuard {
+    fn drop(&mut self) {
+        TEST_OUTPUT.with(|cell| *cell.borrow_mut() = self.prev_output.take());
+    }
+}
+
+/// Checks whether the output of the test in a child process should be marked.
+pub(crate) fn should_mark_output() -> bool {
+    TEST_OUTPUT.with(|cell| cell.borrow().is_some())
+}
+
+/// Runs a test in a child process, marking its output if requested by the parent process.
+pub(crate) fn with_output_markers<R>(test_fn: impl FnOnce() -> R) -> R {
+    if env::var_os(MARK_OUTPUT_ENV_VAR).is_none() {
+        return test_fn();
+    }
+    print_markers(OUTPUT_START_MARKER);
+    let output = test_fn();
+    print_markers(OUTPUT_END_MARKER);
+    output
+}
+
+fn print_markers(marker: &str) {
+    // Flush streams so that the marker is correctly ordered with the test output.
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "{marker}")
+        .and_then(|()| stdout.flush())
+        .ok();
+    let mut stderr = io::stderr().lock();
+    writeln!(stderr, "{marker}")
+        .and_then(|()| stderr.flush())
+        .ok();
+}
+
+/// Splits the output into the part between markers (i.e., produced by the test), and the output
+/// with marker lines removed. Returns `None` if the output isn't marked.
+fn split_marked_output(output: &str) -> Option<(&str, String)> {
+    let start_marker = format!("{OUTPUT_START_MARKER}\n");
+    let start = output.find(&start_marker)?;
+    let (before, rest) = (&output[..start], &output[start + start_marker.len()..]);
+    let end_marker = format!("{OUTPUT_END_MARKER}\n");
+    // The end marker may be missing if the test has panicked.
+    let (marked, after) = rest.find(&end_marker).map_or((rest, ""), |end| {
+        (&rest[..end], &rest[end + end_marker.len()..])
+    });
+    Some((marked, format!("{before}{marked}{after}")))
+}
+
+/// Records the test output if it's marked, and removes markers from it.
+fn record_output(output: Cow<'_, str>, record: impl FnOnce(&str)) -> Cow<'_, str> {
+    if let Some((marked, stripped)) = split_marked_output(&output) {
+        record(marked);
+        Cow::Owned(stripped)
+    } else {
+        output
+    }
+}
+
+/// Forwards output of a child process via `print!` / `eprint!` so that it's captured
+/// by the test harness, truncating it according to the [`TruncateOutput`] budget if necessary.
+/// If the output is marked, the output produced by the test is recorded for [`ExpectNoOutput`].
+pub(crate) fn forward_output(stdout: &[u8], stderr: &[u8]) {
+    let (stdout, stderr) = TEST_OUTPUT.with(|cell| {
+        let mut test_output = cell.borrow_mut();
+        let stdout = String::from_utf8_lossy(stdout);
+        let stderr = String::from_utf8_lossy(stderr);
+        let Some(test_output) = test_output.as_mut() else {
+            return (stdout, stderr);
+        };
+        test_output.is_used = true;
+        let stdout = record_output(stdout, |marked| test_output.stdout.push_str(marked));
+        let stderr = record_output(stderr, |marked| test_output.stderr.push_str(marked));
+        (stdout, stderr)
+    });
+    let (stdout, stderr) = (stdout.as_bytes(), stderr.as_bytes());
+
+    OUTPUT_BUDGET.with(|cell| {
+        let mut budget = cell.get();
+        let (stdout, stderr) = if let Some(budget) = &mut budget {
+            (budget.take(stdout), budget.take(stderr))
+        } else {
+            (
+                String::from_utf8_lossy(stdout),
+                String::from_utf8_lossy(stderr),
+            )
+        };
+        cell.set(budget);
+        print!("{stdout}");
+        eprint!("{stderr}");
+    });
+}
+
+/// [Test decorator](DecorateTest) that caps the combined size of stdout and stderr output
+/// emitted by the test to the specified number of bytes. Excess output is truncated
+/// with a notice. This is useful to prevent chatty failing tests (especially combined
+/// with [retries](super::Retry)) from producing huge CI logs.
+///
+/// # Limitations
+///
+/// The standard test harness doesn't provide a stable way to intercept output of a test
+/// running in the same process. Thus, `TruncateOutput` only applies to output of tests
+/// running in a [`ForkedProcess`](super::ForkedProcess), and it must follow `ForkedProcess`
+/// in the decorator list (with no [`Timeout`](super::Timeout) between them). If this is not
+/// the case, the decorator has no effect and prints a warning to stderr.
+///
+/// The output budget is shared among all child processes spawned during a single invocation
+/// of the decorator. For example, if `TruncateOutput` follows [`Retry`](super::Retry)
+/// in the decorator list, the budget applies to all retry attempts combined.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{ForkedProcess, Retry, TruncateOutput}};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(ForkedProcess::new(), Retry::times(3), TruncateOutput(64 * 1_024))]
+/// fn chatty_test() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct TruncateOutput(pub usize);
+
+impl<R> DecorateTest<R> for TruncateOutput {
+    fn decorate_and_test<F: TestFn<R>>(&self, test_fn: F) -> R {
+        let _guard = BudgetGuard::new(self.0);
+        let output = test_fn();
+        if !is_budget_used() {
+            eprintln!(
+                "`TruncateOutput` has no effect since the test is not run in a `ForkedProcess`"
+            );
+        }
+        output
+    }
+}
+
+/// [Test decorator](DecorateTest) that fails the test if it writes anything to stdout or stderr.
+/// The unexpected output is included into the panic message. This can be used to enforce
+/// that the tested library code doesn't print, e.g. leftover debugging output.
+///
+/// # Limitations
+///
+/// Similarly to [`TruncateOutput`], `ExpectNoOutput` only applies to tests running
+/// in a [`ForkedProcess`](super::ForkedProcess), and it must follow `ForkedProcess`
+/// in the decorator list. If this is not the case, the decorator has no effect and prints
+/// a warning to stderr. Output produced by decorators applied in the child process
+/// (i.e., preceding `ForkedProcess` in the decorator list) counts as the test output.
+///
+/// If the test fails in the child process, the failure is propagated without checking
+/// the output.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{ExpectNoOutput, ForkedProcess}};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(ForkedProcess::new(), ExpectNoOutput)]
+/// fn library_code_is_silent() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExpectNoOutput;
+
+impl ExpectNoOutput {
+    fn check(output: &TestOutput) {
+        if output.stdout.is_empty() && output.stderr.is_empty() {
+            return;
+        }
+
+        let mut message = format!(
+            "Test is expected to produce no output{}, but it wrote:",
+            test_location_suffix()
+        );
+        for (stream, text) in [("stdout", &output.stdout), ("stderr", &output.stderr)] {
+            if !text.is_empty() {
+                write!(message, "\n--- {stream} ---\n{text}").unwrap();
+            }
+        }
+        panic!("{message}");
+    }
+}
+
+impl<R> DecorateTest<R> for ExpectNoOutput {
+    fn decorate_and_test<F: TestFn<R>>(&self, test_fn: F) -> R {
+        let guard = TestOutputGuard::new();
+        let output = test_fn();
+        let test_output = TestOutputGuard::take_output();
+        drop(guard);
+
+        if test_output.is_used {
+            Self::check(&test_output);
+        } else {
+            eprintln!(
+                "`ExpectNoOutput` has no effect since the test is not run in a `ForkedProcess`"
+            );
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncating_output() {
+        let mut budget = OutputBudget {
+            remaining: 10,
+            is_used: false,
+        };
+        assert_eq!(budget.take(b"hello"), "hello");
+        assert_eq!(budget.remaining, 5);
+        assert!(budget.is_used);
+
+        let output = budget.take("wörld and more".as_bytes());
+        assert_eq!(output, "wörl\n[10 bytes truncated by `TruncateOutput`]\n");
+        assert_eq!(budget.remaining, 0);
+        assert_eq!(budget.take(b""), "");
+    }
+
+    #[test]
+    fn truncating_output_at_char_boundary() {
+        let mut budget = OutputBudget {
+            remaining: 2,
+            is_used: false,
+        };
+        let output = budget.take("aö".as_bytes());
+        assert_eq!(output, "a\n[2 bytes truncated by `TruncateOutput`]\n");
+    }
+
+    #[test]
+    fn budget_is_scoped() {
+        static DECORATOR: TruncateOutput = TruncateOutput(100);
+
+        DECORATOR.decorate_and_test(|| {
+            let budget = OUTPUT_BUDGET.with(Cell::get).unwrap();
+            assert_eq!(budget.remaining, 100);
+            forward_output(b"test", b"");
+            let budget = OUTPUT_BUDGET.with(Cell::get).unwrap();
+            assert_eq!(budget.remaining, 96);
+        });
+        assert!(OUTPUT_BUDGET.with(Cell::get).is_none());
+    }
+
+    #[test]
+    fn splitting_marked_output() {
+        let output =
+            format!("\nrunning 1 test\n{OUTPUT_START_MARKER}\nHello\n{OUTPUT_END_MARKER}\n.\n");
+        let (marked, stripped) = split_marked_output(&output).unwrap();
+        assert_eq!(marked, "Hello\n");
+        assert_eq!(stripped, "\nrunning 1 test\nHello\n.\n");
+
+        let output = format!("{OUTPUT_START_MARKER}\n{OUTPUT_END_MARKER}\n");
+        let (marked, stripped) = split_marked_output(&output).unwrap();
+        assert_eq!(marked, "");
+        assert_eq!(stripped, "");
+
+        let output = format!("{OUTPUT_START_MARKER}\npanicked");
+        let (marked, _) = split_marked_output(&output).unwrap();
+        assert_eq!(marked, "panicked");
+
+        assert!(split_marked_output("running 1 test\n").is_none());
+    }
+
+    #[test]
+    fn recording_test_output() {
+        let guard = TestOutputGuard::new();
+        assert!(should_mark_output());
+        let stdout = format!("running 1 test\n{OUTPUT_START_MARKER}\n{OUTPUT_END_MARKER}\n.\n");
+        let stderr = format!("{OUTPUT_START_MARKER}\nwarning!\n{OUTPUT_END_MARKER}\n");
+        forward_output(stdout.as_bytes(), stderr.as_bytes());
+        let test_output = TestOutputGuard::take_output();
+        drop(guard);
+        assert!(!should_mark_output());
+
+        assert!(test_output.is_used);
+        assert_eq!(test_output.stdout, "");
+        assert_eq!(test_output.stderr, "warning!\n");
+        let err = std::panic::catch_unwind(|| ExpectNoOutput::check(&test_output)).unwrap_err();
+        let message = err.downcast_ref::<String>().unwrap();
+        assert!(message.contains("--- stderr ---\nwarning!"), "{message}");
+        assert!(!message.contains("stdout"), "{message}");
+    }
+}
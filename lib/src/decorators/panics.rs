@@ -0,0 +1,273 @@
+//! Panic hook customization for decorated tests.
+
+use std::{
+    any::Any,
+    backtrace::Backtrace as StdBacktrace,
+    cell::{Cell, RefCell},
+    fmt::Write as _,
+    panic::{self, Location},
+    sync::Once,
+    thread::LocalKey,
+};
+
+use super::{extract_panic_str, DecorateTest, OutputSink, TestFn};
+
+thread_local! {
+    static REPORT_PANICS: Cell<bool> = const { Cell::new(false) };
+    static CAPTURE_BACKTRACES: Cell<bool> = const { Cell::new(false) };
+    static CAPTURED_BACKTRACE: RefCell<Option<StdBacktrace>> = const { RefCell::new(None) };
+}
+
+/// Installs the panic hook handling panics in decorated tests. The hook is installed once
+/// per process; panics in other tests are delegated to the previously installed hook.
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        #[cfg(feature = "color-eyre")]
+        let report_hook = {
+            let (panic_hook, eyre_hook) = color_eyre::config::HookBuilder::default().into_hooks();
+            // Installing the `eyre` hook fails if it is already installed, which is fine.
+            eyre_hook.install().ok();
+            panic_hook
+        };
+
+        let prev_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if CAPTURE_BACKTRACES.with(Cell::get) {
+                let backtrace = StdBacktrace::force_capture();
+                CAPTURED_BACKTRACE.with(|cell| *cell.borrow_mut() = Some(backtrace));
+            }
+
+            if REPORT_PANICS.with(Cell::get) {
+                #[cfg(feature = "color-eyre")]
+                eprintln!("{}", report_hook.panic_report(info));
+                #[cfg(not(feature = "color-eyre"))]
+                eprintln!("{}", PanicReport::report(info.payload(), info.location()));
+            } else {
+                prev_hook(info);
+            }
+        }));
+    });
+}
+
+/// Sets a thread-local flag for the panic hook until dropped.
+#[derive(Debug)]
+struct FlagGuard {
+    flag: &'static LocalKey<Cell<bool>>,
+    prev_value: bool,
+}
+
+impl FlagGuard {
+    fn new(flag: &'static LocalKey<Cell<bool>>) -> Self {
+        Self {
+            flag,
+            prev_value: flag.with(|flag| flag.replace(true)),
+        }
+    }
+}
+
+impl Drop for FlagGuard {
+    fn drop(&mut self) {
+        self.flag.with(|flag| flag.set(self.prev_value));
+    }
+}
+
+/// [Test decorator](DecorateTest) that prints a detailed report for panics in the wrapped test.
+///
+/// The report includes the panic message, its location and a backtrace, which is captured
+/// regardless of the `RUST_BACKTRACE` env variable. If the `color-eyre` crate feature
+/// is enabled, the report is produced by the [`color-eyre`] panic hook instead;
+/// the `color-eyre` error hook is installed for `eyre::Report`s as well.
+///
+/// Span traces are not captured, even if the `tracing` crate feature is enabled. Capturing them
+/// would require `color-eyre` built with its `capture-spantrace` feature and the `tracing-error`
+/// layer installed in the subscriber used by the test. This crate doesn't depend on `tracing-error`
+/// and doesn't modify subscribers installed by the test (the subscriber built by `LogLevel`
+/// doesn't include the layer either), so reports only contain the panic location and backtrace.
+///
+/// The panic hook is installed once per process on the first use of the decorator.
+/// Panics in tests not decorated with `PanicReport` are handled by the previously installed
+/// panic hook (e.g., the default one), so that their output remains unchanged.
+///
+/// Panic reporting is enabled for the thread executing the decorator. Hence, if the test
+/// is decorated with a [`Timeout`](super::Timeout), `PanicReport` should be placed *before*
+/// the timeout in the decorator list, so that it wraps the test on the same thread.
+///
+/// [`color-eyre`]: https://docs.rs/color-eyre/
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{PanicReport, Timeout}};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(PanicReport, Timeout::secs(5))]
+/// fn test_with_panic_report() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PanicReport;
+
+impl PanicReport {
+    #[cfg_attr(feature = "color-eyre", allow(dead_code))]
+    fn report(payload: &(dyn Any + Send), location: Option<&Location<'_>>) -> String {
+        let mut report = String::from("Test panicked");
+        if let Some(location) = location {
+            write!(report, " at {location}").unwrap();
+        }
+        let message = extract_panic_str(payload).unwrap_or("(non-string panic payload)");
+        let backtrace = StdBacktrace::force_capture();
+        write!(report, ":\n{message}\n\nBacktrace:\n{backtrace}").unwrap();
+        report
+    }
+}
+
+impl<R> DecorateTest<R> for PanicReport {
+    fn decorate_and_test<F: TestFn<R>>(&self, test_fn: F) -> R {
+        install_panic_hook();
+        let _guard = FlagGuard::new(&REPORT_PANICS);
+        test_fn()
+    }
+}
+
+/// [Test decorator](DecorateTest) that captures a backtrace if the wrapped test panics,
+/// and prints it only if the panic is propagated from the decorator (i.e., the test fails).
+///
+/// The backtrace is captured regardless of the `RUST_BACKTRACE` env variable. This keeps
+/// the output of passing tests clean, while making failures actionable (e.g., on CI, where
+/// setting `RUST_BACKTRACE` globally can produce a lot of noise). The backtrace is emitted
+/// to the [global sink](OutputSink::global()) unless another sink is
+/// [specified](Self::with_sink()).
+///
+/// Like [`PanicReport`], this decorator uses a panic hook installed once per process,
+/// and only captures backtraces on the thread executing the decorator. If the test
+/// is decorated with a [`Timeout`](super::Timeout), `Backtrace` should be placed *before*
+/// the timeout in the decorator list.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{Backtrace, Retry}};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(Backtrace::new(), Retry::times(2))]
+/// // ^ The backtrace will be printed for each failed attempt
+/// fn test_with_backtrace() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Backtrace {
+    sink: Option<OutputSink>,
+}
+
+impl Backtrace {
+    /// Creates a decorator emitting backtraces to the [global sink](OutputSink::global()).
+    pub const fn new() -> Self {
+        Self { sink: None }
+    }
+
+    /// Specifies the sink for captured backtraces. If not specified,
+    /// the [global sink](OutputSink::global()) is used.
+    #[must_use]
+    pub const fn with_sink(self, sink: OutputSink) -> Self {
+        Self { sink: Some(sink) }
+    }
+
+    fn take_captured() -> Option<StdBacktrace> {
+        CAPTURED_BACKTRACE.with(|cell| cell.borrow_mut().take())
+    }
+}
+
+impl<R> DecorateTest<R> for Backtrace {
+    fn decorate_and_test<F: TestFn<R>>(&self, test_fn: F) -> R {
+        install_panic_hook();
+        let guard = FlagGuard::new(&CAPTURE_BACKTRACES);
+        Self::take_captured(); // remove a stale backtrace, if any
+        let output = panic::catch_unwind(test_fn);
+        drop(guard);
+
+        output.unwrap_or_else(|panic_object| {
+            if let Some(backtrace) = Self::take_captured() {
+                OutputSink::resolve(self.sink)
+                    .emit(format_args!("Backtrace for the test panic:\n{backtrace}"));
+            }
+            panic::resume_unwind(panic_object);
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{backtrace::BacktraceStatus, sync::Mutex};
+
+    use super::*;
+
+    #[test]
+    fn flag_guard_restores_flag() {
+        assert!(!REPORT_PANICS.with(Cell::get));
+        {
+            let _guard = FlagGuard::new(&REPORT_PANICS);
+            assert!(REPORT_PANICS.with(Cell::get));
+            {
+                let _inner_guard = FlagGuard::new(&REPORT_PANICS);
+                assert!(REPORT_PANICS.with(Cell::get));
+            }
+            assert!(REPORT_PANICS.with(Cell::get));
+        }
+        assert!(!REPORT_PANICS.with(Cell::get));
+    }
+
+    #[test]
+    fn decorating_panicking_test() {
+        static DECORATOR: PanicReport = PanicReport;
+
+        let test_fn: fn() = || panic!("oops");
+        let panic_object =
+            panic::catch_unwind(|| DECORATOR.decorate_and_test(test_fn)).unwrap_err();
+        assert_eq!(extract_panic_str(&*panic_object), Some("oops"));
+        assert!(!REPORT_PANICS.with(Cell::get));
+    }
+
+    #[test]
+    fn capturing_backtrace() {
+        static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+        static DECORATOR: Backtrace = Backtrace::new().with_sink(OutputSink::Custom(|message| {
+            MESSAGES.lock().unwrap().push(message.to_owned());
+        }));
+
+        install_panic_hook();
+        let guard = FlagGuard::new(&CAPTURE_BACKTRACES);
+        panic::catch_unwind(|| panic!("oops")).unwrap_err();
+        drop(guard);
+        let backtrace = Backtrace::take_captured().unwrap();
+        assert_eq!(backtrace.status(), BacktraceStatus::Captured);
+
+        let test_fn: fn() = || panic!("oops");
+        let panic_object =
+            panic::catch_unwind(|| DECORATOR.decorate_and_test(test_fn)).unwrap_err();
+        assert_eq!(extract_panic_str(&*panic_object), Some("oops"));
+        assert!(Backtrace::take_captured().is_none());
+        assert!(!CAPTURE_BACKTRACES.with(Cell::get));
+
+        let messages = MESSAGES.lock().unwrap();
+        assert_eq!(messages.len(), 1, "{messages:?}");
+        assert!(
+            messages[0].starts_with("Backtrace for the test panic:"),
+            "{messages:?}"
+        );
+    }
+
+    #[cfg(not(feature = "color-eyre"))]
+    #[test]
+    fn panic_report() {
+        let location = Location::caller();
+        let report = PanicReport::report(&"oops", Some(location));
+        let expected_start = format!("Test panicked at {location}:\noops\n\nBacktrace:\n");
+        assert!(report.starts_with(&expected_start), "{report}");
+    }
+}
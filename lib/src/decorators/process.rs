@@ -0,0 +1,210 @@
+//! Process isolation for decorated tests.
+
+use std::{
+    env,
+    process::{Command, ExitStatus},
+    thread,
+};
+
+use super::{
+    output::{self, forward_output, MARK_OUTPUT_ENV_VAR},
+    DecorateTest, TestFn,
+};
+
+/// Name of the env variable set for the child process spawned by [`ForkedProcess`].
+/// The value of the variable is the name of the test executed in the child.
+const CHILD_ENV_VAR: &str = "TEST_CASING_FORKED_TEST";
+
+/// Checks whether the current test is executed in a child process spawned by [`ForkedProcess`].
+pub(crate) fn is_forked_child() -> bool {
+    let Some(test_name) = current_test_name() else {
+        return false;
+    };
+    env::var_os(CHILD_ENV_VAR).is_some_and(|var| var == *test_name)
+}
+
+/// Returns the name of the currently executing test. This relies on the standard test harness
+/// naming test threads after the executed test.
+pub(crate) fn current_test_name() -> Option<String> {
+    let current_thread = thread::current();
+    let name = current_thread.name()?;
+    (name != "main").then(|| name.to_owned())
+}
+
+/// [Test decorator](DecorateTest) that runs the decorated test in a child process.
+///
+/// The child process re-executes the current test binary with a filter matching
+/// just the decorated test. The output of the child process is captured and forwarded
+/// to the parent test (optionally truncated with [`TruncateOutput`](super::TruncateOutput)
+/// or checked with [`ExpectNoOutput`](super::ExpectNoOutput)),
+/// and the child exit status determines the parent test outcome.
+///
+/// Besides isolating global state mutated by the test, this allows testing code that aborts
+/// the process (e.g., via [`process::abort()`](std::process::abort()) or a segfault),
+/// which would otherwise crash the entire test binary. Such an abort is reported as a panic
+/// in the parent process mentioning the exit status of the child (e.g., the terminating signal
+/// on Unix).
+///
+/// # Splitting decorators between processes
+///
+/// If `ForkedProcess` is a part of a decorator tuple (e.g., in a [`decorate`](crate::decorate)
+/// attribute with multiple decorators), decorators preceding it in the tuple (i.e., ones wrapped
+/// by `ForkedProcess`) are applied in the child process only, and decorators following it
+/// are applied in the parent process only. For example,
+/// with `#[decorate(Timeout::secs(5), ForkedProcess::new(), Retry::times(2))]`,
+/// each retry attempt spawns a new child process, and the timeout is enforced inside the child.
+///
+/// The split only applies within a single decorator list; if there are multiple `decorate`
+/// attributes on a test, decorators in the outer attributes are applied in both processes.
+///
+/// # Limitations
+///
+/// - The test must run on a thread named after the test, which is what the standard
+///   test harness does. The decorator will panic if it cannot determine the test name.
+/// - `#[should_panic]` is not supported; a panic in the child process leads to a panic
+///   in the parent with a message different from the original one.
+/// - If a test returns a `Result`, an error in the child process leads to a panic
+///   in the parent (the error value cannot be transferred across processes).
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{ForkedProcess, Retry, Timeout}};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(Timeout::secs(5), ForkedProcess::new(), Retry::times(1))]
+/// fn test_mutating_global_state() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ForkedProcess {
+    _private: (),
+}
+
+impl ForkedProcess {
+    /// Creates a decorator.
+    pub const fn new() -> Self {
+        Self { _private: () }
+    }
+
+    fn decorate_inner<R, F: TestFn<R>>(test_fn: F, ok_value: R) -> R {
+        if is_forked_child() {
+            return output::with_output_markers(test_fn);
+        }
+
+        let test_name = current_test_name().unwrap_or_else(|| {
+            panic!(
+                "Cannot determine the test name; `ForkedProcess` must be used in tests \
+                 executed by the standard test harness"
+            );
+        });
+        let status = Self::run_child(&test_name);
+        if status.success() {
+            ok_value
+        } else {
+            panic!("Test `{test_name}` failed in a child process ({status})");
+        }
+    }
+
+    fn run_child(test_name: &str) -> ExitStatus {
+        let executable = env::current_exe().unwrap_or_else(|err| {
+            panic!("Cannot determine the test executable: {err}");
+        });
+        let mut command = Command::new(executable);
+        command
+            .args([test_name, "--exact", "--include-ignored", "--nocapture"])
+            .args(["--test-threads=1", "--quiet"])
+            .env(CHILD_ENV_VAR, test_name);
+        if output::should_mark_output() {
+            command.env(MARK_OUTPUT_ENV_VAR, "1");
+        }
+        let output = command.output().unwrap_or_else(|err| {
+            panic!("Failed spawning a child process for test `{test_name}`: {err}");
+        });
+
+        forward_output(&output.stdout, &output.stderr);
+        output.status
+    }
+}
+
+impl DecorateTest<()> for ForkedProcess {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        Self::decorate_inner(test_fn, ());
+    }
+
+    fn is_process_boundary(&self) -> bool {
+        true
+    }
+}
+
+impl<E: 'static> DecorateTest<Result<(), E>> for ForkedProcess {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        Self::decorate_inner(test_fn, Ok(()))
+    }
+
+    fn is_process_boundary(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic;
+
+    use super::*;
+    use crate::decorators::extract_panic_str;
+
+    #[test]
+    fn test_name_is_determined() {
+        let test_name = current_test_name().unwrap();
+        assert!(
+            test_name.ends_with("tests::test_name_is_determined"),
+            "{test_name}"
+        );
+    }
+
+    #[test]
+    fn running_test_in_child_process() {
+        static DECORATOR: ForkedProcess = ForkedProcess::new();
+
+        DECORATOR.decorate_and_test(|| assert!(is_forked_child()));
+    }
+
+    #[test]
+    fn failing_test_in_child_process() {
+        static DECORATOR: ForkedProcess = ForkedProcess::new();
+
+        let test_fn: fn() = || panic!("oops");
+        let result = panic::catch_unwind(|| DECORATOR.decorate_and_test(test_fn));
+        if is_forked_child() {
+            // Propagate the panic to the parent process.
+            panic::resume_unwind(result.unwrap_err());
+        }
+
+        let panic_object = result.unwrap_err();
+        let panic_str = extract_panic_str(&*panic_object).unwrap();
+        assert!(
+            panic_str.contains("failed in a child process"),
+            "{panic_str}"
+        );
+    }
+
+    #[test]
+    fn aborting_test_in_child_process() {
+        static DECORATOR: ForkedProcess = ForkedProcess::new();
+
+        let test_fn: fn() = || std::process::abort();
+        let panic_object =
+            panic::catch_unwind(|| DECORATOR.decorate_and_test(test_fn)).unwrap_err();
+        let panic_str = extract_panic_str(&*panic_object).unwrap();
+        assert!(
+            panic_str.contains("failed in a child process"),
+            "{panic_str}"
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! Preset bundles of [test decorators](super::DecorateTest) with sensible defaults.
+//!
+//! Profiles allow standardizing test decoration across a workspace using a single decorator,
+//! e.g. `#[decorate(profiles::integration())]`. Each profile can be tweaked via builder methods,
+//! or converted into a tuple of its constituent decorators.
+//!
+//! # Examples
+//!
+//! ```
+//! use test_casing::{decorate, decorators::{profiles, Sequence}};
+//! use std::time::Duration;
+//!
+//! // Profile shared across the workspace.
+//! const INTEGRATION: profiles::Integration = profiles::integration()
+//!     .with_timeout(Duration::from_secs(10))
+//!     .with_retries(3);
+//! static SEQUENCE: Sequence = Sequence::new();
+//!
+//! #[test]
+//! # fn eat_test_attribute() {}
+//! #[decorate(profiles::integration())]
+//! fn integration_test() {
+//!     // test logic
+//! }
+//!
+//! #[test]
+//! # fn eat_test_attribute2() {}
+//! #[decorate(INTEGRATION, &SEQUENCE)]
+//! fn sequential_integration_test() {
+//!     // test logic
+//! }
+//! ```
+
+use std::time::Duration;
+
+use super::{Backtrace, DecorateTest, Retry, TestFn, Timeout};
+
+/// Returns the [`Integration`] profile with default settings.
+pub const fn integration() -> Integration {
+    Integration::DEFAULT
+}
+
+/// Profile for integration tests, which may be slow and flaky because of the external
+/// infrastructure they depend on.
+///
+/// The profile consists of the following decorators:
+///
+/// - [`Backtrace`], so that a backtrace is printed if the test fails
+/// - [`Timeout`] for each test attempt; 60 seconds by default
+/// - [`Retry`]; 2 retries without a delay by default
+///
+/// Constructed using [`integration()`].
+#[derive(Debug)]
+pub struct Integration {
+    decorators: (Backtrace, Timeout, Retry),
+}
+
+impl Integration {
+    const DEFAULT: Self = Self {
+        decorators: (Backtrace::new(), Timeout::secs(60), Retry::times(2)),
+    };
+
+    /// Sets the timeout for each test attempt.
+    #[must_use]
+    pub const fn with_timeout(self, timeout: Duration) -> Self {
+        let (backtrace, _, retry) = self.decorators;
+        Self {
+            decorators: (backtrace, Timeout(timeout), retry),
+        }
+    }
+
+    /// Sets the number of retries. Retries are disabled if `retries` is 0.
+    #[must_use]
+    pub const fn with_retries(self, retries: usize) -> Self {
+        let (backtrace, timeout, retry) = self.decorators;
+        Self {
+            decorators: (
+                backtrace,
+                timeout,
+                Retry {
+                    times: retries,
+                    ..retry
+                },
+            ),
+        }
+    }
+
+    /// Sets the delay between retries.
+    #[must_use]
+    pub const fn with_retry_delay(self, delay: Duration) -> Self {
+        let (backtrace, timeout, retry) = self.decorators;
+        Self {
+            decorators: (backtrace, timeout, retry.with_delay(delay)),
+        }
+    }
+
+    /// Converts this profile into a tuple of decorators, e.g. to extend it with other decorators.
+    pub const fn into_decorators(self) -> (Backtrace, Timeout, Retry) {
+        self.decorators
+    }
+}
+
+impl<R> DecorateTest<R> for Integration
+where
+    (Backtrace, Timeout, Retry): DecorateTest<R>,
+{
+    fn decorate_and_test<F: TestFn<R>>(&'static self, test_fn: F) -> R {
+        self.decorators.decorate_and_test(test_fn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        panic,
+        sync::atomic::{AtomicU32, Ordering},
+        thread,
+    };
+
+    use super::*;
+
+    #[test]
+    fn integration_profile_retries_timeouts() {
+        static PROFILE: Integration = integration()
+            .with_timeout(Duration::from_millis(100))
+            .with_retries(1);
+        static TEST_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+        let test_fn: fn() = || {
+            if TEST_COUNTER.fetch_add(1, Ordering::Relaxed) == 0 {
+                thread::sleep(Duration::from_secs(1));
+            }
+        };
+        PROFILE.decorate_and_test(test_fn);
+        assert_eq!(TEST_COUNTER.load(Ordering::Relaxed), 2);
+
+        let test_fn: fn() = || panic!("oops");
+        panic::catch_unwind(|| PROFILE.decorate_and_test(test_fn)).unwrap_err();
+    }
+}
@@ -0,0 +1,248 @@
+//! Detailed reporting for errors returned by tests.
+
+use std::{
+    backtrace::{Backtrace, BacktraceStatus},
+    error::Error,
+    fmt::{self, Write as _},
+    io,
+};
+
+use super::{DecorateTest, TestFn};
+
+/// Error that can be reported by the [`ErrorReport`] decorator.
+///
+/// This trait is implemented for boxed standard errors, [`io::Error`], `String`s and,
+/// if the corresponding crate features are enabled, `anyhow::Error` and `eyre::Report`.
+/// It can be implemented for custom error types as well. Error types not implementing
+/// the standard [`Error`] trait (e.g., errors from assertion frameworks) can use
+/// the default implementation, in which case the error is reported using its [`Debug`](fmt::Debug)
+/// implementation.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::decorators::ReportError;
+///
+/// #[derive(Debug)]
+/// struct AssertionFailure {
+///     expected: String,
+///     actual: String,
+/// }
+///
+/// impl ReportError for AssertionFailure {}
+/// ```
+pub trait ReportError: fmt::Debug {
+    /// Returns a reference to the standard error, if any. The error chain will be reported based on
+    /// the [sources](Error::source()) of this error.
+    ///
+    /// The default implementation returns `None`, meaning that the error will be reported
+    /// using its `Debug` implementation.
+    fn as_error(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+
+    /// Returns the backtrace captured with the error, if any.
+    fn backtrace(&self) -> Option<&Backtrace> {
+        None
+    }
+}
+
+impl ReportError for Box<dyn Error> {
+    fn as_error(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&**self)
+    }
+}
+
+impl ReportError for Box<dyn Error + Send> {
+    fn as_error(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&**self)
+    }
+}
+
+impl ReportError for Box<dyn Error + Send + Sync> {
+    fn as_error(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&**self)
+    }
+}
+
+impl ReportError for io::Error {
+    fn as_error(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self)
+    }
+}
+
+impl ReportError for String {}
+
+impl ReportError for &'static str {}
+
+#[cfg(feature = "anyhow")]
+impl ReportError for anyhow::Error {
+    fn as_error(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.as_ref())
+    }
+
+    fn backtrace(&self) -> Option<&Backtrace> {
+        Some(anyhow::Error::backtrace(self))
+    }
+}
+
+#[cfg(feature = "eyre")]
+impl ReportError for eyre::Report {
+    fn as_error(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.as_ref())
+    }
+}
+
+/// [Test decorator](DecorateTest) that prints a detailed report if the wrapped test returns
+/// an error. The report contains the full error chain and the backtrace captured
+/// with the error (if any). The error is propagated after printing the report.
+///
+/// The error type must implement [`ReportError`]. Errors not implementing the standard
+/// [`Error`] trait are reported using their `Debug` implementation.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{ErrorReport, Retry}};
+/// use std::error::Error;
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(ErrorReport, Retry::times(2))]
+/// // ^ The report will be printed for each failed attempt
+/// fn test_with_detailed_errors() -> Result<(), Box<dyn Error>> {
+///     // test logic
+/// #   Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ErrorReport;
+
+impl ErrorReport {
+    fn report(err: &impl ReportError) -> String {
+        let mut report = String::new();
+        if let Some(error) = err.as_error() {
+            writeln!(report, "Test failed with an error: {error}").unwrap();
+
+            let sources = (0..).zip(Self::sources(error));
+            for (i, source) in sources {
+                if i == 0 {
+                    report.push_str("\nCaused by:\n");
+                }
+                writeln!(report, "    {i}: {source}").unwrap();
+            }
+        } else {
+            writeln!(report, "Test failed with an error: {err:?}").unwrap();
+        }
+
+        let backtrace = err.backtrace();
+        if let Some(backtrace) = backtrace.filter(|bt| bt.status() == BacktraceStatus::Captured) {
+            write!(report, "\nBacktrace:\n{backtrace}").unwrap();
+        }
+        report
+    }
+
+    fn sources<'a>(
+        error: &'a (dyn Error + 'static),
+    ) -> impl Iterator<Item = &'a (dyn Error + 'static)> {
+        let mut source = error.source();
+        std::iter::from_fn(move || {
+            let current = source?;
+            source = current.source();
+            Some(current)
+        })
+    }
+}
+
+impl<E: ReportError + 'static> DecorateTest<Result<(), E>> for ErrorReport {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        test_fn().map_err(|err| {
+            println!("{}", Self::report(&err));
+            err
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct WrappingError(io::Error);
+
+    impl fmt::Display for WrappingError {
+        fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("failed accessing resource")
+        }
+    }
+
+    impl Error for WrappingError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn reporting_error_chain() {
+        let io_err = io::Error::new(io::ErrorKind::NotFound, "file not found");
+        let err: Box<dyn Error> = Box::new(WrappingError(io_err));
+        let report = ErrorReport::report(&err);
+
+        assert_eq!(
+            report,
+            "Test failed with an error: failed accessing resource\n\n\
+             Caused by:\n    0: file not found\n"
+        );
+    }
+
+    #[test]
+    fn reporting_debug_only_error() {
+        #[derive(Debug)]
+        #[allow(dead_code)] // field is used in the `Debug` output
+        struct AssertionFailure {
+            actual: u32,
+        }
+
+        impl ReportError for AssertionFailure {}
+
+        let report = ErrorReport::report(&AssertionFailure { actual: 42 });
+        assert_eq!(
+            report,
+            "Test failed with an error: AssertionFailure { actual: 42 }\n"
+        );
+        let report = ErrorReport::report(&String::from("oops"));
+        assert_eq!(report, "Test failed with an error: \"oops\"\n");
+    }
+
+    #[test]
+    fn decorating_test() {
+        static DECORATOR: ErrorReport = ErrorReport;
+
+        let test_fn = || Err::<(), _>(io::Error::new(io::ErrorKind::Other, "oops"));
+        let err = DECORATOR.decorate_and_test(test_fn).unwrap_err();
+        assert_eq!(err.to_string(), "oops");
+    }
+
+    #[cfg(feature = "anyhow")]
+    #[test]
+    fn reporting_anyhow_error_chain() {
+        use anyhow::Context as _;
+
+        let err = Err::<(), _>(io::Error::new(io::ErrorKind::NotFound, "file not found"))
+            .context("failed reading config")
+            .context("failed initializing test")
+            .unwrap_err();
+        let report = ErrorReport::report(&err);
+
+        assert!(
+            report.starts_with(
+                "Test failed with an error: failed initializing test\n\n\
+                 Caused by:\n    0: failed reading config\n    1: file not found\n"
+            ),
+            "{report}"
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! Decorator with guaranteed teardown.
+
+use std::{fmt, panic};
+
+use super::{DecorateTest, TestFn};
+
+/// Outcome of a test passed to the teardown function of a [`Scoped`] decorator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestOutcome {
+    /// The test has completed successfully.
+    Passed,
+    /// The test has returned an error.
+    Failed,
+    /// The test has panicked. This includes panics raised by decorators wrapped by
+    /// the [`Scoped`] decorator, such as an expired [`Timeout`](super::Timeout).
+    Panicked,
+}
+
+/// [Test decorator](DecorateTest) that runs setup before the test and teardown after it.
+/// Unlike cleanup in a [`Drop`] guard inside the test body, the teardown is guaranteed to run
+/// after the wrapped test completes, regardless of its outcome, which is passed to the teardown
+/// function.
+///
+/// `Scoped` only guarantees teardown for the decorators it wraps, i.e. ones preceding it
+/// in the decorator list. For example, if `Scoped` is placed after a [`Timeout`](super::Timeout),
+/// the teardown will run when the timeout expires, even though the test thread is abandoned
+/// by the timeout. If `Scoped` is placed after a [`Retry`](super::Retry), the teardown will run
+/// once after all retries; if it's placed before `Retry`, setup and teardown will run
+/// for each attempt.
+///
+/// If the test has panicked and the teardown panics as well, the teardown panic is reported
+/// to stderr, and the original panic is propagated.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{Scoped, TestOutcome, Timeout}};
+/// use std::{env, fs, path::PathBuf};
+///
+/// static WORK_DIR: Scoped<PathBuf> = Scoped::new(
+///     || {
+///         let dir = env::temp_dir().join("scoped-test");
+///         fs::create_dir_all(&dir).unwrap();
+///         dir
+///     },
+///     |dir, outcome| {
+///         if outcome == TestOutcome::Passed {
+///             fs::remove_dir_all(dir).ok();
+///         } else {
+///             println!("Retained test dir for debugging: {}", dir.display());
+///         }
+///     },
+/// );
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(Timeout::secs(5), &WORK_DIR)]
+/// fn test_with_work_dir() {
+///     // test logic
+/// }
+/// ```
+pub struct Scoped<S> {
+    setup: fn() -> S,
+    teardown: fn(S, TestOutcome),
+}
+
+impl<S> fmt::Debug for Scoped<S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("Scoped")
+            .field("setup", &(self.setup as *const ()))
+            .field("teardown", &(self.teardown as *const ()))
+            .finish()
+    }
+}
+
+impl<S> Clone for Scoped<S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for Scoped<S> {}
+
+impl<S> Scoped<S> {
+    /// Creates a decorator with the specified setup and teardown functions. The state returned
+    /// by `setup` is passed to `teardown` together with the test outcome.
+    pub const fn new(setup: fn() -> S, teardown: fn(S, TestOutcome)) -> Self {
+        Self { setup, teardown }
+    }
+
+    fn decorate_inner<R, F: TestFn<R>>(self, test_fn: F, is_ok: fn(&R) -> bool) -> R {
+        let state = (self.setup)();
+        match panic::catch_unwind(test_fn) {
+            Ok(output) => {
+                let outcome = if is_ok(&output) {
+                    TestOutcome::Passed
+                } else {
+                    TestOutcome::Failed
+                };
+                (self.teardown)(state, outcome);
+                output
+            }
+            Err(panic_object) => {
+                let teardown = self.teardown;
+                let teardown_result = panic::catch_unwind(panic::AssertUnwindSafe(move || {
+                    teardown(state, TestOutcome::Panicked);
+                }));
+                if teardown_result.is_err() {
+                    eprintln!("Teardown has panicked after the test panic");
+                }
+                panic::resume_unwind(panic_object)
+            }
+        }
+    }
+}
+
+impl<S: 'static> DecorateTest<()> for Scoped<S> {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        self.decorate_inner(test_fn, |()| true);
+    }
+}
+
+impl<S: 'static, E> DecorateTest<Result<(), E>> for Scoped<S> {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.decorate_inner(test_fn, Result::is_ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Mutex, PoisonError},
+        thread,
+        time::Duration,
+    };
+
+    use super::*;
+    use crate::decorators::Timeout;
+
+    static OUTCOMES: Mutex<Vec<(u32, TestOutcome)>> = Mutex::new(Vec::new());
+
+    fn record_outcome(id: u32, outcome: TestOutcome) {
+        OUTCOMES
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push((id, outcome));
+    }
+
+    fn recorded_outcomes(id: u32) -> Vec<TestOutcome> {
+        let outcomes = OUTCOMES.lock().unwrap_or_else(PoisonError::into_inner);
+        outcomes
+            .iter()
+            .filter_map(|&(outcome_id, outcome)| (outcome_id == id).then_some(outcome))
+            .collect()
+    }
+
+    #[test]
+    fn teardown_receives_outcome() {
+        static DECORATOR: Scoped<u32> = Scoped::new(|| 1, record_outcome);
+
+        let test_fn: fn() -> Result<(), &'static str> = || Ok(());
+        DECORATOR.decorate_and_test(test_fn).unwrap();
+        let test_fn: fn() -> Result<(), &'static str> = || Err("oops");
+        DECORATOR.decorate_and_test(test_fn).unwrap_err();
+        let test_fn: fn() = || panic!("oops");
+        panic::catch_unwind(|| DECORATOR.decorate_and_test(test_fn)).unwrap_err();
+
+        assert_eq!(
+            recorded_outcomes(1),
+            [
+                TestOutcome::Passed,
+                TestOutcome::Failed,
+                TestOutcome::Panicked
+            ]
+        );
+    }
+
+    #[test]
+    fn teardown_runs_on_timeout() {
+        static DECORATORS: (Timeout, Scoped<u32>) =
+            (Timeout::millis(50), Scoped::new(|| 2, record_outcome));
+
+        let test_fn: fn() = || thread::sleep(Duration::from_secs(1));
+        panic::catch_unwind(|| DECORATORS.decorate_and_test(test_fn)).unwrap_err();
+        assert_eq!(recorded_outcomes(2), [TestOutcome::Panicked]);
+    }
+
+    #[test]
+    fn original_panic_is_propagated_if_teardown_panics() {
+        static DECORATOR: Scoped<()> = Scoped::new(|| (), |(), _| panic!("teardown"));
+
+        let test_fn: fn() = || panic!("test");
+        let panic_object =
+            panic::catch_unwind(|| DECORATOR.decorate_and_test(test_fn)).unwrap_err();
+        assert_eq!(*panic_object.downcast_ref::<&str>().unwrap(), "test");
+    }
+}
@@ -0,0 +1,132 @@
+//! Interoperability with the `serial_test` crate.
+
+use std::{future::Future, pin::pin, task::Context};
+
+use super::{DecorateTest, TestFn};
+
+/// [Test decorator](DecorateTest) that acquires the same named locks as the
+/// [`serial`](https://docs.rs/serial_test/latest/serial_test/attr.serial.html) attribute
+/// from the [`serial_test`] crate.
+///
+/// This allows mixing tests marked with `#[serial]` and decorated tests,
+/// e.g. when migrating a test suite incrementally. A test decorated with `Serial`
+/// never runs concurrently with `#[serial]` tests (and other `Serial`-decorated tests)
+/// that share at least one lock key with it.
+///
+/// Tests in a [`Sequence`](super::Sequence) are not synchronized with `#[serial]` tests
+/// on their own. To synchronize them, add `Serial` *before* the sequence in the decorator list
+/// (i.e., `#[decorate(Serial::key("db"), &SEQUENCE)]`). Using the same order of decorators
+/// for all tests in the sequence ensures that locks are always acquired in the same order,
+/// which prevents deadlocks.
+///
+/// [`serial_test`]: https://docs.rs/serial_test/
+///
+/// # Examples
+///
+/// ```
+/// use serial_test::serial;
+/// use test_casing::{decorate, decorators::{Sequence, Serial}};
+///
+/// static SEQUENCE: Sequence = Sequence::new();
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[serial(db)]
+/// fn legacy_db_test() {
+///     // test logic
+/// }
+///
+/// #[test]
+/// # fn eat_test_attribute2() {}
+/// #[decorate(Serial::key("db"), &SEQUENCE)]
+/// fn decorated_db_test() {
+///     // test logic
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "serial_test")))]
+#[derive(Debug, Clone, Copy)]
+pub struct Serial {
+    keys: LockKeys,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum LockKeys {
+    Single(&'static str),
+    Multiple(&'static [&'static str]),
+}
+
+impl LockKeys {
+    fn to_vec(self) -> Vec<&'static str> {
+        match self {
+            Self::Single(key) => vec![key],
+            Self::Multiple(keys) => keys.to_vec(),
+        }
+    }
+}
+
+impl Serial {
+    /// Uses the default lock, i.e., one used by `#[serial]` without arguments.
+    pub const fn new() -> Self {
+        Self::key("")
+    }
+
+    /// Uses the lock with the specified key, e.g. `Serial::key("db")` corresponds
+    /// to `#[serial(db)]`.
+    pub const fn key(key: &'static str) -> Self {
+        Self {
+            keys: LockKeys::Single(key),
+        }
+    }
+
+    /// Uses locks with all specified keys, e.g. `Serial::keys(&["db", "fs"])` corresponds
+    /// to `#[serial(db, fs)]`.
+    pub const fn keys(keys: &'static [&'static str]) -> Self {
+        Self {
+            keys: LockKeys::Multiple(keys),
+        }
+    }
+}
+
+impl Default for Serial {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R> DecorateTest<R> for Serial {
+    fn decorate_and_test<F: TestFn<R>>(&self, test_fn: F) -> R {
+        // Unlike the sync locking function in `serial_test`, which only accepts a function pointer,
+        // the async one accepts a future that can capture the test function. Since the test
+        // function is sync, the future completes on the first poll.
+        let mut output = None;
+        let locked_test = serial_test::local_async_serial_core(self.keys.to_vec(), None, async {
+            output = Some(test_fn());
+        });
+        let mut cx = Context::from_waker(futures_task::noop_waker_ref());
+        let poll = pin!(locked_test).poll(&mut cx);
+        assert!(
+            poll.is_ready(),
+            "locking `serial_test` keys unexpectedly yielded"
+        );
+        output.expect("test function was not called")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acquiring_serial_test_locks() {
+        static DECORATOR: Serial = Serial::keys(&["test", "other_test"]);
+
+        let test_fn: fn() -> u32 = || {
+            assert!(serial_test::is_locked_serially(Some("test")));
+            assert!(serial_test::is_locked_serially(Some("other_test")));
+            assert!(!serial_test::is_locked_serially(None));
+            42
+        };
+        assert_eq!(DECORATOR.decorate_and_test(test_fn), 42);
+        assert!(!serial_test::is_locked_serially(Some("test")));
+    }
+}
@@ -0,0 +1,227 @@
+//! Randomized concurrency testing provided by the `shuttle` crate.
+
+use std::{
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hasher},
+    panic,
+};
+
+use shuttle::{
+    scheduler::{PctScheduler, RandomScheduler},
+    Config, Runner,
+};
+
+use super::{test_location_suffix, DecorateTest, OutputSink, ReproduceFlake, TestFn};
+
+/// [Test decorator](DecorateTest) that runs the test body under a randomized scheduler
+/// from the [`shuttle`] crate, i.e. executes it repeatedly, exploring random interleavings
+/// of concurrent operations.
+///
+/// Two schedulers are supported: the uniformly random one ([`Self::random()`]), and
+/// the probabilistic concurrency testing (PCT) one ([`Self::pct()`]), which provides
+/// probabilistic guarantees to find bugs of the specified depth.
+///
+/// The test body must use synchronization primitives and threads from `shuttle` (e.g.,
+/// `shuttle::sync::Arc` and `shuttle::thread::spawn()`) instead of the standard ones;
+/// see the `shuttle` docs for details. If a test returns an error, it's converted
+/// to a panic using the error's [`Debug`](fmt::Debug) implementation.
+///
+/// The scheduler is seeded with the seed specified via [`Self::with_seed()`]. If it's not
+/// specified, the seed is taken from [`ReproduceFlake`] (if the decorator precedes
+/// `ReproduceFlake` in the decorator list), or is chosen randomly. If the test fails,
+/// the seed is printed together with the failing schedule, so that the failure
+/// can be reproduced by specifying the seed explicitly.
+///
+/// [`shuttle`]: https://docs.rs/shuttle/
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::ShuttleCheck, test_casing};
+/// use shuttle::{sync::{Arc, Mutex}, thread};
+///
+/// #[test_casing(2, [2, 4])]
+/// #[decorate(ShuttleCheck::pct(1_000, 3))]
+/// fn concurrent_pushes(thread_count: usize) {
+///     let items = Arc::new(Mutex::new(vec![]));
+///     let handles: Vec<_> = (0..thread_count)
+///         .map(|i| {
+///             let items = items.clone();
+///             thread::spawn(move || items.lock().unwrap().push(i))
+///         })
+///         .collect();
+///     for handle in handles {
+///         handle.join().unwrap();
+///     }
+///     assert_eq!(items.lock().unwrap().len(), thread_count);
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "shuttle")))]
+#[derive(Debug, Clone, Copy)]
+pub struct ShuttleCheck {
+    iterations: usize,
+    pct_depth: Option<usize>,
+    seed: Option<u64>,
+    sink: Option<OutputSink>,
+}
+
+impl ShuttleCheck {
+    /// Creates a decorator using the uniformly random scheduler with the specified number
+    /// of iterations.
+    pub const fn random(iterations: usize) -> Self {
+        Self {
+            iterations,
+            pct_depth: None,
+            seed: None,
+            sink: None,
+        }
+    }
+
+    /// Creates a decorator using the PCT scheduler with the specified number of iterations
+    /// and the maximum depth of bugs to find.
+    pub const fn pct(iterations: usize, depth: usize) -> Self {
+        Self {
+            iterations,
+            pct_depth: Some(depth),
+            seed: None,
+            sink: None,
+        }
+    }
+
+    /// Specifies the seed for the scheduler.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Specifies the sink for the message with the seed of a failed check. If not specified,
+    /// the [global sink](OutputSink::global()) is used.
+    #[must_use]
+    pub const fn with_sink(mut self, sink: OutputSink) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
+    fn resolve_seed(&self) -> u64 {
+        self.seed
+            .or_else(ReproduceFlake::seed)
+            .unwrap_or_else(|| RandomState::new().build_hasher().finish())
+    }
+
+    fn check(&self, test_fn: impl Fn() + Send + Sync + 'static) {
+        let seed = self.resolve_seed();
+        let config = Config::new();
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            if let Some(depth) = self.pct_depth {
+                let scheduler = PctScheduler::new_from_seed(seed, depth, self.iterations);
+                Runner::new(scheduler, config).run(test_fn);
+            } else {
+                let scheduler = RandomScheduler::new_from_seed(seed, self.iterations);
+                Runner::new(scheduler, config).run(test_fn);
+            }
+        }));
+
+        if let Err(panic_object) = result {
+            OutputSink::resolve(self.sink).emit(format_args!(
+                "Shuttle check with seed {seed} failed{}; use `ShuttleCheck::with_seed({seed})` \
+                 to reproduce",
+                test_location_suffix()
+            ));
+            panic::resume_unwind(panic_object);
+        }
+    }
+}
+
+impl DecorateTest<()> for ShuttleCheck {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        self.check(test_fn);
+    }
+}
+
+impl<E: fmt::Debug> DecorateTest<Result<(), E>> for ShuttleCheck {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.check(move || {
+            if let Err(err) = test_fn() {
+                panic!("Test returned an error: {err:?}");
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use shuttle::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+    };
+
+    use super::*;
+
+    fn increment_concurrently(is_atomic: bool) -> Result<(), String> {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let counter = counter.clone();
+                thread::spawn(move || {
+                    if is_atomic {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                    } else {
+                        let value = counter.load(Ordering::SeqCst);
+                        counter.store(value + 1, Ordering::SeqCst);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let value = counter.load(Ordering::SeqCst);
+        if value == 2 {
+            Ok(())
+        } else {
+            Err(format!("lost update: {value}"))
+        }
+    }
+
+    #[test]
+    fn checking_correct_code() {
+        static RANDOM: ShuttleCheck = ShuttleCheck::random(100).with_seed(1);
+        static PCT: ShuttleCheck = ShuttleCheck::pct(100, 2).with_seed(1);
+
+        let test_fn: fn() -> Result<(), String> = || increment_concurrently(true);
+        RANDOM.decorate_and_test(test_fn).unwrap();
+        PCT.decorate_and_test(test_fn).unwrap();
+    }
+
+    #[test]
+    fn checking_racy_code() {
+        static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+        static DECORATOR: ShuttleCheck =
+            ShuttleCheck::pct(1_000, 2)
+                .with_seed(42)
+                .with_sink(OutputSink::Custom(|message| {
+                    MESSAGES.lock().unwrap().push(message.to_owned());
+                }));
+
+        let test_fn: fn() -> Result<(), String> = || increment_concurrently(false);
+        panic::catch_unwind(|| DECORATOR.decorate_and_test(test_fn)).unwrap_err();
+        let messages = MESSAGES.lock().unwrap();
+        assert_eq!(messages.len(), 1, "{messages:?}");
+        assert!(
+            messages[0].starts_with("Shuttle check with seed 42 failed"),
+            "{messages:?}"
+        );
+    }
+}
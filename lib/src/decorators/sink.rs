@@ -0,0 +1,102 @@
+//! Output sinks for progress messages emitted by built-in decorators.
+
+use std::{
+    fmt,
+    sync::{PoisonError, RwLock},
+};
+
+static GLOBAL_SINK: RwLock<OutputSink> = RwLock::new(OutputSink::Stdout);
+
+/// Destination of progress messages emitted by built-in decorators, such as [`Retry`](super::Retry)
+/// and [`Sequence`](super::Sequence).
+///
+/// By default, messages are printed to the standard output, which is captured by the test harness
+/// (i.e., it's only shown for failed tests, or if the `--nocapture` option is specified).
+/// The sink can be changed globally using [`Self::set_global()`], or for a specific decorator
+/// (e.g., using [`Retry::with_sink()`](super::Retry::with_sink())); a per-decorator sink
+/// takes precedence over the global one.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{OutputSink, Retry}};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(Retry::times(2).with_sink(OutputSink::Stderr))]
+/// fn test_with_retries() {
+///     // test logic
+/// }
+///
+/// // Sinks can be customized as well.
+/// fn log_message(message: &str) {
+///     // Forward the message to the logging infra used by the project
+/// }
+///
+/// const RETRY: Retry = Retry::times(2).with_sink(OutputSink::Custom(log_message));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum OutputSink {
+    /// Prints messages to the standard output. This is the default.
+    #[default]
+    Stdout,
+    /// Prints messages to the standard error.
+    Stderr,
+    /// Emits messages as `INFO`-level [`tracing`] events with the `test_casing::decorators` target.
+    /// Unlike [`LogLevel`](super::LogLevel), the sink does not install a `tracing` subscriber,
+    /// so the events are processed by the subscriber used by the project (if any).
+    ///
+    /// [`tracing`]: https://docs.rs/tracing/
+    #[cfg(feature = "tracing")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+    Tracing,
+    /// Discards messages.
+    Silent,
+    /// Passes messages to the specified function.
+    Custom(fn(&str)),
+}
+
+impl OutputSink {
+    /// Sets the global output sink used by decorators without a sink specified explicitly.
+    pub fn set_global(sink: Self) {
+        *GLOBAL_SINK.write().unwrap_or_else(PoisonError::into_inner) = sink;
+    }
+
+    /// Returns the global output sink.
+    pub fn global() -> Self {
+        *GLOBAL_SINK.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(super) fn resolve(sink: Option<Self>) -> Self {
+        sink.unwrap_or_else(Self::global)
+    }
+
+    pub(super) fn emit(self, message: fmt::Arguments<'_>) {
+        match self {
+            Self::Stdout => println!("{message}"),
+            Self::Stderr => eprintln!("{message}"),
+            #[cfg(feature = "tracing")]
+            Self::Tracing => tracing::info!(target: "test_casing::decorators", "{message}"),
+            Self::Silent => { /* do nothing */ }
+            Self::Custom(write_fn) => write_fn(&message.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[test]
+    fn emitting_to_custom_sink() {
+        static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+        let sink = OutputSink::Custom(|message| MESSAGES.lock().unwrap().push(message.to_owned()));
+        sink.emit(format_args!("Test attempt #{}", 1));
+        OutputSink::Silent.emit(format_args!("Test attempt #{}", 2));
+        assert_eq!(*MESSAGES.lock().unwrap(), ["Test attempt #1"]);
+    }
+}
@@ -0,0 +1,322 @@
+//! Decorators skipping tests based on runtime conditions.
+
+use std::{
+    env::consts,
+    panic,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use super::{test_location_suffix, DecorateTest, OutputSink, TestFn};
+
+/// [Test decorator](DecorateTest) that skips the wrapped test if the specified predicate
+/// evaluates to `true`. The predicate is evaluated each time the test is run, so it can depend
+/// on runtime conditions that cannot be expressed via `cfg` predicates or constants
+/// (e.g., availability of an external service).
+///
+/// Since the standard test harness doesn't support skipping tests at runtime, a skipped test
+/// is reported as passed; the decorator prints a message with the skip reason
+/// to the [global sink](OutputSink::global()). The same applies to the nightly harness: decorators
+/// run after the test is registered, so they cannot mark it as ignored. For conditions that
+/// can be evaluated when registering tests, consider using the `#[ignore_if(..)]` attribute
+/// with [`test_casing`](macro@crate::test_casing) instead.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::SkipIf};
+///
+/// fn no_database() -> bool {
+///     std::env::var_os("DATABASE_URL").is_none()
+/// }
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(SkipIf::new(no_database).with_reason("`DATABASE_URL` is not set"))]
+/// fn database_test() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SkipIf {
+    condition: fn() -> bool,
+    reason: Option<&'static str>,
+}
+
+impl SkipIf {
+    /// Creates a decorator skipping the test if `condition` returns `true`.
+    pub const fn new(condition: fn() -> bool) -> Self {
+        Self {
+            condition,
+            reason: None,
+        }
+    }
+
+    /// Specifies the human-readable reason for skipping the test, which is included
+    /// into the printed message.
+    #[must_use]
+    pub const fn with_reason(self, reason: &'static str) -> Self {
+        Self {
+            reason: Some(reason),
+            ..self
+        }
+    }
+
+    fn decorate_inner<R, F: TestFn<R>>(self, test_fn: F, skipped_value: R) -> R {
+        if !(self.condition)() {
+            return test_fn();
+        }
+
+        let location = test_location_suffix();
+        if let Some(reason) = self.reason {
+            OutputSink::global().emit(format_args!("Skipping test{location}: {reason}"));
+        } else {
+            OutputSink::global().emit(format_args!("Skipping test{location}"));
+        }
+        skipped_value
+    }
+}
+
+impl DecorateTest<()> for SkipIf {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        self.decorate_inner(test_fn, ());
+    }
+}
+
+impl<E> DecorateTest<Result<(), E>> for SkipIf {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.decorate_inner(test_fn, Ok(()))
+    }
+}
+
+/// [Test decorator](DecorateTest) that skips the wrapped test unless it runs on one
+/// of the specified platforms. Platforms are compared with [`OS`](consts::OS)
+/// and [`ARCH`](consts::ARCH) constants of the test binary (e.g., `linux` / `macos` / `windows`
+/// for OSes and `x86_64` / `aarch64` for architectures).
+///
+/// Unlike `cfg` gating, the test is still compiled on all platforms. This is useful if the test
+/// should be type-checked everywhere, or if the platform check is combined with other runtime
+/// checks (e.g., with a [`SkipIf`] checking the kernel version). Similar to `SkipIf`, a skipped
+/// test is reported as passed with a message printed to the [global sink](OutputSink::global()).
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::Platform};
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(Platform::only(&["linux", "macos"]))]
+/// fn unix_test() {
+///     // test logic
+/// }
+///
+/// #[test]
+/// # fn eat_test_attribute2() {}
+/// #[decorate(Platform::only(&["linux"]).with_arch(&["x86_64"]))]
+/// fn linux_x86_64_test() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Platform {
+    os: &'static [&'static str],
+    arch: &'static [&'static str],
+}
+
+impl Platform {
+    /// Creates a decorator running the test only on the specified OSes. An empty list
+    /// corresponds to any OS.
+    pub const fn only(os: &'static [&'static str]) -> Self {
+        Self { os, arch: &[] }
+    }
+
+    /// Additionally restricts the test to the specified architectures. An empty list
+    /// corresponds to any architecture.
+    #[must_use]
+    pub const fn with_arch(self, arch: &'static [&'static str]) -> Self {
+        Self { arch, ..self }
+    }
+
+    fn matches(self, os: &str, arch: &str) -> bool {
+        let os_matches = self.os.is_empty() || self.os.contains(&os);
+        let arch_matches = self.arch.is_empty() || self.arch.contains(&arch);
+        os_matches && arch_matches
+    }
+
+    fn decorate_inner<R, F: TestFn<R>>(self, test_fn: F, skipped_value: R) -> R {
+        if self.matches(consts::OS, consts::ARCH) {
+            return test_fn();
+        }
+
+        OutputSink::global().emit(format_args!(
+            "Skipping test{}: unsupported platform {}-{}",
+            test_location_suffix(),
+            consts::OS,
+            consts::ARCH
+        ));
+        skipped_value
+    }
+}
+
+impl DecorateTest<()> for Platform {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        self.decorate_inner(test_fn, ());
+    }
+}
+
+impl<E> DecorateTest<Result<(), E>> for Platform {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.decorate_inner(test_fn, Ok(()))
+    }
+}
+
+/// [Test decorator](DecorateTest) that skips all tests in a group once a test in the group fails.
+/// This is similar to [`Sequence::abort_on_failure()`](super::Sequence::abort_on_failure()),
+/// but doesn't serialize tests. Useful for expensive test suites, in which a single failure
+/// (e.g., caused by unavailable infrastructure) makes the remaining tests pointless.
+///
+/// Tests that have already started when a failure occurs run to completion. Skipped tests
+/// are reported as passed with a message printed to the [output sink](Self::with_sink()).
+/// The decorator should be placed into a `static` and referenced in the
+/// [`decorate`](crate::decorate) attributes.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{FailFast, Timeout}, test_casing};
+///
+/// static FAIL_FAST: FailFast = FailFast::new();
+///
+/// #[test_casing(10, 0..10)]
+/// #[decorate(Timeout::secs(30), &FAIL_FAST)]
+/// fn expensive_test(index: usize) {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct FailFast {
+    failed: AtomicBool,
+    sink: Option<OutputSink>,
+}
+
+impl FailFast {
+    /// Creates a new group of tests.
+    pub const fn new() -> Self {
+        Self {
+            failed: AtomicBool::new(false),
+            sink: None,
+        }
+    }
+
+    /// Specifies the sink for messages about skipped tests. If not specified,
+    /// the [global sink](OutputSink::global()) is used.
+    #[must_use]
+    pub const fn with_sink(mut self, sink: OutputSink) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
+    /// Checks whether a test in the group has failed.
+    pub fn has_failed(&self) -> bool {
+        self.failed.load(Ordering::SeqCst)
+    }
+
+    fn decorate_inner<R, F: TestFn<R>>(&self, test_fn: F, ok_value: R, is_ok: fn(&R) -> bool) -> R {
+        if self.has_failed() {
+            OutputSink::resolve(self.sink).emit(format_args!(
+                "Skipping test{} because a previous test in the same fail-fast group has failed",
+                test_location_suffix()
+            ));
+            return ok_value;
+        }
+
+        let output = panic::catch_unwind(test_fn);
+        if !output.as_ref().is_ok_and(is_ok) {
+            self.failed.store(true, Ordering::SeqCst);
+        }
+        output.unwrap_or_else(|panic_object| panic::resume_unwind(panic_object))
+    }
+}
+
+impl DecorateTest<()> for FailFast {
+    fn decorate_and_test<F: TestFn<()>>(&'static self, test_fn: F) {
+        self.decorate_inner(test_fn, (), |()| true);
+    }
+}
+
+impl<E> DecorateTest<Result<(), E>> for FailFast {
+    fn decorate_and_test<F>(&'static self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.decorate_inner(test_fn, Ok(()), Result::is_ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skipping_test() {
+        static DECORATOR: SkipIf = SkipIf::new(|| true).with_reason("always skipped");
+
+        let test_fn: fn() -> Result<(), &'static str> = || Err("should be skipped");
+        assert_eq!(DECORATOR.decorate_and_test(test_fn), Ok(()));
+    }
+
+    #[test]
+    fn not_skipping_test() {
+        static DECORATOR: SkipIf = SkipIf::new(|| false);
+
+        let test_fn: fn() -> Result<(), &'static str> = || Err("not skipped");
+        assert_eq!(DECORATOR.decorate_and_test(test_fn), Err("not skipped"));
+    }
+
+    #[test]
+    fn matching_platforms() {
+        let platform = Platform::only(&["linux", "macos"]);
+        assert!(platform.matches("linux", "x86_64"));
+        assert!(platform.matches("macos", "aarch64"));
+        assert!(!platform.matches("windows", "x86_64"));
+
+        let platform = platform.with_arch(&["aarch64"]);
+        assert!(!platform.matches("linux", "x86_64"));
+        assert!(platform.matches("linux", "aarch64"));
+        assert!(Platform::only(&[])
+            .with_arch(&["aarch64"])
+            .matches("windows", "aarch64"));
+    }
+
+    #[test]
+    fn skipping_test_on_other_platform() {
+        static DECORATOR: Platform = Platform::only(&["unknown"]);
+
+        let test_fn: fn() -> Result<(), &'static str> = || Err("should be skipped");
+        assert_eq!(DECORATOR.decorate_and_test(test_fn), Ok(()));
+    }
+
+    #[test]
+    fn skipping_tests_after_failure() {
+        static FAIL_FAST: FailFast = FailFast::new().with_sink(OutputSink::Silent);
+
+        let test_fn: fn() -> Result<(), &'static str> = || Ok(());
+        assert_eq!(FAIL_FAST.decorate_and_test(test_fn), Ok(()));
+        assert!(!FAIL_FAST.has_failed());
+
+        let test_fn: fn() = || panic!("oops");
+        panic::catch_unwind(|| FAIL_FAST.decorate_and_test(test_fn)).unwrap_err();
+        assert!(FAIL_FAST.has_failed());
+
+        // The panicking test must be skipped now.
+        FAIL_FAST.decorate_and_test(test_fn);
+        let test_fn: fn() -> Result<(), &'static str> = || Err("should be skipped");
+        assert_eq!(FAIL_FAST.decorate_and_test(test_fn), Ok(()));
+    }
+}
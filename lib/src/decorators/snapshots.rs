@@ -0,0 +1,161 @@
+//! Integration with the `insta` snapshot testing library.
+
+use insta::Settings;
+
+use super::{DecorateTest, TestFn};
+
+/// [Test decorator](DecorateTest) that scopes [`insta`] snapshot settings around the wrapped test.
+///
+/// The settings are based on the settings active when the test starts (i.e., the defaults
+/// unless they are overridden by an outer decorator or the test harness) and are bound
+/// to the test for its entire duration. This allows extracting snapshot configuration
+/// (the snapshot path, redactions, etc.) into a reusable constant.
+///
+/// `insta` settings are thread-local. Hence, if the test is decorated with
+/// a [`Timeout`](super::Timeout), `Snapshots` should be placed *before* the timeout
+/// in the decorator list, so that it wraps the test on the same thread.
+///
+/// [`insta`]: https://docs.rs/insta/
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{Snapshots, Timeout}};
+///
+/// const SNAPSHOTS: Snapshots = Snapshots::new()
+///     .with_snapshot_path("snapshots/api")
+///     .with_prepended_module(false)
+///     .with_redactions(&[(".timestamp", "[timestamp]"), (".**.id", "[id]")]);
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(SNAPSHOTS, Timeout::secs(5))]
+/// fn api_response_snapshot() {
+///     // test logic using `insta` assertions
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "insta")))]
+#[derive(Debug, Clone, Copy)]
+pub struct Snapshots {
+    snapshot_path: Option<&'static str>,
+    prepend_module: Option<bool>,
+    snapshot_suffix: Option<&'static str>,
+    redactions: &'static [(&'static str, &'static str)],
+    configure: Option<fn(&mut Settings)>,
+}
+
+impl Snapshots {
+    /// Creates a decorator that doesn't change any settings.
+    pub const fn new() -> Self {
+        Self {
+            snapshot_path: None,
+            prepend_module: None,
+            snapshot_suffix: None,
+            redactions: &[],
+            configure: None,
+        }
+    }
+
+    /// Sets the path to the snapshot directory. If the path is relative, it is resolved
+    /// relative to the file containing the test.
+    #[must_use]
+    pub const fn with_snapshot_path(mut self, path: &'static str) -> Self {
+        self.snapshot_path = Some(path);
+        self
+    }
+
+    /// Specifies whether to prepend the module name to snapshot names.
+    #[must_use]
+    pub const fn with_prepended_module(mut self, prepend: bool) -> Self {
+        self.prepend_module = Some(prepend);
+        self
+    }
+
+    /// Sets the suffix added to snapshot names.
+    #[must_use]
+    pub const fn with_snapshot_suffix(mut self, suffix: &'static str) -> Self {
+        self.snapshot_suffix = Some(suffix);
+        self
+    }
+
+    /// Adds static redactions as `(selector, replacement)` tuples. Redactions apply
+    /// to serialized snapshots (e.g., ones asserted with `insta::assert_json_snapshot!`).
+    #[must_use]
+    pub const fn with_redactions(
+        mut self,
+        redactions: &'static [(&'static str, &'static str)],
+    ) -> Self {
+        self.redactions = redactions;
+        self
+    }
+
+    /// Adds a function to customize settings not covered by other methods
+    /// (e.g., dynamic redactions). The function is called after all other settings are applied.
+    #[must_use]
+    pub const fn with_configure(mut self, configure: fn(&mut Settings)) -> Self {
+        self.configure = Some(configure);
+        self
+    }
+
+    fn settings(&self) -> Settings {
+        let mut settings = Settings::clone_current();
+        if let Some(path) = self.snapshot_path {
+            settings.set_snapshot_path(path);
+        }
+        if let Some(prepend) = self.prepend_module {
+            settings.set_prepend_module_to_snapshot(prepend);
+        }
+        if let Some(suffix) = self.snapshot_suffix {
+            settings.set_snapshot_suffix(suffix);
+        }
+        for &(selector, replacement) in self.redactions {
+            settings.add_redaction(selector, replacement);
+        }
+        if let Some(configure) = self.configure {
+            configure(&mut settings);
+        }
+        settings
+    }
+}
+
+impl Default for Snapshots {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R> DecorateTest<R> for Snapshots {
+    fn decorate_and_test<F: TestFn<R>>(&self, test_fn: F) -> R {
+        self.settings().bind(test_fn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn binding_settings() {
+        static DECORATOR: Snapshots = Snapshots::new()
+            .with_snapshot_path("snapshots/decorated")
+            .with_prepended_module(false)
+            .with_snapshot_suffix("test")
+            .with_configure(|settings| settings.set_description("decorated"));
+
+        let test_fn: fn() -> u32 = || {
+            let settings = Settings::clone_current();
+            assert_eq!(settings.snapshot_path(), Path::new("snapshots/decorated"));
+            assert!(!settings.prepend_module_to_snapshot());
+            assert_eq!(settings.snapshot_suffix(), Some("test"));
+            assert_eq!(settings.description(), Some("decorated"));
+            42
+        };
+        assert_eq!(DECORATOR.decorate_and_test(test_fn), 42);
+
+        let settings = Settings::clone_current();
+        assert_eq!(settings.snapshot_path(), Path::new("snapshots"));
+        assert!(settings.prepend_module_to_snapshot());
+    }
+}
@@ -0,0 +1,209 @@
+//! Temporary directory fixture.
+
+use std::{
+    cell::RefCell,
+    env, fs, io, panic,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use super::{test_location_suffix, DecorateTest, OutputSink, TestFn};
+
+thread_local! {
+    static CURRENT_DIR: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// Sets the temporary directory for the current test and restores the previous one on drop.
+#[derive(Debug)]
+struct TempDirGuard {
+    prev_dir: Option<PathBuf>,
+}
+
+impl TempDirGuard {
+    fn new(dir: PathBuf) -> Self {
+        Self {
+            prev_dir: CURRENT_DIR.with(|cell| cell.replace(Some(dir))),
+        }
+    }
+}
+
+impl Drop for TempDirGuard {
+    fn drop(&mut self) {
+        CURRENT_DIR.with(|cell| *cell.borrow_mut() = self.prev_dir.take());
+    }
+}
+
+/// [Test decorator](DecorateTest) that creates a unique temporary directory for each test run
+/// and removes it after the test. The test can access the directory via [`Self::path()`].
+///
+/// By default, the directory is removed regardless of the test outcome. If the decorator
+/// is configured to [keep directories on failure](Self::keep_on_failure()), the directory
+/// of a failed test is retained, and its path is reported to the [global sink](OutputSink::global()).
+///
+/// The directory path is tracked per thread, so `TempDir` must precede decorators running
+/// the test on another thread (e.g., a [`Timeout`](super::Timeout)) in the decorator list.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{TempDir, Timeout}};
+/// use std::fs;
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(TempDir::new().keep_on_failure(), Timeout::secs(5))]
+/// fn test_with_temp_dir() {
+///     let dir = TempDir::path().unwrap();
+///     fs::write(dir.join("test.txt"), "Hello, world!").unwrap();
+///     // other test logic
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct TempDir {
+    prefix: &'static str,
+    keep_on_failure: bool,
+}
+
+impl Default for TempDir {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TempDir {
+    /// Creates a decorator with the default settings.
+    pub const fn new() -> Self {
+        Self {
+            prefix: "test-casing",
+            keep_on_failure: false,
+        }
+    }
+
+    /// Specifies the prefix for names of created directories.
+    #[must_use]
+    pub const fn with_prefix(self, prefix: &'static str) -> Self {
+        Self { prefix, ..self }
+    }
+
+    /// Retains the directory if the test fails (i.e., panics or returns an error).
+    #[must_use]
+    pub const fn keep_on_failure(self) -> Self {
+        Self {
+            keep_on_failure: true,
+            ..self
+        }
+    }
+
+    /// Returns the temporary directory of the test running on the current thread, or `None`
+    /// if called outside a test decorated with `TempDir`.
+    pub fn path() -> Option<PathBuf> {
+        CURRENT_DIR.with(|cell| cell.borrow().clone())
+    }
+
+    fn create_dir(self) -> io::Result<PathBuf> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        loop {
+            let index = COUNTER.fetch_add(1, Ordering::Relaxed);
+            let name = format!("{}-{}-{index}", self.prefix, process::id());
+            let dir = env::temp_dir().join(name);
+            match fs::create_dir(&dir) {
+                Ok(()) => return Ok(dir),
+                // The directory may be left over from a previous run of a process with the same ID;
+                // in this case, try the next index.
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    fn remove_dir(dir: &Path) {
+        if let Err(err) = fs::remove_dir_all(dir) {
+            OutputSink::global().emit(format_args!(
+                "Failed removing temporary directory `{}`: {err}",
+                dir.display()
+            ));
+        }
+    }
+
+    fn run<R, F: TestFn<R>>(self, test_fn: F, is_ok: fn(&R) -> bool) -> R {
+        let dir = self
+            .create_dir()
+            .unwrap_or_else(|err| panic!("Failed creating temporary directory: {err}"));
+        let output = {
+            let _guard = TempDirGuard::new(dir.clone());
+            panic::catch_unwind(test_fn)
+        };
+
+        let is_passed = output.as_ref().is_ok_and(is_ok);
+        if is_passed || !self.keep_on_failure {
+            Self::remove_dir(&dir);
+        } else {
+            OutputSink::global().emit(format_args!(
+                "Retained temporary directory `{}` for the failed test{}",
+                dir.display(),
+                test_location_suffix()
+            ));
+        }
+        output.unwrap_or_else(|panic_object| panic::resume_unwind(panic_object))
+    }
+}
+
+impl DecorateTest<()> for TempDir {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        self.run(test_fn, |()| true);
+    }
+}
+
+impl<E> DecorateTest<Result<(), E>> for TempDir {
+    fn decorate_and_test<F>(&self, test_fn: F) -> Result<(), E>
+    where
+        F: TestFn<Result<(), E>>,
+    {
+        self.run(test_fn, Result::is_ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    fn check_temp_dir(dir_cell: &Mutex<Option<PathBuf>>) -> Result<(), &'static str> {
+        let dir = TempDir::path().unwrap();
+        assert!(dir.is_dir());
+        fs::write(dir.join("test.txt"), "test").unwrap();
+        *dir_cell.lock().unwrap() = Some(dir);
+        Err("oops")
+    }
+
+    #[test]
+    fn creating_and_removing_temp_dir() {
+        static DECORATOR: TempDir = TempDir::new();
+        static TEST_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+        let test_fn: fn() -> Result<(), &'static str> = || check_temp_dir(&TEST_DIR);
+        DECORATOR.decorate_and_test(test_fn).unwrap_err();
+        let dir = TEST_DIR.lock().unwrap().take().unwrap();
+        assert!(!dir.exists());
+        assert!(TempDir::path().is_none());
+    }
+
+    #[test]
+    fn keeping_temp_dir_on_failure() {
+        static DECORATOR: TempDir = TempDir::new()
+            .with_prefix("test-casing-retained")
+            .keep_on_failure();
+        static TEST_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+        let test_fn: fn() -> Result<(), &'static str> = || check_temp_dir(&TEST_DIR);
+        DECORATOR.decorate_and_test(test_fn).unwrap_err();
+        let dir = TEST_DIR.lock().unwrap().take().unwrap();
+        let dir_name = dir.file_name().unwrap().to_str().unwrap();
+        assert!(dir_name.starts_with("test-casing-retained-"), "{dir_name}");
+        assert!(dir.join("test.txt").is_file());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
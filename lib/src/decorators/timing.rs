@@ -0,0 +1,302 @@
+//! Decorators measuring test durations.
+
+use std::{
+    cmp::Reverse,
+    fmt::Write as _,
+    fs, panic,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+
+use super::{
+    process::current_test_name, summary, test_location_suffix, DecorateTest, OutputSink, TestFn,
+};
+
+/// [Test decorator](DecorateTest) that warns if the wrapped test takes longer than the specified
+/// threshold. Unlike a [`Timeout`](super::Timeout), the decorator never fails the test; it only
+/// provides visibility into slow tests, e.g. among many cases generated by
+/// [`test_casing`](macro@crate::test_casing).
+///
+/// The warning is emitted to the [global sink](OutputSink::global()) unless another sink
+/// is [specified](Self::with_sink()); e.g., [`OutputSink::Stderr`] makes warnings visible
+/// regardless of output capturing. Warnings are emitted for both passed and failed tests.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::{OutputSink, WarnIfSlow}};
+/// use std::time::Duration;
+///
+/// #[test]
+/// # fn eat_test_attribute() {}
+/// #[decorate(WarnIfSlow::secs(2).with_sink(OutputSink::Stderr))]
+/// fn possibly_slow_test() {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct WarnIfSlow {
+    threshold: Duration,
+    sink: Option<OutputSink>,
+}
+
+impl WarnIfSlow {
+    /// Creates a decorator with the specified threshold.
+    pub const fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            sink: None,
+        }
+    }
+
+    /// Creates a decorator with the specified threshold in seconds.
+    pub const fn secs(secs: u64) -> Self {
+        Self::new(Duration::from_secs(secs))
+    }
+
+    /// Creates a decorator with the specified threshold in milliseconds.
+    pub const fn millis(millis: u64) -> Self {
+        Self::new(Duration::from_millis(millis))
+    }
+
+    /// Specifies the sink for warnings. If not specified, the [global sink](OutputSink::global())
+    /// is used.
+    #[must_use]
+    pub const fn with_sink(self, sink: OutputSink) -> Self {
+        Self {
+            sink: Some(sink),
+            ..self
+        }
+    }
+}
+
+impl<R> DecorateTest<R> for WarnIfSlow {
+    fn decorate_and_test<F: TestFn<R>>(&self, test_fn: F) -> R {
+        let started_at = Instant::now();
+        let output = panic::catch_unwind(test_fn);
+        let elapsed = started_at.elapsed();
+        if elapsed > self.threshold {
+            OutputSink::resolve(self.sink).emit(format_args!(
+                "Test took {elapsed:?}, exceeding the threshold {:?}{}",
+                self.threshold,
+                test_location_suffix()
+            ));
+        }
+        output.unwrap_or_else(|panic_object| panic::resume_unwind(panic_object))
+    }
+}
+
+/// Duration of a single test run recorded by [`RecordTiming`].
+#[derive(Debug, Clone, PartialEq)]
+struct TimingEntry {
+    test_name: String,
+    duration: Duration,
+    passed: bool,
+}
+
+/// [Test decorator](DecorateTest) that records durations of the wrapped tests and outputs
+/// a summary with tests sorted by decreasing duration on process exit. This allows finding out
+/// which tests (e.g., which cases generated by [`test_casing`](macro@crate::test_casing))
+/// dominate the test suite duration.
+///
+/// The summary is emitted to the [global sink](OutputSink::global()) unless another sink
+/// is [specified](Self::with_sink()), or [written to a file](Self::with_summary_file()).
+/// Like [`Sequence`](super::Sequence) summaries, it is only supported on Unix and Windows targets.
+/// The decorator should be placed into a `static` and referenced in the
+/// [`decorate`](crate::decorate) attributes; each instance tracks its own tests.
+///
+/// Tests are identified by the name of the thread they run on, which is the test name
+/// for the standard test harness. If the thread is unnamed, the test location is used instead.
+///
+/// # Examples
+///
+/// ```
+/// use test_casing::{decorate, decorators::RecordTiming, test_casing};
+///
+/// static TIMING: RecordTiming = RecordTiming::new().with_summary_file("target/test-timing.txt");
+///
+/// #[test_casing(5, 0..5)]
+/// #[decorate(&TIMING)]
+/// fn timed_test(size: usize) {
+///     // test logic
+/// }
+/// ```
+#[derive(Debug)]
+pub struct RecordTiming {
+    summary_file: Option<&'static str>,
+    sink: Option<OutputSink>,
+    entries: Mutex<Vec<TimingEntry>>,
+    is_summary_registered: AtomicBool,
+}
+
+impl Default for RecordTiming {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RecordTiming {
+    /// Creates a decorator emitting the summary to the [global sink](OutputSink::global()).
+    pub const fn new() -> Self {
+        Self {
+            summary_file: None,
+            sink: None,
+            entries: Mutex::new(Vec::new()),
+            is_summary_registered: AtomicBool::new(false),
+        }
+    }
+
+    /// Writes the summary to the specified file instead of emitting it to the sink. A relative
+    /// path is resolved relative to the current directory; for tests run by Cargo, this is
+    /// the package root.
+    #[must_use]
+    pub const fn with_summary_file(mut self, path: &'static str) -> Self {
+        self.summary_file = Some(path);
+        self
+    }
+
+    /// Specifies the sink for the summary (or for errors writing the summary file). If not
+    /// specified, the [global sink](OutputSink::global()) is used.
+    #[must_use]
+    pub const fn with_sink(mut self, sink: OutputSink) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
+    fn sorted_entries(&self) -> Vec<TimingEntry> {
+        let mut entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        entries.sort_by_key(|entry| Reverse(entry.duration));
+        entries
+    }
+
+    fn format_summary(&self) -> String {
+        let entries = self.sorted_entries();
+        let total: Duration = entries.iter().map(|entry| entry.duration).sum();
+        let mut summary = format!("Test timings ({} run(s), {total:?} total):", entries.len());
+        for entry in &entries {
+            let failed = if entry.passed { "" } else { " (failed)" };
+            let duration = format!("{:?}", entry.duration);
+            write!(summary, "\n{duration:>12}  {}{failed}", entry.test_name).unwrap();
+        }
+        summary
+    }
+}
+
+impl summary::PrintSummary for RecordTiming {
+    fn print_summary(&self) {
+        let summary = self.format_summary();
+        let sink = OutputSink::resolve(self.sink);
+        if let Some(path) = self.summary_file {
+            if let Err(err) = fs::write(path, summary + "\n") {
+                sink.emit(format_args!(
+                    "Failed writing test timings to `{path}`: {err}"
+                ));
+            }
+        } else {
+            sink.emit(format_args!("{summary}"));
+        }
+    }
+}
+
+impl<R> DecorateTest<R> for RecordTiming {
+    fn decorate_and_test<F: TestFn<R>>(&'static self, test_fn: F) -> R {
+        if !self.is_summary_registered.swap(true, Ordering::SeqCst) {
+            summary::register(self);
+        }
+
+        let started_at = Instant::now();
+        let output = panic::catch_unwind(test_fn);
+        let duration = started_at.elapsed();
+        let test_name = current_test_name().unwrap_or_else(|| {
+            let location = test_location_suffix();
+            let location = location.trim();
+            if location.is_empty() {
+                "(unknown test)".to_owned()
+            } else {
+                location.to_owned()
+            }
+        });
+        let entry = TimingEntry {
+            test_name,
+            duration,
+            passed: output.is_ok(),
+        };
+        self.entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(entry);
+        output.unwrap_or_else(|panic_object| panic::resume_unwind(panic_object))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Mutex, thread};
+
+    use super::*;
+
+    #[test]
+    fn warning_about_slow_test() {
+        static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+        static DECORATOR: WarnIfSlow =
+            WarnIfSlow::millis(20).with_sink(OutputSink::Custom(|message| {
+                MESSAGES.lock().unwrap().push(message.to_owned());
+            }));
+
+        DECORATOR.decorate_and_test(|| {});
+        assert!(MESSAGES.lock().unwrap().is_empty());
+
+        let test_fn: fn() -> Result<(), &'static str> = || {
+            thread::sleep(Duration::from_millis(50));
+            Err("oops")
+        };
+        assert_eq!(DECORATOR.decorate_and_test(test_fn), Err("oops"));
+        let messages = MESSAGES.lock().unwrap();
+        assert_eq!(messages.len(), 1);
+        assert!(
+            messages[0].contains("exceeding the threshold 20ms"),
+            "{messages:?}"
+        );
+    }
+
+    #[test]
+    fn recording_timings() {
+        static TIMING: RecordTiming = RecordTiming::new().with_sink(OutputSink::Silent);
+
+        TIMING.decorate_and_test(|| thread::sleep(Duration::from_millis(10)));
+        let test_fn: fn() = || {
+            thread::sleep(Duration::from_millis(30));
+            panic!("oops");
+        };
+        panic::catch_unwind(|| TIMING.decorate_and_test(test_fn)).unwrap_err();
+
+        let entries = TIMING.sorted_entries();
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].duration >= Duration::from_millis(30));
+        assert!(!entries[0].passed);
+        assert!(entries[1].passed);
+        assert!(
+            entries[1].test_name.ends_with("recording_timings"),
+            "{entries:?}"
+        );
+
+        let summary = TIMING.format_summary();
+        let lines: Vec<_> = summary.lines().collect();
+        assert_eq!(lines.len(), 3, "{summary}");
+        assert!(
+            lines[0].starts_with("Test timings (2 run(s), "),
+            "{summary}"
+        );
+        assert!(
+            lines[1].ends_with("recording_timings (failed)"),
+            "{summary}"
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! Expected-output files for test cases.
+
+use std::{
+    env, fmt, fs, io,
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+/// Name of the env variable that switches [`Expected`] to updating expected-output files.
+const UPDATE_ENV_VAR: &str = "TEST_CASING_UPDATE_EXPECTED";
+
+/// Expected output of a test case loaded from a file. Provided to tested functions
+/// by the [`test_casing`](crate::test_casing) macro for args with the `#[expected(..)]` attribute;
+/// see its docs for details.
+///
+/// The expected output can be accessed via [`Deref`] to `str`, or compared with the actual output
+/// using [`Self::assert_eq()`]. If the `TEST_CASING_UPDATE_EXPECTED` env variable is set,
+/// `assert_eq()` overwrites the file with the actual output instead of comparing; missing files
+/// are treated as empty in this mode.
+///
+/// ```shell
+/// TEST_CASING_UPDATE_EXPECTED=1 cargo test
+/// ```
+#[derive(Debug, Clone)]
+pub struct Expected {
+    path: PathBuf,
+    contents: String,
+}
+
+impl Expected {
+    fn is_updating() -> bool {
+        env::var_os(UPDATE_ENV_VAR).is_some()
+    }
+
+    /// Substitutes the `{fn}`, `{case}` and `{index}` placeholders in the path template.
+    fn resolve_path(path_template: &str, fn_name: &str, index: usize) -> PathBuf {
+        let path = path_template
+            .replace("{fn}", fn_name)
+            .replace("{case}", &format!("case_{index}"))
+            .replace("{index}", &index.to_string());
+        PathBuf::from(path)
+    }
+
+    #[doc(hidden)] // used by the `#[test_casing]` macro; logically private
+    pub fn load(path_template: &str, fn_name: &str, index: usize) -> Self {
+        let path = Self::resolve_path(path_template, fn_name, index);
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound && Self::is_updating() => {
+                String::new()
+            }
+            Err(err) => panic!(
+                "Failed reading expected output from `{}`: {err}; set the `{UPDATE_ENV_VAR}` \
+                 env variable to create or update expected-output files",
+                path.display()
+            ),
+        };
+        Self { path, contents }
+    }
+
+    /// Returns the path to the expected-output file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the expected output.
+    pub fn as_str(&self) -> &str {
+        &self.contents
+    }
+
+    /// Compares the actual output with the expected one.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the outputs differ, unless expected-output files are being updated.
+    /// - Panics if updating the expected-output file fails.
+    #[track_caller]
+    pub fn assert_eq(self, actual: impl AsRef<str>) {
+        let actual = actual.as_ref();
+        if Self::is_updating() {
+            if actual != self.contents {
+                self.update(actual).unwrap_or_else(|err| {
+                    panic!(
+                        "Failed updating expected output in `{}`: {err}",
+                        self.path.display()
+                    );
+                });
+                println!("Updated expected output in `{}`", self.path.display());
+            }
+        } else {
+            assert_eq!(
+                actual,
+                self.contents,
+                "actual output differs from the expected one in `{}`; set the `{UPDATE_ENV_VAR}` \
+                 env variable to update expected-output files",
+                self.path.display()
+            );
+        }
+    }
+
+    fn update(&self, actual: &str) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, actual)
+    }
+}
+
+impl Deref for Expected {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.contents
+    }
+}
+
+impl AsRef<str> for Expected {
+    fn as_ref(&self) -> &str {
+        &self.contents
+    }
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolving_path_template() {
+        let path = Expected::resolve_path("tests/expected/{fn}/{case}.txt", "tested_fn", 3);
+        assert_eq!(path, Path::new("tests/expected/tested_fn/case_3.txt"));
+        let path = Expected::resolve_path("tests/expected/{fn}-{index}.json", "tested_fn", 10);
+        assert_eq!(path, Path::new("tests/expected/tested_fn-10.json"));
+    }
+}
@@ -0,0 +1,428 @@
+//! Recording failed test cases into a summary file.
+
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::BTreeMap,
+    env,
+    fmt::{self, Write as _},
+    fs,
+    future::Future,
+    io::{self, Write as _},
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    pin::pin,
+    sync::{Mutex, Once, OnceLock, PoisonError},
+    thread,
+};
+
+use crate::{
+    decorators::{
+        summary::{self, PrintSummary},
+        CatchUnwind,
+    },
+    test_casing::write_json_string,
+};
+
+/// Name of the env variable specifying the path to the failure summary file.
+const SUMMARY_ENV_VAR: &str = "TEST_CASING_FAILURE_SUMMARY";
+
+/// Maximum length (in chars) of an error or panic message included into the summary.
+const MAX_ERROR_LEN: usize = 500;
+
+/// Error recorded for panics with a non-string payload.
+const DEFAULT_PANIC_MESSAGE: &str = "test panicked";
+
+thread_local! {
+    /// Message of the last panic on the current thread, captured by the panic hook.
+    static LAST_PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Failed (or not completed) test case.
+#[derive(Debug)]
+struct CaseFailure {
+    args: String,
+    attempts: usize,
+    error: Option<String>,
+}
+
+/// Test cases keyed by the test path and the case index.
+type Failures = BTreeMap<(&'static str, usize), CaseFailure>;
+
+static FAILURES: Mutex<Failures> = Mutex::new(BTreeMap::new());
+
+/// Gets the summary path from the [`SUMMARY_ENV_VAR`] env variable.
+fn summary_path() -> Option<&'static PathBuf> {
+    static PATH: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+    PATH.get_or_init(|| {
+        let path = env::var_os(SUMMARY_ENV_VAR)?;
+        (!path.is_empty()).then(|| PathBuf::from(path))
+    })
+    .as_ref()
+}
+
+fn lock_failures() -> std::sync::MutexGuard<'static, Failures> {
+    FAILURES.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&'static str>() {
+        (*message).to_owned()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        DEFAULT_PANIC_MESSAGE.to_owned()
+    }
+}
+
+fn excerpt(mut message: String) -> String {
+    let char_count = message.chars().count();
+    if char_count > MAX_ERROR_LEN {
+        let (truncated_pos, _) = message.char_indices().nth(MAX_ERROR_LEN).unwrap();
+        message.truncate(truncated_pos);
+        let truncated_count = char_count - MAX_ERROR_LEN;
+        write!(message, "… ({truncated_count} chars truncated)").unwrap();
+    }
+    message
+}
+
+/// Writes the failure summary on process exit.
+#[derive(Debug)]
+struct FailureSummary;
+
+impl PrintSummary for FailureSummary {
+    fn print_summary(&self) {
+        let Some(path) = summary_path() else {
+            return;
+        };
+        if let Err(err) = write_summary(path, &lock_failures()) {
+            eprintln!(
+                "Failed writing test failure summary to `{}`: {err}",
+                path.display()
+            );
+        }
+    }
+}
+
+/// Installs the panic hook capturing panic messages and registers the exit hook writing
+/// the summary.
+fn install_hooks() {
+    static INSTALL_HOOKS: Once = Once::new();
+
+    INSTALL_HOOKS.call_once(|| {
+        let prev_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let message = panic_message(info.payload());
+            LAST_PANIC.with(|cell| *cell.borrow_mut() = Some(message));
+            prev_hook(info);
+        }));
+        summary::register(&FailureSummary);
+    });
+}
+
+/// Appends failures to the summary file, one JSON object per line.
+fn write_summary(path: &Path, failures: &Failures) -> io::Result<()> {
+    if failures.is_empty() {
+        return Ok(());
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    let mut buffer = String::new();
+    for ((test, case), failure) in failures {
+        failure.write_line(&mut buffer, test, *case);
+    }
+    file.write_all(buffer.as_bytes())
+}
+
+impl CaseFailure {
+    fn write_line(&self, buffer: &mut String, test: &str, case: usize) {
+        buffer.push_str("{\"test\":");
+        write_json_string(buffer, test);
+        write!(buffer, ",\"case\":{case},\"args\":").unwrap();
+        write_json_string(buffer, &self.args);
+        write!(buffer, ",\"attempts\":{},\"error\":", self.attempts).unwrap();
+        let error = self.error.as_deref().unwrap_or("test did not complete");
+        write_json_string(buffer, error);
+        buffer.push_str("}\n");
+    }
+}
+
+/// Records the outcome of a test case into the failure summary if the summary is enabled
+/// via the env variable. The case is considered failed if the guard is dropped while panicking,
+/// or if [`Self::finish()`] is called with an error.
+#[doc(hidden)] // used by the `#[test_casing]` macro; logically private
+#[derive(Debug)]
+pub struct CaseFailureGuard {
+    key: Option<(&'static str, usize)>,
+}
+
+impl CaseFailureGuard {
+    /// Creates a guard for the specified case. `describe_args` is only called if the summary
+    /// is enabled.
+    pub fn new(test: &'static str, case: usize, describe_args: impl FnOnce() -> String) -> Self {
+        if summary_path().is_none() {
+            return Self { key: None };
+        }
+        Self::start(test, case, describe_args)
+    }
+
+    fn start(test: &'static str, case: usize, describe_args: impl FnOnce() -> String) -> Self {
+        install_hooks();
+        let key = (test, case);
+        let mut failures = lock_failures();
+        let failure = failures.entry(key).or_insert_with(|| CaseFailure {
+            args: describe_args(),
+            attempts: 0,
+            error: None,
+        });
+        // Until the case completes, it's recorded as not completed (e.g., because of a timeout).
+        failure.attempts += 1;
+        failure.error = None;
+        Self { key: Some(key) }
+    }
+
+    /// Finishes the case with the specified output, recording an error if any.
+    pub fn finish<T, E: fmt::Debug>(mut self, output: &Result<T, E>) {
+        let Some(key) = self.key.take() else {
+            return;
+        };
+        let mut failures = lock_failures();
+        match output {
+            Ok(_) => {
+                failures.remove(&key);
+            }
+            Err(err) => {
+                if let Some(failure) = failures.get_mut(&key) {
+                    failure.error = Some(excerpt(format!("{err:?}")));
+                }
+            }
+        }
+    }
+}
+
+impl Drop for CaseFailureGuard {
+    fn drop(&mut self) {
+        let Some(key) = self.key.take() else {
+            return;
+        };
+        let mut failures = lock_failures();
+        if thread::panicking() {
+            let message = LAST_PANIC.with(|cell| cell.borrow_mut().take());
+            if let Some(failure) = failures.get_mut(&key) {
+                let message = message.unwrap_or_else(|| DEFAULT_PANIC_MESSAGE.to_owned());
+                failure.error = Some(excerpt(message));
+            }
+        } else {
+            failures.remove(&key);
+        }
+    }
+}
+
+/// Output of a test case that can be checked for errors.
+#[doc(hidden)] // used by the `#[test_casing]` macro; logically private
+pub trait CaseOutput {
+    /// Returns the error message if the case failed.
+    fn error_message(&self) -> Option<String>;
+}
+
+impl CaseOutput for () {
+    fn error_message(&self) -> Option<String> {
+        None
+    }
+}
+
+impl<T, E: fmt::Debug> CaseOutput for Result<T, E> {
+    fn error_message(&self) -> Option<String> {
+        self.as_ref().err().map(|err| format!("{err:?}"))
+    }
+}
+
+/// Runs test cases in a single test (which is generated if the number of cases is not known
+/// to the macro), so that a failing case doesn't prevent running the following cases.
+/// Failures are reported together once all cases are run.
+#[doc(hidden)] // used by the `#[test_casing]` macro; logically private
+#[derive(Debug)]
+pub struct AllCasesRunner {
+    test: &'static str,
+    case_count: usize,
+    failures: Vec<(usize, String)>,
+}
+
+impl AllCasesRunner {
+    pub fn new(test: &'static str) -> Self {
+        Self {
+            test,
+            case_count: 0,
+            failures: Vec::new(),
+        }
+    }
+
+    /// Runs a single case, recording its failure (an error or a panic) if any.
+    pub fn run<R: CaseOutput>(&mut self, case: usize, test_fn: impl FnOnce() -> R) {
+        let output = panic::catch_unwind(AssertUnwindSafe(test_fn));
+        self.record(case, output);
+    }
+
+    /// Async version of [`Self::run()`].
+    pub async fn run_async<R: CaseOutput>(&mut self, case: usize, test: impl Future<Output = R>) {
+        let output = CatchUnwind(pin!(test)).await;
+        self.record(case, output);
+    }
+
+    fn record<R: CaseOutput>(&mut self, case: usize, output: thread::Result<R>) {
+        self.case_count += 1;
+        let error = match output {
+            Ok(output) => output.error_message(),
+            Err(panic_object) => Some(panic_message(&*panic_object)),
+        };
+        if let Some(error) = error {
+            self.failures.push((case, excerpt(error)));
+        }
+    }
+
+    fn failure_message(&self) -> Option<String> {
+        if self.failures.is_empty() {
+            return None;
+        }
+        let mut message = format!(
+            "{} of {} test case(s) failed in `{}`:",
+            self.failures.len(),
+            self.case_count,
+            self.test
+        );
+        for (case, error) in &self.failures {
+            write!(message, "\n  case #{case}: {error}").unwrap();
+        }
+        Some(message)
+    }
+
+    /// Finishes running cases.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the run cases failed, listing all failures.
+    #[track_caller]
+    pub fn finish(self) {
+        if let Some(message) = self.failure_message() {
+            panic!("{message}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary_lines(test: &str) -> Vec<String> {
+        let failures = lock_failures();
+        let mut buffer = String::new();
+        for ((test, case), failure) in failures.range((test, 0)..=(test, usize::MAX)) {
+            failure.write_line(&mut buffer, test, *case);
+        }
+        buffer.lines().map(str::to_owned).collect()
+    }
+
+    #[test]
+    fn recording_failed_cases() {
+        const TEST: &str = "failures::recording_failed_cases";
+
+        let guard = CaseFailureGuard::start(TEST, 0, || "number = 0".to_owned());
+        guard.finish(&Ok::<_, String>(()));
+        let guard = CaseFailureGuard::start(TEST, 1, || "number = 1".to_owned());
+        guard.finish(&Err::<(), _>("number is odd".to_owned()));
+        let panic_result = panic::catch_unwind(|| {
+            let _guard = CaseFailureGuard::start(TEST, 2, || "number = \"2\"".to_owned());
+            panic!("number is too large");
+        });
+        panic_result.unwrap_err();
+
+        assert_eq!(
+            summary_lines(TEST),
+            [
+                r#"{"test":"failures::recording_failed_cases","case":1,"args":"number = 1","attempts":1,"error":"\"number is odd\""}"#,
+                r#"{"test":"failures::recording_failed_cases","case":2,"args":"number = \"2\"","attempts":1,"error":"number is too large"}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn recording_retried_cases() {
+        const TEST: &str = "failures::recording_retried_cases";
+
+        for attempt in 0..3 {
+            let guard = CaseFailureGuard::start(TEST, 0, || "flag = true".to_owned());
+            guard.finish(&Err::<(), _>(format!("attempt #{attempt} failed")));
+        }
+        for attempt in 0..2 {
+            let guard = CaseFailureGuard::start(TEST, 1, || "flag = false".to_owned());
+            let output = if attempt == 0 { Err("oops") } else { Ok(()) };
+            guard.finish(&output);
+        }
+        // Not completed case
+        let guard = CaseFailureGuard::start(TEST, 2, String::new);
+        std::mem::forget(guard);
+
+        assert_eq!(
+            summary_lines(TEST),
+            [
+                r#"{"test":"failures::recording_retried_cases","case":0,"args":"flag = true","attempts":3,"error":"\"attempt #2 failed\""}"#,
+                r#"{"test":"failures::recording_retried_cases","case":2,"args":"","attempts":1,"error":"test did not complete"}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn truncating_long_errors() {
+        let error = excerpt("!".repeat(MAX_ERROR_LEN + 10));
+        assert!(error.starts_with(&"!".repeat(MAX_ERROR_LEN)), "{error}");
+        assert!(error.ends_with("… (10 chars truncated)"), "{error}");
+    }
+
+    #[test]
+    fn running_all_cases() {
+        let mut runner = AllCasesRunner::new("failures::running_all_cases");
+        runner.run(0, || Ok::<_, &str>(()));
+        runner.run(1, || Err::<(), _>("number is odd"));
+        runner.run::<()>(2, || panic!("number is too large"));
+        runner.run(3, || {});
+
+        let message = runner.failure_message().unwrap();
+        assert_eq!(
+            message,
+            "2 of 4 test case(s) failed in `failures::running_all_cases`:\n  \
+             case #1: \"number is odd\"\n  case #2: number is too large"
+        );
+        let panic_object = panic::catch_unwind(|| runner.finish()).unwrap_err();
+        assert_eq!(panic_message(&*panic_object), message);
+
+        let runner = AllCasesRunner::new("failures::running_all_cases");
+        assert!(runner.failure_message().is_none());
+        runner.finish();
+    }
+
+    #[test]
+    fn running_all_async_cases() {
+        let mut runner = AllCasesRunner::new("failures::running_all_async_cases");
+        async_std::task::block_on(async {
+            runner.run_async(0, async {}).await;
+            runner
+                .run_async::<()>(1, async { panic!("number is too large") })
+                .await;
+            runner.run_async(2, async { Err::<(), _>("oops") }).await;
+        });
+
+        let message = runner.failure_message().unwrap();
+        assert!(
+            message.starts_with("2 of 3 test case(s) failed"),
+            "{message}"
+        );
+        assert!(
+            message.contains("case #1: number is too large"),
+            "{message}"
+        );
+        assert!(message.ends_with("case #2: \"oops\""), "{message}");
+    }
+}
@@ -40,11 +40,28 @@
 //! This allows specifying the (potentially qualified) function name to restrict the test scope.
 //!
 //! If the [`nightly` crate feature](#nightly) is not enabled, names of particular test cases
-//! are not descriptive; they have the `case_NN` format, where `NN` is the 0-based case index.
+//! have the `case_NN` format, where `NN` is the 0-based case index.
 //! The values of arguments provided to the test are printed to the standard output
 //! at the test start. (The standard output is captured and thus may be not visible
 //! unless the `--nocapture` option is specified in the `cargo test` command.)
 //!
+//! If the case expression is an array of literals (or of tuples of literals), or a [`Product`]
+//! of such arrays, the macro evaluates the args during expansion and appends them to the case
+//! names in a sanitized form, e.g. `case_1_foo_bar` for the `"Foo bar"` arg; non-alphanumeric
+//! chars are replaced with `_`, and the appended part is truncated to 40 chars. Values of args
+//! [formatted](macro@test_casing#formatting-arguments) as `redact`, `skip` or `opaque`
+//! are omitted. The generated test functions are also documented with the arg values.
+//! Other case expressions fall back to index-only names, as do all cases if the `names = index`
+//! [attribute option](macro@test_casing#inputs) is specified. Here's an excerpt
+//! from the integration tests for this crate:
+//!
+//! ```text
+//! test expected_output::case_0_foo ... ok
+//! test expected_output::case_1_bar ... ok
+//! test cases_with_redacted_args::case_0_alice ... ok
+//! test cases_with_redacted_args::case_1_bob ... ok
+//! ```
+//!
 //! If the `nightly` feature *is* enabled, the names are more descriptive, containing [`Debug`]
 //! presentation of all args together with their names. Here's an excerpt from the integration
 //! tests for this crate:
@@ -62,6 +79,59 @@
 //! The names are fully considered when filtering tests, meaning that it's possible to run
 //! particular cases using a filter like `cargo test 'number = 5'`.
 //!
+//! # Test tags
+//!
+//! The [`test_casing`](macro@test_casing) macro accepts an optional `tags = [..]` argument,
+//! which encodes tags into the names of generated test cases. This allows targeting test cases
+//! by their tags in test filters, such as [`nextest` filtersets] used to select tests
+//! or to specify per-test overrides (retries, timeouts, etc.).
+//!
+//! Each tag must consist of lowercase ASCII letters and digits, optionally separated by single
+//! `-` or `_` chars (e.g., `slow` or `serial-db`). Tags are encoded as follows:
+//!
+//! - Each tag is converted into the `__tag_{tag}` string, where `-` chars in the tag
+//!   are replaced with `_`. (E.g., `serial-db` is converted into `__tag_serial_db`.)
+//! - Converted tags are concatenated in the order of their declaration, followed by `__`.
+//! - The resulting string is appended to the name of each test case
+//!   (e.g., `case_0__tag_slow__tag_serial_db__`).
+//!
+//! Thus, a test case has a tag `tag` if and only if its name contains `__tag_{tag}__`
+//! (with `-` chars replaced by `_`), regardless of other tags on the test.
+//! For example, the following `nextest` config increases the timeout for tests tagged with `slow`:
+//!
+//! ```toml
+//! [[profile.default.overrides]]
+//! filter = 'test(/__tag_slow__/)'
+//! slow-timeout = { period = "60s", terminate-after = 5 }
+//! ```
+//!
+//! Tags are not supported by the [`decorate`] macro, since renaming the decorated function
+//! would break test attributes (such as `#[test]`) placed before it.
+//!
+//! [`nextest` filtersets]: https://nexte.st/docs/filtersets/
+//!
+//! # Disabling decorators
+//!
+//! If the `TEST_CASING_NO_DECORATORS` env variable is set to a non-empty value other than `0`,
+//! all decorators are bypassed, and decorated tests are called directly. This applies to tests
+//! annotated with the [`decorate`] macro, and to decorators applied with the functions
+//! from the [`decorators`] module, such as [`run_decorated()`](decorators::run_decorated).
+//! Disabling decorators can be useful when running tests under Miri, a debugger or a profiler,
+//! where threads spawned by decorators (e.g., [`Timeout`](decorators::Timeout)) interfere.
+//!
+//! ```shell
+//! TEST_CASING_NO_DECORATORS=1 cargo +nightly miri test
+//! ```
+//!
+//! # Default timeout
+//!
+//! The `TEST_CASING_DEFAULT_TIMEOUT` env variable (e.g., `TEST_CASING_DEFAULT_TIMEOUT=5m`)
+//! specifies a fallback timeout for tests generated by the [`decorate`] and [`test_casing`] macros
+//! that don't have a [`Timeout`](decorators::Timeout) decorator, so that a single hung test
+//! cannot stall the entire test suite. A test exceeding the default timeout fails, while other
+//! tests continue running. The default timeout can also be set programmatically;
+//! see [`Timeout::set_default()`](decorators::Timeout::set_default()) for details.
+//!
 //! # Alternatives and similar tools
 //!
 //! - The approach to test casing from this crate can be reproduced with some amount of copy-pasting
@@ -106,14 +176,159 @@
 //!
 //! [custom test frameworks]: https://github.com/rust-lang/rust/issues/50297
 //! [the CI config]: https://github.com/slowli/test-casing/blob/main/.github/workflows/ci.yml
+//!
+//! Names of the generated tests follow a stable grammar, so that tools consuming test output
+//! (e.g., `cargo test -- -Z unstable-options --format json`) can rely on it:
+//!
+//! ```text
+//! {path}::case_{index}{tags} [{args}]
+//! ```
+//!
+//! - `path` is the path to the tested function within the crate, e.g. `tests::numbers_are_small`.
+//! - `index` is the zero-based case index; unlike function names on stable Rust,
+//!   it is not zero-padded.
+//! - `tags` is the (possibly empty) [tag suffix](#test-tags).
+//! - `args` is the compact description of case args, as printed on stable Rust. It is always
+//!   single-line; control chars (e.g., newlines emitted by custom arg formatting) are escaped
+//!   in the same way as in Rust string literals.
+//!
+//! Since `path`, `index` and `tags` never contain spaces, a test name can be split into the test
+//! path and args at the first occurrence of ` [`; args may contain brackets themselves.
+//!
+//! ## `anyhow`, `eyre`
+//!
+//! *(Off by default)*
+//!
+//! Implement [`ReportError`](decorators::ReportError) for error types from the [`anyhow`]
+//! and [`eyre`] crates, so that tests returning these errors can be decorated with
+//! [`ErrorReport`](decorators::ErrorReport).
+//!
+//! [`anyhow`]: https://docs.rs/anyhow/
+//! [`eyre`]: https://docs.rs/eyre/
+//!
+//! ## `color-eyre`
+//!
+//! *(Off by default; implies `eyre`)*
+//!
+//! Makes the [`PanicReport`](decorators::PanicReport) decorator use panic and error hooks
+//! from the [`color-eyre`] crate.
+//!
+//! [`color-eyre`]: https://docs.rs/color-eyre/
+//!
+//! ## `regex`
+//!
+//! *(Off by default)*
+//!
+//! Allows matching panic messages against regular expressions in the
+//! [`ExpectPanic`](decorators::ExpectPanic) decorator using the [`regex`] crate.
+//!
+//! [`regex`]: https://docs.rs/regex/
+//!
+//! ## `serial_test`
+//!
+//! *(Off by default)*
+//!
+//! Provides the [`Serial`](decorators::Serial) decorator that acquires the same named locks
+//! as the `serial` attribute from the [`serial_test`] crate. Since the decorator relies
+//! on `serial_test` internals, the dependency is pinned to a specific version.
+//!
+//! [`serial_test`]: https://docs.rs/serial_test/
+//!
+//! ## `insta`
+//!
+//! *(Off by default)*
+//!
+//! Provides the [`Snapshots`](decorators::Snapshots) decorator that scopes settings
+//! of the [`insta`] snapshot testing library around the decorated test.
+//!
+//! [`insta`]: https://docs.rs/insta/
+//!
+//! ## `leak-check`
+//!
+//! *(Off by default)*
+//!
+//! Provides the [`LeakCheck`](decorators::LeakCheck) decorator that checks that the decorated test
+//! doesn't leave allocations outstanding, and the [`TrackingAllocator`](decorators::TrackingAllocator)
+//! global allocator it relies on.
+//!
+//! ## `loom`
+//!
+//! *(Off by default)*
+//!
+//! Provides the [`LoomModel`](decorators::LoomModel) decorator that runs the decorated test
+//! under the [`loom`] model checker.
+//!
+//! [`loom`]: https://docs.rs/loom/
+//!
+//! ## `mockito`
+//!
+//! *(Off by default)*
+//!
+//! Provides the [`MockServer`](decorators::MockServer) decorator that starts a mock HTTP server
+//! from the [`mockito`] crate for the duration of the decorated test.
+//!
+//! [`mockito`]: https://docs.rs/mockito/
+//!
+//! ## `shuttle`
+//!
+//! *(Off by default)*
+//!
+//! Provides the [`ShuttleCheck`](decorators::ShuttleCheck) decorator that runs the decorated test
+//! under a randomized scheduler from the [`shuttle`] crate.
+//!
+//! [`shuttle`]: https://docs.rs/shuttle/
+//!
+//! ## `testcontainers`
+//!
+//! *(Off by default)*
+//!
+//! Provides the [`DockerContainer`](decorators::DockerContainer) and
+//! [`SharedContainer`](decorators::SharedContainer) decorators that run Docker containers
+//! for the decorated tests using the [`testcontainers`] crate. Note that this feature
+//! requires a newer Rust version (1.81+) than the rest of the crate.
+//!
+//! [`testcontainers`]: https://docs.rs/testcontainers/
+//!
+//! ## `tokio`, `async-std`
+//!
+//! *(Off by default)*
+//!
+//! Provide the [`AsyncTimeout`](decorators::AsyncTimeout) decorator that tracks the timeout
+//! for async tests using the timer of the [`tokio`] or [`async-std`] runtime, respectively.
+//! The `tokio` feature additionally provides the [`MockClock`](decorators::MockClock) decorator
+//! pausing the `tokio` clock for the test.
+//!
+//! [`tokio`]: https://docs.rs/tokio/
+//! [`async-std`]: https://docs.rs/async-std/
+//!
+//! ## `tracing`
+//!
+//! *(Off by default)*
+//!
+//! Provides the [`LogLevel`](decorators::LogLevel) decorator that overrides log directives
+//! for [`tracing`] events emitted by the decorated test.
+//!
+//! [`tracing`]: https://docs.rs/tracing/
+//!
+//! ## `turmoil`
+//!
+//! *(Off by default)*
+//!
+//! Provides the [`NetworkSim`](decorators::NetworkSim) decorator that runs the decorated test
+//! in a network simulated by the [`turmoil`] crate.
+//!
+//! [`turmoil`]: https://docs.rs/turmoil/
 
 #![cfg_attr(feature = "nightly", feature(custom_test_frameworks, test))]
 // Documentation settings
+#![cfg_attr(docsrs, feature(doc_cfg))]
 #![doc(html_root_url = "https://docs.rs/test-casing/0.1.3")]
 // Linter settings
 #![warn(missing_debug_implementations, missing_docs, bare_trait_objects)]
 #![warn(clippy::all, clippy::pedantic)]
 #![allow(clippy::must_use_candidate, clippy::module_name_repetitions)]
+// Doc tests use `#[test]` attributes followed by a dummy function for illustration purposes.
+#![allow(clippy::test_attr_in_doctest)]
 
 /// Wraps a tested function to add retries, timeouts etc.
 ///
@@ -124,6 +339,10 @@
 /// of one or more [test decorators](decorators::DecorateTest). Each decorator must
 /// be a constant expression (i.e., it should be usable as a definition of a `static` variable).
 ///
+/// A decorated test can be conditionally skipped using the `#[ignore_if(..)]` attribute
+/// placed below `decorate`; see [`test_casing`](macro@test_casing#conditionally-ignoring-tests)
+/// docs for details.
+///
 /// # Examples
 ///
 /// ## Basic usage
@@ -141,7 +360,9 @@
 ///
 /// ## Tests returning `Result`s
 ///
-/// Decorators can be used on tests returning `Result`s, too:
+/// Decorators can be used on tests returning `Result`s, too. Similar to the standard test harness,
+/// the error type is only required to implement [`Debug`](core::fmt::Debug), so that decorators
+/// work with error types from assertion frameworks like [`googletest`](https://docs.rs/googletest/).
 ///
 /// ```
 /// use test_casing::{decorate, decorators::{Retry, Timeout}};
@@ -189,6 +410,20 @@
 /// }
 /// ```
 ///
+/// In this case, decorators wrap the blocking function produced by the test macro.
+/// Alternatively, if the `decorate` macro is applied *before* the test macro, decorators
+/// wrap the test future and operate inside the async runtime. This requires all decorators
+/// to implement [`DecorateTestAsync`](decorators::DecorateTestAsync).
+///
+/// ```
+/// # use test_casing::{decorate, decorators::{Retry, Timeout}};
+/// #[decorate(Timeout::secs(1), Retry::times(3))]
+/// #[async_std::test]
+/// async fn async_test() {
+///     // test logic
+/// }
+/// ```
+///
 /// ## Composability and reuse
 ///
 /// Decorators can be extracted to a `const`ant or a `static` for readability, composability
@@ -222,7 +457,7 @@
 /// ## Use with `test_casing`
 ///
 /// When used together with the [`test_casing`](macro@test_casing) macro, the decorators will apply
-/// to each generated case.
+/// to each generated case. The `decorate` attribute must be placed *after* `test_casing`.
 ///
 /// ```
 /// use test_casing::{decorate, test_casing, decorators::Timeout};
@@ -239,12 +474,13 @@ pub use test_casing_macro::decorate;
 ///
 /// # Inputs
 ///
-/// This attribute must be placed on a freestanding function with 1..8 arguments.
+/// This attribute must be placed on a freestanding function with 1..=12 arguments.
 /// The attribute must be invoked with 2 values:
 ///
-/// 1. Number of test cases, a number literal
+/// 1. Number of test cases, a number literal or a constant expression of type `usize`
 /// 2. A *case iterator* expression evaluating to an implementation of [`IntoIterator`]
-///    with [`Debug`]gable, `'static` items.
+///    with `'static` items. Items must be [`Debug`]gable, unless the corresponding args
+///    are [formatted](#formatting-arguments) without using `Debug`.
 ///    If the target function has a single argument, the iterator item type must equal to
 ///    the argument type. Otherwise, the iterator must return a tuple in which each item
 ///    corresponds to the argument with the same index.
@@ -254,8 +490,166 @@ pub use test_casing_macro::decorate;
 /// return at least the number of items specified as the first attribute argument, and can
 /// return more items; these additional items will not be tested.
 ///
+/// If the number of test cases is not a literal (e.g., it's a path to a constant
+/// or `CASES.len()`), the macro cannot know the number of cases. In this case, all cases
+/// are tested sequentially in a single test named `all_cases` (including with the `nightly`
+/// feature, since test cases must be declared during macro expansion). A failing case
+/// (either panicking or returning an error) doesn't prevent testing the following cases;
+/// once all cases are tested, the test panics with a message listing all failed cases.
+/// Because a single test cannot express expectations for individual cases, `#[should_panic]`
+/// is rejected with a compile error in this mode. A single case can still be selected
+/// by its index as described [below](#running-a-single-case). Use a literal number of cases
+/// to get a separate test for each case (which can be filtered, ignored or retried individually).
+///
+/// If the number of test cases is specified as `0` (e.g., if the set of cases is platform-specific
+/// and is empty for the current target), no cases are generated. Instead, the macro generates
+/// an ignored marker test named `no_cases`, which notes that the set of cases is empty.
+///
+/// Besides the cases, the macro generates an ignored test named `validate_cases`. This test
+/// constructs all cases and prints their args, but doesn't run the target function.
+/// It can be used to quickly check that the case expression and [`Debug`] implementations
+/// for args don't panic before running a long test suite, e.g. via
+/// `cargo test -- --ignored validate_cases`.
+///
+/// Optionally, the attribute may have additional arguments:
+///
+/// - `tags = ["tag", ..]`. The tags are [encoded](index.html#test-tags) into the names
+///   of all generated test cases.
+/// - `group = name`. Specifies the group of cases; see [below](#case-groups) for details.
+/// - `async_with = path::to::test`. Specifies the test attribute for async functions;
+///   see [below](#async-tests) for details.
+/// - `print_args = compact | pretty | json`. Specifies how case args are printed before running
+///   a test case. By default (`compact`), all args are printed on a single line. With `pretty`,
+///   each arg is printed on a separate line, and large values are printed using the multi-line
+///   `{:#?}` format. With `json`, the case is printed as a single-line JSON object
+///   with the test path, case index and args (with `Debug` representations of the args
+///   as string values), e.g. `{"test":"tests::parsing","case":0,"args":{"s":"\"42\""}}`.
+///   This can be used to parse case args from test logs by log aggregators.
+/// - `max_arg_width = N`. Truncates printed args to at most `N` chars, marking the truncated
+///   part. This is useful if the args are large, e.g. blobs or long strings.
+/// - `names = descriptive | index`. Specifies how generated test cases are named on stable Rust.
+///   By default (`descriptive`), arg values of literal cases are appended to the case index
+///   (e.g., `case_1_foo_bar`); with `index`, the names only contain the index (e.g., `case_1`).
+/// - `case_fields = path::to::Struct`. Specifies that the case iterator yields instances
+///   of the specified struct rather than tuples. The arguments of the target function
+///   are bound to the struct fields with the same names, regardless of their order;
+///   struct fields not corresponding to any argument are ignored. This is more readable
+///   and less fragile than large tuples.
+/// - `export`. Generates functions running the cases outside of tests; see [below](#exporting-cases)
+///   for details.
+/// - `exhaustive = domain`. Generates a test checking that the cases cover all values
+///   from the specified domain; see [below](#checking-coverage) for details.
+///
 /// [`Debug`]: core::fmt::Debug
 ///
+/// # Case groups
+///
+/// Multiple `test_casing` attributes may be placed on a single function, e.g. to combine
+/// a curated set of cases with a randomly sampled one. In this case, each attribute must specify
+/// a unique group name via the `group = name` argument. Cases for each group are generated
+/// in a separate child module named after the group (e.g., `basic::case_0`, `fuzz::case_0`).
+/// All test attributes (e.g., `#[test]` or `#[should_panic]`) must be placed after
+/// the `test_casing` attributes.
+///
+/// # Test suites
+///
+/// Test names are derived from the module structure, so the idiomatic way to group related tested
+/// functions is to place them into a common module. For example, the cases below are named
+/// `parser_tests::parse_int::case_0_0`, `parser_tests::parse_int::case_1_42` etc.,
+/// and can be selected together with a filter like `cargo test parser_tests::`.
+///
+/// ```
+/// mod parser_tests {
+///     use test_casing::test_casing;
+///
+///     #[test_casing(2, ["0", "42"])]
+///     fn parse_int(s: &str) {
+///         s.parse::<u32>().unwrap();
+///     }
+///
+///     #[test_casing(2, ["0.5", "-1"])]
+///     fn parse_float(s: &str) {
+///         s.parse::<f64>().unwrap();
+///     }
+/// }
+/// ```
+///
+/// # Exporting cases
+///
+/// With the `export` argument, the macro additionally generates a `pub(crate)` constant
+/// `CASE_COUNT` with the number of cases and a `pub(crate)` function `run_case(index)`, which
+/// binds the case with the specified zero-based index to the function args (including
+/// [mapping](#mapping-arguments) and [defaults](#default-arguments)) and calls the function.
+/// These items are placed in the module named after the tested function, and are available
+/// in non-test builds as well. Thus, they can be used in benchmarks, examples or fuzz targets
+/// to exercise the same cases without duplicating the setup. (In this case, the tested function
+/// must be available in non-test builds, and `test-casing` must be a regular dependency.)
+/// For a [case group](#case-groups), the names are suffixed with the group name,
+/// e.g. `CASE_COUNT_BASIC` and `run_case_basic()`.
+///
+/// ```
+/// # use test_casing::test_casing;
+/// #[test_casing(3, [2, 3, 5], export)]
+/// fn number_is_prime(number: u32) {
+///     assert!((2..number).all(|i| number % i != 0));
+/// }
+///
+/// # fn main() {
+/// // E.g., in a benchmark:
+/// for i in 0..number_is_prime::CASE_COUNT {
+///     number_is_prime::run_case(i);
+/// }
+/// # }
+/// ```
+///
+/// # Checking coverage
+///
+/// With the `exhaustive = domain` argument, the macro additionally generates a test named
+/// `check_coverage`, which checks that the tested cases cover all values from the domain
+/// (e.g., all variants of an enum, or all keys of a lookup table), and fails listing
+/// the missing values otherwise. This guards the cases against falling out of sync
+/// with the tested code. The domain is an expression returning an iterable over values
+/// comparable to the cases via [`PartialEq`]; e.g., if the tested function has multiple args,
+/// the domain values must be comparable to tuples. The cases may contain values
+/// not present in the domain. See [`assert_cases_cover()`] for more details.
+///
+/// ```
+/// # use test_casing::test_casing;
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Color {
+///     Red,
+///     Green,
+///     Blue,
+/// }
+///
+/// impl Color {
+///     const ALL: [Self; 3] = [Self::Red, Self::Green, Self::Blue];
+/// }
+///
+/// #[test_casing(3, [Color::Red, Color::Green, Color::Blue], exhaustive = Color::ALL)]
+/// fn color_is_supported(color: Color) {
+///     // test logic
+/// }
+/// ```
+///
+/// [`PartialEq`]: core::cmp::PartialEq
+///
+/// # Per-argument values
+///
+/// Alternatively, the attribute may be invoked without args, with each argument of the target
+/// function having a `#[values(..)]` attribute listing the values for this argument. Test cases
+/// are generated as the Cartesian product of the values for all args (i.e., as a [`Product`]),
+/// and the number of cases is computed automatically. This is more readable than
+/// a separate case expression for small sets of values.
+///
+/// # Inline cases
+///
+/// For small tests, cases can be specified inline with one `#[case(..)]` attribute per case,
+/// placed after the `#[test_casing(inline)]` attribute. Each `#[case(..)]` attribute must
+/// contain values for all args (except for [args with defaults](#default-arguments)).
+/// The number of cases is computed automatically. Other arguments described [above](#inputs)
+/// may follow `inline`, e.g. `#[test_casing(inline, tags = ["slow"])]`.
+///
 /// # Mapping arguments
 ///
 /// To support more idiomatic signatures for parameterized test functions, it is possible
@@ -266,6 +660,153 @@ pub use test_casing_macro::decorate;
 /// [`String::as_str()`]). This is specified as `#[map(ref = path::to::method)]`, a la
 /// `serde` transforms.
 ///
+/// # Default arguments
+///
+/// Trailing arguments of the target function may specify default values via the
+/// `#[default(..)]` attribute, e.g. `#[default(Mode::Fast)] mode: Mode`. Such arguments
+/// are not provided by the case iterator; that is, the iterator yields shorter tuples
+/// (or single values if there's a single argument without a default), and the macro supplies
+/// the default values for the remaining arguments. This allows to add a parameter
+/// to a test function without changing existing case expressions. Arguments with defaults
+/// are not printed and cannot have `#[map(..)]` or `#[fmt(..)]` attributes.
+///
+/// # Expected output
+///
+/// Similarly to [default arguments](#default-arguments), a trailing argument of type [`Expected`]
+/// with the `#[expected(file = "..")]` attribute is not provided by the case iterator.
+/// Instead, it's loaded from a per-case file. The file path may contain the following
+/// placeholders:
+///
+/// - `{fn}` is replaced with the name of the target function.
+/// - `{case}` is replaced with `case_` followed by the zero-based case index, e.g. `case_3`.
+///   Unlike generated test names, the index is not zero-padded, so that adding cases
+///   doesn't rename existing files.
+/// - `{index}` is replaced with the zero-based case index.
+///
+/// A relative path is resolved relative to the current directory; for tests run by Cargo,
+/// this is the root directory of the tested crate. The actual output can be compared with
+/// the expected one using [`Expected::assert_eq()`]. If the `TEST_CASING_UPDATE_EXPECTED`
+/// env variable is set, the expected-output files are (re)generated from the actual output
+/// instead.
+///
+/// # Property tests
+///
+/// The `test_casing` attribute can be stacked above a property test attribute, such as
+/// `#[proptest]` from the [`test-strategy`] crate or `#[quickcheck]` from the [`quickcheck_macros`]
+/// crate. In this case, the cases provide values for the leading args of the target function,
+/// while the property test attribute drives the remaining args marked with the `#[property]`
+/// attribute. That is, each generated test case is a separate property test with fixed values
+/// of the case args. Args with `#[property]` must be placed after all args provided by cases
+/// (but before args with [defaults](#default-arguments)), and they cannot have other attributes
+/// supported by this macro. Other attributes on these args (e.g., `#[strategy(..)]`)
+/// are forwarded to the generated case functions.
+///
+/// The property test attribute generates the test attribute itself, so `#[test]` must not
+/// be specified. Since a property test runs the target function many times, a case is printed
+/// only once. Async target functions are not supported.
+///
+/// ```ignore
+/// use test_strategy::proptest;
+/// # use test_casing::test_casing;
+///
+/// #[test_casing(2, [10, 100])]
+/// #[proptest]
+/// fn saturating_add_is_bounded(limit: u32, #[property] #[strategy(0..1_000_u32)] value: u32) {
+///     assert!(value.saturating_add(limit).min(limit) <= limit);
+/// }
+/// ```
+///
+/// [`test-strategy`]: https://docs.rs/test-strategy/
+/// [`quickcheck_macros`]: https://docs.rs/quickcheck_macros/
+///
+/// # Formatting arguments
+///
+/// Before running a test case, its args are printed using their [`Debug`] implementations.
+/// (With the `nightly` feature, the args are also included into the test names.) This can be
+/// customized for separate args using the `#[fmt(..)]` attribute:
+///
+/// - `#[fmt(redact)]` replaces the arg value with `***`. This is useful for sensitive values,
+///   such as secrets or tokens, which should not leak into CI logs.
+/// - `#[fmt(skip)]` omits the arg altogether.
+/// - `#[fmt(opaque)]` replaces the arg value with `<opaque>`. This is useful for args
+///   not implementing [`Debug`], such as closures, channels or generated clients.
+/// - `#[fmt(hex)]` prints the arg as a hex string of its bytes. The arg type must implement
+///   `AsRef<[u8]>`; this is useful for byte buffers.
+/// - `#[fmt(display)]` prints the arg using its [`Display`](core::fmt::Display) implementation.
+/// - `#[fmt(with = path::to::function)]` prints the arg using the specified function.
+///   The function must have the `fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result` signature,
+///   where `T` is the arg type returned by the case iterator (i.e., before
+///   [mapping](#mapping-arguments)).
+///
+/// In all cases, the arg is still passed to the test function as usual. Only args printed
+/// using their `Debug` implementation (i.e., without the `#[fmt(..)]` attribute) are required
+/// to implement `Debug`.
+///
+/// By default, test cases are printed to stdout. This can be changed using the
+/// `TEST_CASING_CASE_OUTPUT` env variable: `stderr` prints test cases to stderr, and `none`
+/// disables printing altogether. This can be useful if the test harness treats unexpected
+/// stdout output as test pollution.
+///
+/// ```shell
+/// TEST_CASING_CASE_OUTPUT=stderr cargo test
+/// ```
+///
+/// # Running a single case
+///
+/// If the `TEST_CASING_ONLY` env variable is set, all generated test cases not matching it
+/// are skipped (i.e., pass without running the test function) with a message printed
+/// as described above. The variable may contain either a zero-based case index, or a name
+/// of the generated test, optionally prefixed by its parent modules (e.g., `case_1`
+/// or `tested_fn::case_1`). The [descriptive suffix](index.html#test-cases-structure)
+/// of the name may be omitted, so `case_1` also selects `case_1_foo`. This is useful to iterate on a single failing case
+/// in environments where libtest filtering by name is awkward, such as IDEs or wrapper scripts.
+/// Note that an index filter applies to all `#[test_casing]` tests, so it's usually combined
+/// with a name filter for the test function:
+///
+/// ```shell
+/// TEST_CASING_ONLY=3 cargo test numbers_are_small
+/// ```
+///
+/// # Failure summary
+///
+/// If the `TEST_CASING_FAILURE_SUMMARY` env variable is set to a file path, failed test cases
+/// are recorded and appended to this file on process exit, one JSON object per line:
+///
+/// ```text
+/// {"test":"tests::numbers_are_small::case_3","case":3,"args":"number = 5","attempts":1,"error":"assertion failed: number < 5"}
+/// ```
+///
+/// Here, `test` is the path to the generated test, `case` is the zero-based case index, and `args`
+/// are the case args printed as described above. `error` is an excerpt of the panic message
+/// or of the `Debug` representation of the error returned by the test function. `attempts`
+/// is the number of times the case was run, which is greater than 1 if the test was
+/// [retried](crate::decorators::Retry). Cases that haven't completed by process exit
+/// (e.g., because of a [timeout](crate::decorators::Timeout)) are recorded with
+/// the "test did not complete" error.
+///
+/// The file is not truncated, so it should be removed before the test run; this allows
+/// several test binaries (e.g., ones run by `cargo test`) to write to the same file.
+/// Tests marked with `#[should_panic]` and property tests are not recorded.
+///
+/// ```shell
+/// TEST_CASING_FAILURE_SUMMARY=target/failures.jsonl cargo test
+/// ```
+///
+/// # Conditionally ignoring tests
+///
+/// The `#[ignore_if(condition, reason = "..")]` attribute placed below the `test_casing` attribute
+/// marks all generated tests as ignored if the condition holds; `reason` is optional.
+/// The condition can be either a `cfg` predicate (e.g., `cfg(miri)`), or an arbitrary `bool`
+/// expression (e.g., a path to a constant, or a function call).
+///
+/// With a `cfg` predicate, the tests are ignored in the same way as with the `ignore` attribute.
+/// Other conditions cannot be expressed with attributes, so on stable Rust, the tests are skipped
+/// at runtime instead; i.e., they pass without running the test function and print a message.
+/// With the `nightly` crate feature, the tests are marked as ignored in both cases.
+///
+/// The attribute is also supported by the [`decorate`] macro. In this case, the test is always
+/// skipped at runtime since the test attribute is expanded before `decorate`.
+///
 /// # Examples
 ///
 /// ## Basic usage
@@ -348,6 +889,65 @@ pub use test_casing_macro::decorate;
 /// }
 /// ```
 ///
+/// The same cases can be specified using [per-argument values](#per-argument-values):
+///
+/// ```
+/// # use test_casing::test_casing;
+/// #[test_casing]
+/// fn numbers_and_strings(#[values(0, 1, 2)] number: usize, #[values("foo", "bar")] s: &str) {
+///     assert!(s.len() <= number);
+/// }
+/// ```
+///
+/// ## Expected output
+///
+/// ```no_run
+/// # use test_casing::{test_casing, Expected};
+/// #[test_casing(3, ["foo", "Bar", "BAZ"])]
+/// fn converting_to_uppercase(
+///     s: &str,
+///     #[expected(file = "tests/expected/{fn}/{case}.txt")] expected: Expected,
+/// ) {
+///     expected.assert_eq(s.to_uppercase());
+/// }
+/// ```
+///
+/// ## Inline cases
+///
+/// ```
+/// # use test_casing::test_casing;
+/// #[test_casing(inline)]
+/// #[case(2, "2")]
+/// #[case(42, "+42")]
+/// fn parsing_number(number: u64, s: &str) {
+///     assert_eq!(s.parse::<u64>().unwrap(), number);
+/// }
+/// ```
+///
+/// ## Struct cases
+///
+/// Instead of tuples, cases can be specified as structs with fields bound to the args
+/// with the same names.
+///
+/// ```
+/// # use test_casing::test_casing;
+/// #[derive(Debug)]
+/// struct ParsingCase {
+///     input: &'static str,
+///     expected: u64,
+/// }
+///
+/// const CASES: [ParsingCase; 2] = [
+///     ParsingCase { input: "42", expected: 42 },
+///     ParsingCase { input: "+7", expected: 7 },
+/// ];
+///
+/// #[test_casing(2, CASES, case_fields = ParsingCase)]
+/// fn parsing_number(input: &str, expected: u64) {
+///     assert_eq!(input.parse::<u64>().unwrap(), expected);
+/// }
+/// ```
+///
 /// ## Reference args
 ///
 /// It is possible to go from a generated argument to its reference by adding
@@ -375,6 +975,57 @@ pub use test_casing_macro::decorate;
 /// }
 /// ```
 ///
+/// ## Redacted args
+///
+/// ```
+/// # use test_casing::test_casing;
+/// #[test_casing(2, [("alice", "token"), ("bob", "other-token")])]
+/// fn authenticating(user: &str, #[fmt(redact)] token: &str) {
+///     // Snipped...
+/// }
+/// ```
+///
+/// ## Non-`Debug` args
+///
+/// ```
+/// # use test_casing::test_casing;
+/// // Doesn't implement `Debug`
+/// struct Client {
+///     base_url: &'static str,
+/// }
+///
+/// #[test_casing(2, [
+///     ("http", Client { base_url: "http://localhost" }),
+///     ("https", Client { base_url: "https://localhost" }),
+/// ])]
+/// fn using_client(scheme: &str, #[fmt(opaque)] client: Client) {
+///     assert!(client.base_url.starts_with(scheme));
+/// }
+/// ```
+///
+/// ## Custom arg formatting
+///
+/// ```
+/// # use test_casing::test_casing;
+/// use std::{fmt, net::Ipv4Addr};
+///
+/// fn fmt_len(values: &Vec<u32>, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+///     write!(formatter, "{} values", values.len())
+/// }
+///
+/// #[test_casing(2, [
+///     (Ipv4Addr::LOCALHOST, b"\xde\xad", vec![1, 2, 3]),
+///     (Ipv4Addr::UNSPECIFIED, b"\xbe\xef", vec![]),
+/// ])]
+/// fn formatting_args(
+///     #[fmt(display)] addr: Ipv4Addr,
+///     #[fmt(hex)] bytes: &[u8; 2],
+///     #[fmt(with = fmt_len)] values: Vec<u32>,
+/// ) {
+///     // Prints e.g. `addr = 127.0.0.1, bytes = dead, values = 3 values`
+/// }
+/// ```
+///
 /// ## `ignore` and `should_panic` attributes
 ///
 /// `ignore` or `should_panic` attributes can be specified below the `test_casing` attribute.
@@ -395,6 +1046,25 @@ pub use test_casing_macro::decorate;
 /// }
 /// ```
 ///
+/// The `ignore_if` attribute ignores generated tests conditionally:
+///
+/// ```
+/// # use test_casing::test_casing;
+/// const HAS_GPU: bool = false;
+///
+/// #[test_casing(2, ["cpu", "gpu"])]
+/// #[ignore_if(cfg(miri), reason = "too slow for Miri")]
+/// fn computing_on_device(device: &str) {
+///     // test logic
+/// }
+///
+/// #[test_casing(2, [1, 16])]
+/// #[ignore_if(!HAS_GPU, reason = "requires GPU")]
+/// fn computing_on_gpu(batch_size: usize) {
+///     // test logic
+/// }
+/// ```
+///
 /// ## Async tests
 ///
 /// `test_casing` supports all kinds of async test wrappers, such as `async_std::test`,
@@ -411,12 +1081,36 @@ pub use test_casing_macro::decorate;
 ///     Ok(())
 /// }
 /// ```
+///
+/// Alternatively, the async test attribute can be specified using the `async_with` argument
+/// of the `test_casing` attribute, mirroring how `#[test]` is added automatically for sync
+/// functions. This is convenient e.g. when defining a custom attribute macro on top of
+/// `test_casing`.
+///
+/// ```
+/// # use test_casing::test_casing;
+/// # use std::error::Error;
+/// #[test_casing(3, ["0", "42", "-3"], async_with = async_std::test)]
+/// async fn parsing_numbers(s: &str) -> Result<(), Box<dyn Error>> {
+///     assert!(s.parse::<i32>()?.abs() < 100);
+///     Ok(())
+/// }
+/// ```
 pub use test_casing_macro::test_casing;
 
 pub mod decorators;
+mod expected;
+mod failures;
 #[cfg(feature = "nightly")]
 #[doc(hidden)] // used by the `#[test_casing]` macro; logically private
 pub mod nightly;
 mod test_casing;
 
-pub use crate::test_casing::{case, ArgNames, Product, ProductIter, TestCases};
+pub use crate::expected::Expected;
+#[doc(hidden)] // used by the `#[test_casing]` macro; logically private
+pub use crate::failures::{AllCasesRunner, CaseFailureGuard, CaseOutput};
+pub use crate::test_casing::{
+    assert_case_count, assert_cases_cover, case, is_case_skipped, next_case, print_case, ArgNames,
+    CaseCache, FormatDisplay, FormatHex, FormatWith, NestedProductIter, Opaque, PrintOptions,
+    Product, ProductIter, ProductVecIter, Redacted, TestCases,
+};
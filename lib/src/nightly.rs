@@ -45,18 +45,17 @@ impl ops::Deref for TestDescAndFn {
 }
 
 #[doc(hidden)]
-pub fn create_test_description<T: fmt::Debug>(
+pub fn create_test_description(
     is_unit_test: bool,
     base_name: &'static str,
-    arg_names: impl crate::ArgNames<T>,
-    cases: impl IntoIterator<Item = T>,
+    describe_case: impl FnOnce(usize) -> String,
     index: usize,
+    name_suffix: &str,
 ) -> TestDesc {
     let path_in_crate = base_name.split_once("::").map_or("", |(_, path)| path);
-    let test_args = crate::case(cases, index);
-    let description = arg_names.print_with_args(&test_args);
+    let description = describe_case(index);
     TestDesc {
-        name: TestName::DynTestName(format!("{path_in_crate}::case_{index} [{description}]")),
+        name: TestName::DynTestName(test_name(path_in_crate, index, name_suffix, &description)),
         ignore: false,
         ignore_message: None,
         source_file: "",
@@ -75,6 +74,27 @@ pub fn create_test_description<T: fmt::Debug>(
     }
 }
 
+/// Creates a test name according to the grammar documented in the crate docs:
+/// `{path}::case_{index}{tags} [{args}]`.
+fn test_name(path_in_crate: &str, index: usize, name_suffix: &str, description: &str) -> String {
+    let args = sanitize_description(description);
+    format!("{path_in_crate}::case_{index}{name_suffix} [{args}]")
+}
+
+/// Makes the case description single-line by escaping control chars (including newlines),
+/// which could otherwise be emitted by custom arg formatting.
+fn sanitize_description(description: &str) -> String {
+    let mut sanitized = String::with_capacity(description.len());
+    for ch in description.trim().chars() {
+        if ch.is_control() {
+            sanitized.extend(ch.escape_default());
+        } else {
+            sanitized.push(ch);
+        }
+    }
+    sanitized
+}
+
 pub fn set_location(
     desc: &mut TestDesc,
     source_file: &'static str,
@@ -95,6 +115,12 @@ pub fn set_ignore(desc: &mut TestDesc, message: Option<&'static str>) {
     desc.ignore_message = message;
 }
 
+pub fn set_ignore_if(desc: &mut TestDesc, condition: bool, message: Option<&'static str>) {
+    if condition && !desc.ignore {
+        set_ignore(desc, message);
+    }
+}
+
 pub fn set_should_panic(desc: &mut TestDesc, message: Option<&'static str>) {
     desc.should_panic = match message {
         None => ShouldPanic::Yes,
@@ -115,11 +141,12 @@ macro_rules! declare_test_case {
         start_col: $start_col:expr,
         end_line: $end_line:expr,
         end_col: $end_col:expr,
-        arg_names: $arg_names:expr,
-        cases: $cases:expr,
+        describe_case: $describe_case:expr,
         index: $test_index:expr,
+        name_suffix: $name_suffix:expr,
         $(ignore: $ignore:expr,)?
         $(panic_message: $panic_message:expr,)?
+        $(ignore_if: ($ignore_condition:expr, $ignore_reason:expr),)?
         testfn: $test_fn:path
     ) => {
         $crate::nightly::LazyTestCase::new(|| {
@@ -127,9 +154,9 @@ macro_rules! declare_test_case {
             let mut desc = $crate::nightly::create_test_description(
                 is_unit_test,
                 $base_name,
-                $arg_names,
-                $cases,
+                $describe_case,
                 $test_index,
+                $name_suffix,
             );
             $crate::nightly::set_location(
                 &mut desc,
@@ -145,9 +172,51 @@ macro_rules! declare_test_case {
             $(
             $crate::nightly::set_should_panic(&mut desc, $panic_message);
             )?
+            $(
+            $crate::nightly::set_ignore_if(&mut desc, $ignore_condition, $ignore_reason);
+            )?
             $crate::nightly::TestDescAndFn::new(desc, || {
                 $crate::nightly::assert_test_result($test_fn())
             })
         })
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses a test name according to the documented grammar.
+    fn parse_test_name(name: &str) -> (&str, &str) {
+        let (path, args) = name.split_once(" [").unwrap();
+        let args = args.strip_suffix(']').unwrap();
+        (path, args)
+    }
+
+    #[test]
+    fn test_names_follow_grammar() {
+        let name = test_name("tests::tested_fn", 3, "", "s = \"[a]\", n = 5");
+        assert_eq!(name, "tests::tested_fn::case_3 [s = \"[a]\", n = 5]");
+        assert_eq!(
+            parse_test_name(&name),
+            ("tests::tested_fn::case_3", "s = \"[a]\", n = 5")
+        );
+
+        let name = test_name("tested_fn", 0, "__tag_slow__", "");
+        assert_eq!(
+            parse_test_name(&name),
+            ("tested_fn::case_0__tag_slow__", "")
+        );
+    }
+
+    #[test]
+    fn sanitizing_case_description() {
+        let name = test_name("tested_fn", 1, "", "value = Custom {\n  x: 1\r\n}\n");
+        assert!(!name.chars().any(char::is_control), "{name}");
+        let (_, args) = parse_test_name(&name);
+        assert_eq!(args, r"value = Custom {\n  x: 1\r\n}");
+
+        let name = test_name("tested_fn", 1, "", "bell = \u{7}");
+        assert_eq!(parse_test_name(&name).1, r"bell = \u{7}");
+    }
+}
@@ -1,44 +1,615 @@
 //! Support types for the `test_casing` macro.
 
-use std::{fmt, iter::Fuse};
+use std::{
+    any::Any,
+    env,
+    fmt::{self, Write as _},
+    iter::Fuse,
+    sync::{Mutex, OnceLock, PoisonError},
+};
 
-/// Obtains a test case from an iterator.
+/// Obtains a test case from an iterator. `declared_count` and `source` (the rendered cases
+/// expression) are only used for diagnostics if the iterator is too short.
 #[doc(hidden)] // used by the `#[test_casing]` macro; logically private
-pub fn case<I: IntoIterator>(iter: I, index: usize) -> I::Item
+#[track_caller]
+pub fn case<I: IntoIterator>(
+    iter: I,
+    index: usize,
+    declared_count: usize,
+    source: &str,
+) -> I::Item {
+    let mut actual_count = 0;
+    for item in iter {
+        if actual_count == index {
+            return item;
+        }
+        actual_count += 1;
+    }
+    panic!(
+        "case #{index} not provided from the cases iterator: {}",
+        count_mismatch(source, declared_count, actual_count)
+    );
+}
+
+/// Obtains the next test case from an iterator that has already yielded `index` cases.
+#[doc(hidden)] // used by the `#[test_casing]` macro; logically private
+#[track_caller]
+pub fn next_case<I: Iterator>(
+    iter: &mut I,
+    index: usize,
+    declared_count: usize,
+    source: &str,
+) -> I::Item {
+    let Some(item) = iter.next() else {
+        panic!(
+            "case #{index} not provided from the cases iterator: {}",
+            count_mismatch(source, declared_count, index)
+        );
+    };
+    item
+}
+
+type BoxedCase = Box<dyn Any + Send>;
+
+/// Test cases shared among tests generated by a single `#[test_casing]` attribute.
+/// Cases are materialized once on the first access, so that each case is produced exactly once
+/// (instead of rebuilding and advancing the iterator for each case).
+#[doc(hidden)] // used by the `#[test_casing]` macro; logically private
+pub struct CaseCache {
+    cases: Mutex<Option<Vec<Option<BoxedCase>>>>,
+}
+
+impl Default for CaseCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for CaseCache {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.debug_struct("CaseCache").finish_non_exhaustive()
+    }
+}
+
+impl CaseCache {
+    pub const fn new() -> Self {
+        Self {
+            cases: Mutex::new(None),
+        }
+    }
+
+    // Closures are avoided so that panics are attributed to the `#[test_casing]` attribute
+    // via `#[track_caller]`.
+    #[track_caller]
+    fn with_slot<I, R>(
+        &self,
+        iter: impl FnOnce() -> I,
+        index: usize,
+        declared_count: usize,
+        source: &str,
+        action: impl FnOnce(&mut Option<BoxedCase>) -> R,
+    ) -> R
+    where
+        I: IntoIterator,
+        I::Item: Send + 'static,
+    {
+        // Poisoning can only be caused by a panic when materializing cases, in which case
+        // `cases` remains `None`, and materialization will be retried (and will fail again).
+        let mut cases = self.cases.lock().unwrap_or_else(PoisonError::into_inner);
+        if cases.is_none() {
+            let mut iter = iter().into_iter();
+            let mut materialized = Vec::with_capacity(declared_count);
+            for i in 0..declared_count {
+                let case = next_case(&mut iter, i, declared_count, source);
+                materialized.push(Some(Box::new(case) as BoxedCase));
+            }
+            *cases = Some(materialized);
+        }
+        let cases = cases.as_mut().unwrap();
+        // ^ `unwrap()` is safe; cases are materialized above
+        action(&mut cases[index])
+    }
+
+    /// Takes the case with the specified index. If the case was already taken (e.g., because
+    /// the test is retried), it is recreated from the iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator doesn't provide the case.
+    #[track_caller]
+    pub fn take<I>(
+        &self,
+        iter: impl Fn() -> I,
+        index: usize,
+        declared_count: usize,
+        source: &str,
+    ) -> I::Item
+    where
+        I: IntoIterator,
+        I::Item: Send + 'static,
+    {
+        let case = self.with_slot(&iter, index, declared_count, source, Option::take);
+        match case {
+            Some(case) => *case.downcast().expect("unexpected case type"),
+            None => crate::case(iter(), index, declared_count, source),
+        }
+    }
+
+    /// Prints the case with the specified index without taking it. `_type_check` is never called;
+    /// it ensures that the item type is inferred in the same way as for [`Self::take()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator doesn't provide the case, or if the case was already taken.
+    #[track_caller]
+    pub fn print<I>(
+        &self,
+        iter: impl FnOnce() -> I,
+        index: usize,
+        declared_count: usize,
+        source: &str,
+        _type_check: impl FnOnce(I::Item),
+        print: impl FnOnce(&I::Item) -> String,
+    ) -> String
+    where
+        I: IntoIterator,
+        I::Item: Send + 'static,
+    {
+        self.with_slot(iter, index, declared_count, source, |slot| {
+            let case = slot.as_ref().expect("case was already taken");
+            print(case.downcast_ref().expect("unexpected case type"))
+        })
+    }
+}
+
+fn count_mismatch(source: &str, declared_count: usize, actual_count: usize) -> String {
+    let actual = if actual_count > declared_count {
+        format!("more than {declared_count}")
+    } else {
+        actual_count.to_string()
+    };
+    format!("`{source}` yielded {actual} case(s), while {declared_count} case(s) were declared")
+}
+
+/// Checks that the provided cases iterator yields exactly `declared_count` items.
+/// `source` is a human-readable description of the cases (e.g., the expression
+/// constructing them) included in the panic message.
+///
+/// The `#[test_casing]` macro doesn't require the cases iterator to be exact (e.g., infinite
+/// iterators are fine as long as the needed number of cases is taken from them).
+/// This function can be used in tests or in custom test harnesses to check that the number
+/// of cases is exactly as expected. At most `declared_count + 1` items are taken
+/// from the iterator.
+///
+/// # Panics
+///
+/// Panics if the number of cases differs from `declared_count`.
+///
+/// # Examples
+///
+/// ```
+/// # use test_casing::{assert_case_count, cases, TestCases};
+/// const CASES: TestCases<u32> = cases!([2, 3, 5, 8]);
+///
+/// assert_case_count(CASES, 4, "CASES");
+/// ```
+#[track_caller]
+pub fn assert_case_count<I: IntoIterator>(iter: I, declared_count: usize, source: &str) {
+    let actual_count = iter.into_iter().take(declared_count + 1).count();
+    assert!(
+        actual_count == declared_count,
+        "unexpected number of cases: {}",
+        count_mismatch(source, declared_count, actual_count)
+    );
+}
+
+/// Checks that the provided cases cover all values from the `domain` (e.g., all variants
+/// of an enum or all keys of a lookup table). Case items and domain values are compared
+/// using [`PartialEq`]; the cases may contain values not present in the domain.
+/// `source` is a human-readable description of the cases included in the panic message.
+///
+/// Since the cases iterator may be infinite, it should be limited (e.g., using
+/// [`Iterator::take()`]) to the number of actually tested cases. The `test_casing` macro
+/// does this automatically for the test generated with the `exhaustive` argument.
+///
+/// # Panics
+///
+/// Panics if some domain values are not covered by the cases; the panic message lists
+/// all missing values.
+///
+/// # Examples
+///
+/// ```
+/// # use test_casing::{assert_cases_cover, cases, TestCases};
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Op {
+///     Add,
+///     Sub,
+/// }
+///
+/// impl Op {
+///     const ALL: [Self; 2] = [Self::Add, Self::Sub];
+/// }
+///
+/// const CASES: TestCases<(Op, u32)> = cases!([(Op::Add, 1), (Op::Sub, 2), (Op::Add, 3)]);
+///
+/// assert_cases_cover(CASES.into_iter().map(|(op, _)| op), Op::ALL, "CASES");
+/// ```
+#[track_caller]
+pub fn assert_cases_cover<I, D>(cases: I, domain: D, source: &str)
 where
-    I::Item: fmt::Debug,
+    I: IntoIterator,
+    I::Item: PartialEq<D::Item>,
+    D: IntoIterator,
+    D::Item: fmt::Debug,
 {
-    iter.into_iter().nth(index).unwrap_or_else(|| {
-        panic!("case #{index} not provided from the cases iterator");
-    })
+    let cases: Vec<_> = cases.into_iter().collect();
+    let missing: Vec<_> = domain
+        .into_iter()
+        .filter(|value| !cases.iter().any(|case| case == value))
+        .collect();
+    assert!(
+        missing.is_empty(),
+        "cases from `{source}` do not cover {} domain value(s): {missing:?}",
+        missing.len()
+    );
+}
+
+/// Name of the env variable specifying where test cases are printed.
+const CASE_OUTPUT_ENV_VAR: &str = "TEST_CASING_CASE_OUTPUT";
+
+/// Destination for printing test cases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CaseOutput {
+    Stdout,
+    Stderr,
+    None,
+}
+
+impl CaseOutput {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "" | "stdout" => Some(Self::Stdout),
+            "stderr" => Some(Self::Stderr),
+            "none" => Some(Self::None),
+            _ => None,
+        }
+    }
+
+    /// Gets the output from the [`CASE_OUTPUT_ENV_VAR`] env variable.
+    fn from_env() -> Self {
+        static OUTPUT: OnceLock<CaseOutput> = OnceLock::new();
+
+        *OUTPUT.get_or_init(|| {
+            let Ok(value) = env::var(CASE_OUTPUT_ENV_VAR) else {
+                return Self::Stdout;
+            };
+            Self::parse(&value).unwrap_or_else(|| {
+                eprintln!(
+                    "Unsupported value of the `{CASE_OUTPUT_ENV_VAR}` env variable: `{value}`; \
+                     expected one of `stdout`, `stderr` or `none`. Falling back to `stdout`"
+                );
+                Self::Stdout
+            })
+        })
+    }
+}
+
+/// Prints information about a test case to the output specified via the env variable.
+#[doc(hidden)] // used by the `#[test_casing]` macro; logically private
+pub fn print_case(message: impl FnOnce() -> String) {
+    match CaseOutput::from_env() {
+        CaseOutput::Stdout => println!("{}", message()),
+        CaseOutput::Stderr => eprintln!("{}", message()),
+        CaseOutput::None => { /* do nothing */ }
+    }
+}
+
+/// Name of the env variable restricting the executed test cases to a single case.
+const ONLY_CASE_ENV_VAR: &str = "TEST_CASING_ONLY";
+
+/// Filter for test cases specified via the env variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum OnlyCase {
+    /// Zero-based index of the case.
+    Index(usize),
+    /// Name of the generated test, e.g. `case_1` or `tested_fn::case_1`.
+    Name(String),
+}
+
+impl OnlyCase {
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value.is_empty() {
+            None
+        } else if let Ok(index) = value.parse() {
+            Some(Self::Index(index))
+        } else {
+            Some(Self::Name(value.to_owned()))
+        }
+    }
+
+    /// Gets the filter from the [`ONLY_CASE_ENV_VAR`] env variable.
+    fn from_env() -> Option<&'static Self> {
+        static ONLY: OnceLock<Option<OnlyCase>> = OnceLock::new();
+
+        ONLY.get_or_init(|| Self::parse(&env::var(ONLY_CASE_ENV_VAR).ok()?))
+            .as_ref()
+    }
+
+    fn matches(&self, index: usize, test_path: &str) -> bool {
+        match self {
+            Self::Index(only_index) => *only_index == index,
+            Self::Name(name) => {
+                // Test names may contain a descriptive suffix (e.g., `case_1_foo`), so we allow
+                // to omit it in the filter. The first underscore separates `case` and the index,
+                // so it's skipped.
+                let last_segment_start = test_path.rfind("::").map_or(0, |pos| pos + 2);
+                let suffix_positions = test_path[last_segment_start..]
+                    .match_indices('_')
+                    .skip(1)
+                    .map(|(pos, _)| last_segment_start + pos);
+                let mut truncated_paths = suffix_positions.map(|pos| &test_path[..pos]);
+                Self::matches_name(name, test_path)
+                    || truncated_paths.any(|path| Self::matches_name(name, path))
+            }
+        }
+    }
+
+    fn matches_name(name: &str, test_path: &str) -> bool {
+        test_path
+            .strip_suffix(name)
+            .is_some_and(|prefix| prefix.is_empty() || prefix.ends_with("::"))
+    }
+
+    fn description(&self) -> String {
+        match self {
+            Self::Index(index) => format!("#{index}"),
+            Self::Name(name) => format!("`{name}`"),
+        }
+    }
+}
+
+/// Checks whether the test case should be skipped because of the filter set
+/// via the env variable. If so, prints a message to the output specified via the env variable.
+#[doc(hidden)] // used by the `#[test_casing]` macro; logically private
+pub fn is_case_skipped(index: usize, test_path: &str) -> bool {
+    let Some(only) = OnlyCase::from_env() else {
+        return false;
+    };
+    let is_skipped = !only.matches(index, test_path);
+    if is_skipped {
+        print_case(|| {
+            format!(
+                "Skipping case #{index} of `{test_path}`: only case {} is selected \
+                 via `{ONLY_CASE_ENV_VAR}`",
+                only.description()
+            )
+        });
+    }
+    is_skipped
+}
+
+/// Options for printing test case arguments.
+#[doc(hidden)] // used by the `#[test_casing]` macro; logically private
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PrintOptions {
+    mode: PrintMode,
+    max_width: Option<usize>,
+}
+
+/// Mode of printing test case arguments.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum PrintMode {
+    #[default]
+    Compact,
+    Pretty,
+    Json,
+}
+
+impl PrintOptions {
+    /// Length of a single-line argument representation after which the pretty mode switches
+    /// to the multi-line representation.
+    const MAX_SINGLE_LINE_WIDTH: usize = 60;
+
+    pub const fn new() -> Self {
+        Self {
+            mode: PrintMode::Compact,
+            max_width: None,
+        }
+    }
+
+    /// Prints each argument on a separate line, switching to the multi-line `{:#?}` format
+    /// for large values.
+    #[must_use]
+    pub const fn pretty(self) -> Self {
+        Self {
+            mode: PrintMode::Pretty,
+            ..self
+        }
+    }
+
+    /// Prints arguments as a JSON object with argument names as keys and `Debug` representations
+    /// of the arguments as string values.
+    #[must_use]
+    pub const fn json(self) -> Self {
+        Self {
+            mode: PrintMode::Json,
+            ..self
+        }
+    }
+
+    /// Sets the maximum width (in chars) of a printed argument. Longer arguments are truncated.
+    #[must_use]
+    pub const fn with_max_width(self, max_width: usize) -> Self {
+        Self {
+            max_width: Some(max_width),
+            ..self
+        }
+    }
+
+    /// Prints named args using these options. In the pretty mode, each arg is printed
+    /// on a separate indented line.
+    pub fn print(self, args: &[(&'static str, &dyn fmt::Debug)]) -> String {
+        if self.mode == PrintMode::Json {
+            return self.print_json(args);
+        }
+
+        let (prefix, separator) = if self.mode == PrintMode::Pretty {
+            ("  ", "\n  ")
+        } else {
+            ("", ", ")
+        };
+
+        let mut buffer = String::from(prefix);
+        for (i, (name, arg)) in args.iter().enumerate() {
+            if i > 0 {
+                buffer.push_str(separator);
+            }
+            buffer.push_str(name);
+            buffer.push_str(" = ");
+            buffer.push_str(&self.format_arg(*arg));
+        }
+        buffer
+    }
+
+    fn print_json(self, args: &[(&'static str, &dyn fmt::Debug)]) -> String {
+        let mut buffer = String::from("{");
+        for (i, (name, arg)) in args.iter().enumerate() {
+            if i > 0 {
+                buffer.push(',');
+            }
+            write_json_string(&mut buffer, name);
+            buffer.push(':');
+            write_json_string(&mut buffer, &self.format_arg(*arg));
+        }
+        buffer.push('}');
+        buffer
+    }
+
+    fn format_arg(self, arg: &dyn fmt::Debug) -> String {
+        let mut formatted = format!("{arg:?}");
+        if self.mode == PrintMode::Pretty && formatted.len() > Self::MAX_SINGLE_LINE_WIDTH {
+            formatted = format!("{arg:#?}").replace('\n', "\n  ");
+        }
+        if let Some(max_width) = self.max_width {
+            let char_count = formatted.chars().count();
+            if char_count > max_width {
+                let (truncated_pos, _) = formatted.char_indices().nth(max_width).unwrap();
+                formatted.truncate(truncated_pos);
+                let truncated_count = char_count - max_width;
+                write!(formatted, "… ({truncated_count} chars truncated)").unwrap();
+            }
+        }
+        formatted
+    }
+}
+
+/// Writes a JSON string literal with the specified contents.
+pub(crate) fn write_json_string(buffer: &mut String, s: &str) {
+    buffer.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => buffer.push_str("\\\""),
+            '\\' => buffer.push_str("\\\\"),
+            '\n' => buffer.push_str("\\n"),
+            '\r' => buffer.push_str("\\r"),
+            '\t' => buffer.push_str("\\t"),
+            ch if ch.is_control() => {
+                write!(buffer, "\\u{:04x}", u32::from(ch)).unwrap();
+            }
+            ch => buffer.push(ch),
+        }
+    }
+    buffer.push('"');
+}
+
+/// Placeholder printed instead of opaque args.
+#[doc(hidden)] // used by the `#[test_casing]` macro; logically private
+#[derive(Clone, Copy)]
+pub struct Opaque;
+
+impl fmt::Debug for Opaque {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("<opaque>")
+    }
+}
+
+/// Wrapper printing an arg as a hex string of its bytes.
+#[doc(hidden)] // used by the `#[test_casing]` macro; logically private
+pub struct FormatHex<'a, T: ?Sized>(pub &'a T);
+
+impl<T: AsRef<[u8]> + ?Sized> fmt::Debug for FormatHex<'_, T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0.as_ref() {
+            write!(formatter, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Wrapper printing an arg using its `Display` implementation.
+#[doc(hidden)] // used by the `#[test_casing]` macro; logically private
+pub struct FormatDisplay<'a, T: ?Sized>(pub &'a T);
+
+impl<T: fmt::Display + ?Sized> fmt::Debug for FormatDisplay<'_, T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.0, formatter)
+    }
+}
+
+/// Wrapper printing an arg using the specified function.
+#[doc(hidden)] // used by the `#[test_casing]` macro; logically private
+pub struct FormatWith<'a, T: ?Sized>(
+    pub &'a T,
+    pub fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+);
+
+impl<T: ?Sized> fmt::Debug for FormatWith<'_, T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.1)(self.0, formatter)
+    }
 }
 
 /// Allows printing named arguments together with their values to a `String`.
 #[doc(hidden)] // used by the `#[test_casing]` macro; logically private
 pub trait ArgNames<T: fmt::Debug>: Copy + IntoIterator<Item = &'static str> {
-    fn print_with_args(self, args: &T) -> String;
+    /// Returns arguments together with their names.
+    fn args(self, args: &T) -> Vec<(&'static str, &dyn fmt::Debug)>;
+
+    /// Prints arguments on a single line.
+    fn print_with_args(self, args: &T) -> String {
+        self.print_with_options(args, PrintOptions::new())
+    }
+
+    /// Prints arguments using the specified options.
+    fn print_with_options(self, args: &T, options: PrintOptions) -> String {
+        options.print(&self.args(args))
+    }
+}
+
+/// Placeholder printed instead of redacted args.
+#[doc(hidden)] // used by the `#[test_casing]` macro; logically private
+#[derive(Clone, Copy)]
+pub struct Redacted;
+
+impl fmt::Debug for Redacted {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("***")
+    }
 }
 
 impl<T: fmt::Debug> ArgNames<T> for [&'static str; 1] {
-    fn print_with_args(self, args: &T) -> String {
-        format!("{name} = {args:?}", name = self[0])
+    fn args(self, args: &T) -> Vec<(&'static str, &dyn fmt::Debug)> {
+        vec![(self[0], args)]
     }
 }
 
 macro_rules! impl_arg_names {
     ($n:tt => $($idx:tt: $arg_ty:ident),+) => {
         impl<$($arg_ty : fmt::Debug,)+> ArgNames<($($arg_ty,)+)> for [&'static str; $n] {
-            fn print_with_args(self, args: &($($arg_ty,)+)) -> String {
-                use std::fmt::Write as _;
-
-                let mut buffer = String::new();
-                $(
-                write!(buffer, "{} = {:?}", self[$idx], args.$idx).unwrap();
-                if $idx + 1 < self.len() {
-                    buffer.push_str(", ");
-                }
-                )+
-                buffer
+            fn args(self, args: &($($arg_ty,)+)) -> Vec<(&'static str, &dyn fmt::Debug)> {
+                vec![$((self[$idx], &args.$idx as &dyn fmt::Debug),)+]
             }
         }
     };
@@ -50,6 +621,11 @@ impl_arg_names!(4 => 0: T, 1: U, 2: V, 3: W);
 impl_arg_names!(5 => 0: T, 1: U, 2: V, 3: W, 4: X);
 impl_arg_names!(6 => 0: T, 1: U, 2: V, 3: W, 4: X, 5: Y);
 impl_arg_names!(7 => 0: T, 1: U, 2: V, 3: W, 4: X, 5: Y, 6: Z);
+impl_arg_names!(8 => 0: T, 1: U, 2: V, 3: W, 4: X, 5: Y, 6: Z, 7: A);
+impl_arg_names!(9 => 0: T, 1: U, 2: V, 3: W, 4: X, 5: Y, 6: Z, 7: A, 8: B);
+impl_arg_names!(10 => 0: T, 1: U, 2: V, 3: W, 4: X, 5: Y, 6: Z, 7: A, 8: B, 9: C);
+impl_arg_names!(11 => 0: T, 1: U, 2: V, 3: W, 4: X, 5: Y, 6: Z, 7: A, 8: B, 9: C, 10: D);
+impl_arg_names!(12 => 0: T, 1: U, 2: V, 3: W, 4: X, 5: Y, 6: Z, 7: A, 8: B, 9: C, 10: D, 11: E);
 
 /// Container for test cases based on a lazily evaluated iterator. Should be constructed
 /// using the [`cases!`](crate::cases) macro.
@@ -71,8 +647,48 @@ impl_arg_names!(7 => 0: T, 1: U, 2: V, 3: W, 4: X, 5: Y, 6: Z);
 ///     (0..5).map(move |_| rng.gen())
 /// });
 /// ```
-pub struct TestCases<T> {
-    lazy: fn() -> Box<dyn Iterator<Item = T>>,
+///
+/// # Combining test cases
+///
+/// Test cases can be combined using [`Self::chain()`] and [`Self::interleave()`].
+/// Both methods are `const`, so that shared base cases can be extended, e.g. per test file,
+/// without a new `cases!` block:
+///
+/// ```
+/// # use test_casing::{cases, TestCases};
+/// const BASE_CASES: TestCases<u32> = cases!([2, 3, 5]);
+/// const EXTRA_CASES: TestCases<u32> = cases!([42, 555]);
+/// const ALL_CASES: TestCases<u32> = BASE_CASES.chain(&EXTRA_CASES);
+/// const MIXED_CASES: TestCases<u32> = BASE_CASES.interleave(&EXTRA_CASES);
+///
+/// let all_cases: Vec<_> = ALL_CASES.into_iter().collect();
+/// assert_eq!(all_cases, [2, 3, 5, 42, 555]);
+/// let mixed_cases: Vec<_> = MIXED_CASES.into_iter().collect();
+/// assert_eq!(mixed_cases, [2, 42, 3, 555, 5]);
+/// ```
+///
+/// More than 2 sets of cases can be concatenated with [`Self::concat()`]:
+///
+/// ```
+/// # use test_casing::{cases, TestCases};
+/// # const BASE_CASES: TestCases<u32> = cases!([2, 3, 5]);
+/// # const EXTRA_CASES: TestCases<u32> = cases!([42, 555]);
+/// const MORE_CASES: TestCases<u32> = cases!(100..102);
+/// const ALL_CASES: TestCases<u32> = TestCases::concat(&[BASE_CASES, EXTRA_CASES, MORE_CASES]);
+///
+/// let all_cases: Vec<_> = ALL_CASES.into_iter().collect();
+/// assert_eq!(all_cases, [2, 3, 5, 42, 555, 100, 101]);
+/// ```
+pub struct TestCases<T: 'static> {
+    source: CasesSource<T>,
+}
+
+/// Source of [`TestCases`].
+enum CasesSource<T: 'static> {
+    Lazy(fn() -> Box<dyn Iterator<Item = T>>),
+    Chain(&'static TestCases<T>, &'static TestCases<T>),
+    Interleave(&'static TestCases<T>, &'static TestCases<T>),
+    Concat(&'static [TestCases<T>]),
 }
 
 impl<T> fmt::Debug for TestCases<T> {
@@ -89,10 +705,49 @@ impl<T> Clone for TestCases<T> {
 
 impl<T> Copy for TestCases<T> {}
 
+impl<T> Clone for CasesSource<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for CasesSource<T> {}
+
 impl<T> TestCases<T> {
     /// Creates a new set of test cases.
     pub const fn new(lazy: fn() -> Box<dyn Iterator<Item = T>>) -> Self {
-        Self { lazy }
+        Self {
+            source: CasesSource::Lazy(lazy),
+        }
+    }
+
+    /// Chains these test cases with `other` cases, so that the `other` cases are produced
+    /// after all cases in `self`.
+    #[must_use]
+    pub const fn chain(&'static self, other: &'static Self) -> Self {
+        Self {
+            source: CasesSource::Chain(self, other),
+        }
+    }
+
+    /// Concatenates several sets of test cases, so that cases from each set are produced
+    /// after all cases from the preceding sets. Unlike with [`Self::chain()`], the number
+    /// of combined sets is not limited to 2, and no intermediate constants are required.
+    #[must_use]
+    pub const fn concat(sets: &'static [Self]) -> Self {
+        Self {
+            source: CasesSource::Concat(sets),
+        }
+    }
+
+    /// Interleaves these test cases with `other` cases, alternating between cases in `self`
+    /// and `other` starting from `self`. If one of the sets is exhausted, the remaining cases
+    /// from the other set are produced.
+    #[must_use]
+    pub const fn interleave(&'static self, other: &'static Self) -> Self {
+        Self {
+            source: CasesSource::Interleave(self, other),
+        }
     }
 }
 
@@ -101,7 +756,40 @@ impl<T> IntoIterator for TestCases<T> {
     type IntoIter = Box<dyn Iterator<Item = T>>;
 
     fn into_iter(self) -> Self::IntoIter {
-        (self.lazy)()
+        match self.source {
+            CasesSource::Lazy(lazy) => lazy(),
+            CasesSource::Chain(first, second) => Box::new(first.into_iter().chain(*second)),
+            CasesSource::Interleave(first, second) => Box::new(Interleave {
+                iters: [first.into_iter().fuse(), second.into_iter().fuse()],
+                next_idx: 0,
+            }),
+            CasesSource::Concat(sets) => Box::new(sets.iter().copied().flatten()),
+        }
+    }
+}
+
+/// Iterator interleaving items from 2 iterators.
+struct Interleave<I> {
+    iters: [Fuse<I>; 2],
+    next_idx: usize,
+}
+
+impl<I: Iterator> Iterator for Interleave<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.next_idx;
+        self.next_idx = 1 - idx;
+        self.iters[idx]
+            .next()
+            .or_else(|| self.iters[1 - idx].next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (first_min, first_max) = self.iters[0].size_hint();
+        let (second_min, second_max) = self.iters[1].size_hint();
+        let max = first_max.and_then(|max| max.checked_add(second_max?));
+        (first_min.saturating_add(second_min), max)
     }
 }
 
@@ -122,7 +810,8 @@ macro_rules! cases {
 
 /// Cartesian product of several test cases.
 ///
-/// For now, this supports products of 2..8 values. The provided [`IntoIterator`] expression
+/// For now, this supports products of 2..=12 values, or a dynamic number of values
+/// (see [below](#dynamic-number-of-components)). The provided [`IntoIterator`] expression
 /// for each value must implement [`Clone`]. One way to do that is using [`TestCases`], which
 /// wraps a lazy iterator initializer and is thus always [`Copy`]able.
 ///
@@ -137,6 +826,24 @@ macro_rules! cases {
 ///     [(0, "test"), (0, "other"), (1, "test"), (1, "other")]
 /// );
 /// ```
+///
+/// # Dynamic number of components
+///
+/// If the number of product components is only known at runtime (e.g., for a per-feature
+/// on / off matrix generated from a list of features), `Product` can wrap a `Vec`
+/// of homogeneous components. In this case, product items are `Vec`s with a value for each
+/// of the components. Unlike with tuples, component items must implement [`Clone`].
+///
+/// ```
+/// # use test_casing::Product;
+/// let features = ["tls", "compression"];
+/// let product = Product(vec![[false, true]; features.len()]);
+/// let values: Vec<_> = product.into_iter().collect();
+/// assert_eq!(
+///     values,
+///     [[false, false], [false, true], [true, false], [true, true]]
+/// );
+/// ```
 #[derive(Debug, Clone, Copy)]
 pub struct Product<Ts>(pub Ts);
 
@@ -164,21 +871,47 @@ macro_rules! impl_product {
     ($head:ident: $head_ty:ident, $($tail:ident: $tail_ty:ident),+) => {
         impl<$head_ty, $($tail_ty,)+> IntoIterator for Product<($head_ty, $($tail_ty,)+)>
         where
-            $head_ty: 'static + Clone + IntoIterator,
-            $($tail_ty: 'static + Clone + IntoIterator,)+
+            $head_ty: Clone + IntoIterator,
+            $($tail_ty: Clone + IntoIterator,)+
         {
             type Item = ($head_ty::Item, $($tail_ty::Item,)+);
-            type IntoIter = Box<dyn Iterator<Item = Self::Item>>;
+            type IntoIter = NestedProductIter<$head_ty, Product<($($tail_ty,)+)>>;
 
             fn into_iter(self) -> Self::IntoIter {
                 let ($head, $($tail,)+) = self.0;
                 let tail = Product(($($tail,)+));
-                let iter = Product(($head, tail))
-                    .into_iter()
-                    .map(|($head, ($($tail,)+))| ($head, $($tail,)+));
-                Box::new(iter)
+                NestedProductIter {
+                    inner: Product(($head, tail)).into_iter(),
+                }
             }
         }
+
+        impl<$head_ty, $($tail_ty,)+> Iterator
+            for NestedProductIter<$head_ty, Product<($($tail_ty,)+)>>
+        where
+            $head_ty: Clone + IntoIterator,
+            $($tail_ty: Clone + IntoIterator,)+
+        {
+            type Item = ($head_ty::Item, $($tail_ty::Item,)+);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                let ($head, ($($tail,)+)) = self.inner.next()?;
+                Some(($head, $($tail,)+))
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.inner.size_hint()
+            }
+        }
+
+        impl<$head_ty, $($tail_ty,)+> ExactSizeIterator
+            for NestedProductIter<$head_ty, Product<($($tail_ty,)+)>>
+        where
+            $head_ty: Clone + IntoIterator,
+            $($tail_ty: Clone + IntoIterator,)+
+            ProductIter<$head_ty, Product<($($tail_ty,)+)>>: ExactSizeIterator,
+        {
+        }
     };
 }
 
@@ -187,8 +920,13 @@ impl_product!(t: T, u: U, v: V, w: W);
 impl_product!(t: T, u: U, v: V, w: W, x: X);
 impl_product!(t: T, u: U, v: V, w: W, x: X, y: Y);
 impl_product!(t: T, u: U, v: V, w: W, x: X, y: Y, z: Z);
+impl_product!(t: T, u: U, v: V, w: W, x: X, y: Y, z: Z, a: A);
+impl_product!(t: T, u: U, v: V, w: W, x: X, y: Y, z: Z, a: A, b: B);
+impl_product!(t: T, u: U, v: V, w: W, x: X, y: Y, z: Z, a: A, b: B, c: C);
+impl_product!(t: T, u: U, v: V, w: W, x: X, y: Y, z: Z, a: A, b: B, c: C, d: D);
+impl_product!(t: T, u: U, v: V, w: W, x: X, y: Y, z: Z, a: A, b: B, c: C, d: D, e: E);
 
-/// Iterator over test cases in [`Product`].
+/// Iterator over test cases in [`Product`] of 2 values.
 #[derive(Debug)]
 pub struct ProductIter<T: IntoIterator, U: IntoIterator> {
     sources: (T, U),
@@ -209,18 +947,165 @@ where
             return None;
         }
 
-        loop {
-            if let Some(second_case) = self.second_iter.next() {
-                let mut first_iter = self.sources.0.clone().into_iter();
-                let Some(first_case) = first_iter.nth(self.first_idx) else {
-                    self.is_finished = true;
-                    return None;
-                };
-                return Some((first_case, second_case));
-            }
+        let second_case = if let Some(case) = self.second_iter.next() {
+            case
+        } else {
             self.first_idx += 1;
             self.second_iter = self.sources.1.clone().into_iter().fuse();
+            let Some(case) = self.second_iter.next() else {
+                // The second source is empty, so the product is empty as well.
+                self.is_finished = true;
+                return None;
+            };
+            case
+        };
+
+        let mut first_iter = self.sources.0.clone().into_iter();
+        let Some(first_case) = first_iter.nth(self.first_idx) else {
+            self.is_finished = true;
+            return None;
+        };
+        Some((first_case, second_case))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.is_finished {
+            return (0, Some(0));
+        }
+
+        let (first_min, first_max) = self.sources.0.clone().into_iter().size_hint();
+        let (second_min, second_max) = self.sources.1.clone().into_iter().size_hint();
+        let (current_min, current_max) = self.second_iter.size_hint();
+
+        // Cases remaining for the current first case, plus cases for the remaining first cases.
+        let min = if first_min > self.first_idx {
+            let remaining_first = first_min - self.first_idx - 1;
+            remaining_first
+                .saturating_mul(second_min)
+                .saturating_add(current_min)
+        } else {
+            0
+        };
+        let max = match first_max {
+            Some(first_max) if first_max <= self.first_idx => Some(0),
+            Some(first_max) => {
+                let remaining_first = first_max - self.first_idx - 1;
+                second_max
+                    .and_then(|second_max| remaining_first.checked_mul(second_max))
+                    .and_then(|max| max.checked_add(current_max?))
+            }
+            None => None,
+        };
+        (min, max)
+    }
+}
+
+impl<T, U> ExactSizeIterator for ProductIter<T, U>
+where
+    T: Clone + IntoIterator,
+    U: Clone + IntoIterator,
+    T::IntoIter: ExactSizeIterator,
+    U::IntoIter: ExactSizeIterator,
+{
+}
+
+/// Iterator over test cases in [`Product`] of 3 or more values.
+pub struct NestedProductIter<T: IntoIterator, U: IntoIterator> {
+    inner: ProductIter<T, U>,
+}
+
+impl<T: IntoIterator, U: IntoIterator> fmt::Debug for NestedProductIter<T, U>
+where
+    ProductIter<T, U>: fmt::Debug,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("NestedProductIter")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// Product of a dynamic number of homogeneous test cases.
+impl<I> IntoIterator for Product<Vec<I>>
+where
+    I: Clone + IntoIterator,
+    I::Item: Clone,
+{
+    type Item = Vec<I::Item>;
+    type IntoIter = ProductVecIter<I>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ProductVecIter {
+            sources: self.0,
+            iters: vec![],
+            current: None,
+            is_finished: false,
+        }
+    }
+}
+
+/// Iterator over test cases in a [`Product`] with a dynamic number of components.
+#[derive(Debug)]
+pub struct ProductVecIter<I: IntoIterator> {
+    sources: Vec<I>,
+    iters: Vec<Fuse<I::IntoIter>>,
+    current: Option<Vec<I::Item>>,
+    is_finished: bool,
+}
+
+impl<I> ProductVecIter<I>
+where
+    I: Clone + IntoIterator,
+    I::Item: Clone,
+{
+    fn init(&mut self) -> Option<Vec<I::Item>> {
+        self.iters = self
+            .sources
+            .iter()
+            .map(|source| source.clone().into_iter().fuse())
+            .collect();
+        self.iters.iter_mut().map(Iterator::next).collect()
+    }
+
+    /// Advances the product like an odometer, starting from the last component.
+    fn advance(&mut self, mut current: Vec<I::Item>) -> Option<Vec<I::Item>> {
+        for idx in (0..self.iters.len()).rev() {
+            if let Some(value) = self.iters[idx].next() {
+                current[idx] = value;
+                return Some(current);
+            }
+            // Restart the component and carry to the previous one.
+            let mut restarted = self.sources[idx].clone().into_iter().fuse();
+            current[idx] = restarted.next()?;
+            self.iters[idx] = restarted;
+        }
+        None
+    }
+}
+
+impl<I> Iterator for ProductVecIter<I>
+where
+    I: Clone + IntoIterator,
+    I::Item: Clone,
+{
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_finished {
+            return None;
+        }
+
+        let next = match self.current.take() {
+            None => self.init(),
+            Some(current) => self.advance(current),
+        };
+        if let Some(next) = &next {
+            self.current = Some(next.clone());
+        } else {
+            self.is_finished = true;
         }
+        next
     }
 }
 
@@ -248,6 +1133,216 @@ mod tests {
         assert_eq!(cases.len(), 12); // 3 * 2 * 2
     }
 
+    #[test]
+    fn only_case_filter() {
+        assert_eq!(OnlyCase::parse(""), None);
+        assert_eq!(OnlyCase::parse(" 3 "), Some(OnlyCase::Index(3)));
+        assert_eq!(
+            OnlyCase::parse("tested::case_1"),
+            Some(OnlyCase::Name("tested::case_1".to_owned()))
+        );
+
+        let path = "tests::tested::case_1";
+        assert!(OnlyCase::Index(1).matches(1, path));
+        assert!(!OnlyCase::Index(0).matches(1, path));
+        for name in ["case_1", "tested::case_1", path] {
+            assert!(OnlyCase::Name(name.to_owned()).matches(1, path), "{name}");
+        }
+        for name in ["se_1", "case_10", "case_", "other::case_1"] {
+            assert!(!OnlyCase::Name(name.to_owned()).matches(1, path), "{name}");
+        }
+
+        let path = "tests::tested::case_1_foo_bar";
+        for name in ["case_1", "tested::case_1_foo", "case_1_foo_bar"] {
+            assert!(OnlyCase::Name(name.to_owned()).matches(1, path), "{name}");
+        }
+        for name in ["case", "case_1_", "case_1_fo", "foo_bar"] {
+            assert!(!OnlyCase::Name(name.to_owned()).matches(1, path), "{name}");
+        }
+    }
+
+    #[test]
+    fn case_count_mismatch_diagnostics() {
+        let panic = std::panic::catch_unwind(|| case([1, 2], 3, 4, "[1, 2]")).unwrap_err();
+        let message = panic.downcast_ref::<String>().unwrap();
+        assert!(message.contains("case #3 not provided"), "{message}");
+        assert!(
+            message.contains("`[1, 2]` yielded 2 case(s), while 4 case(s) were declared"),
+            "{message}"
+        );
+
+        assert_case_count([1, 2, 3], 3, "[1, 2, 3]");
+        let panic = std::panic::catch_unwind(|| assert_case_count(0.., 3, "0..")).unwrap_err();
+        let message = panic.downcast_ref::<String>().unwrap();
+        assert!(
+            message.contains("`0..` yielded more than 3 case(s), while 3 case(s) were declared"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn checking_case_coverage() {
+        assert_cases_cover([3, 1, 2, 1, 5], 1..=3, "[3, 1, 2, 1, 5]");
+        let panic =
+            std::panic::catch_unwind(|| assert_cases_cover([3, 1], 0..4, "[3, 1]")).unwrap_err();
+        let message = panic.downcast_ref::<String>().unwrap();
+        assert!(
+            message.contains("cases from `[3, 1]` do not cover 2 domain value(s): [0, 2]"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn combining_test_cases() {
+        const NUMBERS: TestCases<u32> = cases!(0..3);
+        const MORE_NUMBERS: TestCases<u32> = cases!([10, 20, 30, 40, 50]);
+        const CHAINED: TestCases<u32> = NUMBERS.chain(&MORE_NUMBERS);
+        const INTERLEAVED: TestCases<u32> = NUMBERS.interleave(&MORE_NUMBERS);
+        const NESTED: TestCases<u32> = CHAINED.interleave(&INTERLEAVED);
+        const CONCATENATED: TestCases<u32> =
+            TestCases::concat(&[MORE_NUMBERS, NUMBERS, cases!([]), MORE_NUMBERS]);
+
+        let cases: Vec<_> = CHAINED.into_iter().collect();
+        assert_eq!(cases, [0, 1, 2, 10, 20, 30, 40, 50]);
+        let cases: Vec<_> = INTERLEAVED.into_iter().collect();
+        assert_eq!(cases, [0, 10, 1, 20, 2, 30, 40, 50]);
+        assert_eq!(INTERLEAVED.into_iter().size_hint(), (8, Some(8)));
+        assert_eq!(NESTED.into_iter().count(), 16);
+        let cases: Vec<_> = CONCATENATED.into_iter().collect();
+        assert_eq!(cases, [10, 20, 30, 40, 50, 0, 1, 2, 10, 20, 30, 40, 50]);
+        assert_eq!(TestCases::<u32>::concat(&[]).into_iter().count(), 0);
+    }
+
+    #[test]
+    fn product_with_empty_component() {
+        let product = Product((0..3, 0..0));
+        assert_eq!(product.into_iter().count(), 0);
+        let product = Product((0..0, 0..3));
+        assert_eq!(product.into_iter().count(), 0);
+        let product = Product((0..2, 0..3, 0..0));
+        assert_eq!(product.into_iter().count(), 0);
+    }
+
+    #[test]
+    fn product_size_hint() {
+        let mut iter = Product((0..3, ["0", "1"])).into_iter();
+        for expected_len in (0..=6).rev() {
+            assert_eq!(iter.len(), expected_len);
+            assert_eq!(iter.next().is_some(), expected_len > 0);
+        }
+
+        let mut iter = Product((0..3, [false, true], 0..2, ['a'])).into_iter();
+        assert_eq!(iter.len(), 12);
+        iter.nth(4).unwrap();
+        assert_eq!(iter.len(), 7);
+        assert_eq!(iter.count(), 7);
+
+        let iter = Product((0.., 0..2)).into_iter();
+        assert_eq!(iter.size_hint(), (usize::MAX, None));
+        let iter = Product(((0..4).filter(|&x| x % 2 == 0), 0..2)).into_iter();
+        assert_eq!(iter.size_hint(), (0, Some(8)));
+    }
+
+    #[test]
+    fn dynamic_product() {
+        let product = Product(vec![0..2, 0..3, 5..6]);
+        let cases: Vec<_> = product.into_iter().collect();
+        assert_eq!(
+            cases,
+            [
+                [0, 0, 5],
+                [0, 1, 5],
+                [0, 2, 5],
+                [1, 0, 5],
+                [1, 1, 5],
+                [1, 2, 5]
+            ]
+        );
+
+        let product = Product(vec![cases!(["a", "b"]); 4]);
+        assert_eq!(product.into_iter().count(), 16);
+
+        let product = Product(vec![0..2, 0..0]);
+        assert_eq!(product.into_iter().count(), 0);
+        let product = Product(Vec::<[u8; 2]>::new());
+        let cases: Vec<_> = product.into_iter().collect();
+        assert_eq!(cases, [Vec::<u8>::new()]);
+    }
+
+    #[test]
+    fn printing_args() {
+        let args = (5, "test");
+        let printed = ["number", "s"].print_with_args(&args);
+        assert_eq!(printed, "number = 5, s = \"test\"");
+
+        let args = (5, vec![1_000_000_u32; 10]);
+        let options = PrintOptions::new().pretty();
+        let printed = ["number", "values"].print_with_options(&args, options);
+        let lines: Vec<_> = printed.lines().collect();
+        assert_eq!(lines.len(), 13, "{printed}");
+        assert_eq!(lines[0], "  number = 5");
+        assert_eq!(lines[1], "  values = [");
+        assert_eq!(lines[2], "      1000000,");
+        assert_eq!(lines[12], "  ]");
+
+        let options = PrintOptions::new().with_max_width(10);
+        let printed = ["number", "values"].print_with_options(&args, options);
+        assert_eq!(
+            printed,
+            "number = 5, values = [1000000, … (80 chars truncated)"
+        );
+    }
+
+    #[test]
+    fn parsing_case_output() {
+        assert_eq!(CaseOutput::parse(""), Some(CaseOutput::Stdout));
+        assert_eq!(CaseOutput::parse("stdout"), Some(CaseOutput::Stdout));
+        assert_eq!(CaseOutput::parse("stderr"), Some(CaseOutput::Stderr));
+        assert_eq!(CaseOutput::parse("none"), Some(CaseOutput::None));
+        assert_eq!(CaseOutput::parse("STDERR"), None);
+    }
+
+    #[test]
+    fn printing_args_as_json() {
+        let args = (5, "test\n", vec!["a"]);
+        let options = PrintOptions::new().json();
+        let printed = ["number", "s", "values"].print_with_options(&args, options);
+        assert_eq!(
+            printed,
+            r#"{"number":"5","s":"\"test\\n\"","values":"[\"a\"]"}"#
+        );
+
+        let mut buffer = String::new();
+        write_json_string(&mut buffer, "\u{1}\t");
+        assert_eq!(buffer, r#""\u0001\t""#);
+    }
+
+    #[test]
+    fn printing_placeholder_args() {
+        let args: [(&str, &dyn fmt::Debug); 3] =
+            [("number", &5), ("token", &Redacted), ("client", &Opaque)];
+        let printed = PrintOptions::new().print(&args);
+        assert_eq!(printed, "number = 5, token = ***, client = <opaque>");
+    }
+
+    #[test]
+    fn printing_formatted_args() {
+        fn format_len(values: &[u32; 5], formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(formatter, "{} values", values.len())
+        }
+
+        let bytes = [0xde_u8, 0xad, 0x0f];
+        let path = std::path::Path::new("/tmp");
+        let values = [1_u32; 5];
+        let args: [(&str, &dyn fmt::Debug); 3] = [
+            ("bytes", &FormatHex(&bytes)),
+            ("path", &FormatDisplay(&path.display())),
+            ("values", &FormatWith(&values, format_len)),
+        ];
+        let printed = PrintOptions::new().print(&args);
+        assert_eq!(printed, "bytes = dead0f, path = /tmp, values = 5 values");
+    }
+
     #[test]
     fn unit_test_detection_works() {
         assert!(option_env!("CARGO_TARGET_TMPDIR").is_none());
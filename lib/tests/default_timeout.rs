@@ -0,0 +1,78 @@
+//! Tests for the default timeout. Since the default timeout is read from an env variable
+//! once per process, the tests are run in a child process.
+
+use std::{env, process::Command, thread, time::Duration};
+
+use test_casing::{
+    decorate,
+    decorators::{Retry, Timeout},
+    test_casing,
+};
+
+mod child {
+    use super::*;
+
+    #[test_casing(2, [false, true])]
+    #[ignore = "run in a child process by other tests"]
+    fn maybe_hanging(hang: bool) {
+        if hang {
+            thread::sleep(Duration::from_secs(3_600));
+        }
+    }
+
+    #[test]
+    #[ignore = "run in a child process by other tests"]
+    #[decorate(Retry::times(1))]
+    fn hanging_decorated() {
+        thread::sleep(Duration::from_secs(3_600));
+    }
+
+    #[test]
+    #[ignore = "run in a child process by other tests"]
+    #[decorate(Timeout::secs(10))]
+    fn slow_with_explicit_timeout() {
+        thread::sleep(Duration::from_millis(500));
+    }
+
+    #[test]
+    #[ignore = "run in a child process by other tests"]
+    fn slow_without_decorators() {
+        thread::sleep(Duration::from_millis(500));
+    }
+}
+
+#[test]
+fn default_timeout_fails_only_hung_tests() {
+    let output = Command::new(env::current_exe().unwrap())
+        .args(["child::", "--include-ignored"])
+        .env("TEST_CASING_DEFAULT_TIMEOUT", "100ms")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{stdout}\n{stderr}");
+
+    let test_lines: Vec<_> = stdout
+        .lines()
+        .filter(|line| line.starts_with("test child::"))
+        .collect();
+    // `validate_cases` generated by `test_casing` passes as well.
+    let expected_lines = [
+        "test child::hanging_decorated ... FAILED",
+        "test child::maybe_hanging::case_0_false ... ok",
+        "test child::maybe_hanging::case_1_true ... FAILED",
+        "test child::slow_with_explicit_timeout ... ok",
+        "test child::slow_without_decorators ... ok",
+    ];
+    for line in expected_lines {
+        assert!(test_lines.contains(&line), "{stdout}\n{stderr}");
+    }
+    assert!(
+        stdout.contains("Default timeout 100ms expired for the test"),
+        "{stdout}\n{stderr}"
+    );
+    assert!(
+        stdout.contains("test result: FAILED. 4 passed; 2 failed"),
+        "{stdout}\n{stderr}"
+    );
+}
@@ -0,0 +1,73 @@
+//! Tests for the global output sink. Since the sink is set for the entire process, the decorators
+//! are run in a child process.
+
+use std::{env, process::Command};
+
+use test_casing::decorators::{
+    CpuTime, DecorateTest, OutputSink, PerfBaseline, ReproduceFlake, RequiresCpuFeatures,
+};
+
+/// Env variable specifying whether the child process should silence the global sink.
+const SILENT_ENV_VAR: &str = "TEST_CASING_SILENT_SINK";
+
+const MESSAGES: [&str; 4] = [
+    "Test passed all 2 iterations",
+    "No baseline duration recorded",
+    "CPU time",
+    "CPU features are not supported",
+];
+
+#[test]
+#[ignore = "run in a child process by other tests"]
+fn chatty_decorators() {
+    static REPRODUCE_FLAKE: ReproduceFlake = ReproduceFlake::times(2);
+    static BASELINE: PerfBaseline = PerfBaseline::new("target/global-sink-baseline.txt");
+    static CPU_TIME: CpuTime = CpuTime::new();
+    static CPU_FEATURES: RequiresCpuFeatures = RequiresCpuFeatures(&["unknown-feature"]);
+
+    if env::var_os(SILENT_ENV_VAR).is_some() {
+        OutputSink::set_global(OutputSink::Silent);
+    }
+    let test_fn: fn() = || {};
+    REPRODUCE_FLAKE.decorate_and_test(test_fn);
+    BASELINE.decorate_and_test(test_fn);
+    CPU_TIME.decorate_and_test(test_fn);
+    CPU_FEATURES.decorate_and_test(test_fn);
+}
+
+fn run_chatty_decorators(silent: bool) -> String {
+    let mut command = Command::new(env::current_exe().unwrap());
+    command
+        .args([
+            "chatty_decorators",
+            "--exact",
+            "--include-ignored",
+            "--nocapture",
+        ])
+        .env_remove("TEST_CASING_RECORD_BASELINE");
+    if silent {
+        command.env(SILENT_ENV_VAR, "1");
+    }
+    let output = command.output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stdout}\n{stderr}");
+    assert!(stdout.contains("1 passed"), "{stdout}");
+    stdout + &stderr
+}
+
+#[test]
+fn decorators_emit_messages_to_global_sink() {
+    let output = run_chatty_decorators(false);
+    for message in MESSAGES {
+        assert!(output.contains(message), "{output}");
+    }
+}
+
+#[test]
+fn silent_global_sink_suppresses_messages() {
+    let output = run_chatty_decorators(true);
+    for message in MESSAGES {
+        assert!(!output.contains(message), "{output}");
+    }
+}
@@ -3,6 +3,7 @@
 use async_std::task;
 
 use std::{
+    env,
     error::Error,
     sync::atomic::{AtomicBool, AtomicU32, Ordering},
     thread,
@@ -54,6 +55,36 @@ fn with_retries_and_error() -> Result<(), Box<dyn Error>> {
     }
 }
 
+/// Error type implementing `Debug`, but not `Display` (e.g., similar to ones used in assertion frameworks).
+#[derive(Debug)]
+struct AssertionFailure {
+    is_transient: bool,
+}
+
+#[test]
+#[decorate(Retry::times(1))]
+fn with_retries_and_debug_error() -> Result<(), AssertionFailure> {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    if COUNTER.fetch_add(1, Ordering::Relaxed) == 0 {
+        Err(AssertionFailure { is_transient: true })
+    } else {
+        Ok(())
+    }
+}
+
+#[test]
+#[decorate(Retry::times(2).on_error(|err: &AssertionFailure| err.is_transient), Timeout::secs(1))]
+fn with_error_retries_and_debug_error() -> Result<(), AssertionFailure> {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    if COUNTER.fetch_add(1, Ordering::Relaxed) < 2 {
+        Err(AssertionFailure { is_transient: true })
+    } else {
+        Ok(())
+    }
+}
+
 const RETRY_ERRORS: RetryErrors<Box<dyn Error>> =
     Retry::times(1).on_error(|err| err.to_string().contains("retry"));
 
@@ -131,7 +162,68 @@ async fn async_test_with_timeout() {
     }
 }
 
-static SEQUENCE: Sequence = Sequence::new().abort_on_failure();
+// `decorate` placed before the async test attribute wraps the test future.
+#[decorate(Timeout::millis(100), Retry::times(1))]
+#[async_std::test]
+async fn async_native_test_with_timeout() {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    if COUNTER.fetch_add(1, Ordering::Relaxed) == 0 {
+        task::sleep(Duration::from_secs(5)).await;
+        // ^ will cause the test future to be dropped
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[decorate(AsyncTimeout::tokio(Duration::from_millis(100)), Retry::times(1))]
+#[tokio::test]
+async fn async_native_test_with_tokio_timeout() {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    if COUNTER.fetch_add(1, Ordering::Relaxed) == 0 {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+    }
+}
+
+#[test_casing(2, [1, 2])]
+#[decorate(Retry::times(1))]
+#[async_std::test]
+async fn async_native_test_with_cases(number: u32) -> Result<(), Box<dyn Error + Send>> {
+    task::yield_now().await;
+    assert!(number > 0);
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "oops")]
+#[decorate(Timeout::secs(1), PanicReport)]
+fn with_panic_report() {
+    panic!("oops");
+}
+
+#[test]
+#[should_panic(expected = "oops")]
+#[decorate(Timeout::secs(1), Retry::times(2))]
+fn with_retries_and_expected_panic() {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    match COUNTER.fetch_add(1, Ordering::Relaxed) {
+        0 => panic!("unexpected"),
+        1 => panic!("oops"),
+        _ => unreachable!("the expected panic must not be retried"),
+    }
+}
+
+#[test]
+#[should_panic(expected = "oops")]
+#[decorate(Retry::times(1), Backtrace::new())]
+fn with_backtrace() {
+    panic!("oops");
+}
+
+static SEQUENCE: Sequence = Sequence::new()
+    .abort_on_failure()
+    .with_summary("integration");
 
 /// Checks that test in a `Sequence` are in fact sequential.
 #[derive(Debug)]
@@ -215,3 +307,126 @@ fn cases_with_retries(s: &str) {
     }
     parse_result.unwrap();
 }
+
+/// Decorator that fails if it is applied in a child process spawned by `ForkedProcess`.
+#[derive(Debug)]
+struct ParentProcessOnly;
+
+impl DecorateTest<()> for ParentProcessOnly {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        assert!(
+            env::var_os("TEST_CASING_FORKED_TEST").is_none(),
+            "Decorator applied in a child process"
+        );
+        test_fn();
+    }
+}
+
+/// Decorator that fails if it is applied in the parent process.
+#[derive(Debug)]
+struct ChildProcessOnly;
+
+impl DecorateTest<()> for ChildProcessOnly {
+    fn decorate_and_test<F: TestFn<()>>(&self, test_fn: F) {
+        assert!(
+            env::var_os("TEST_CASING_FORKED_TEST").is_some(),
+            "Decorator applied in the parent process"
+        );
+        test_fn();
+    }
+}
+
+#[test]
+#[decorate(
+    ChildProcessOnly,
+    Timeout::secs(5),
+    ForkedProcess::new(),
+    ParentProcessOnly
+)]
+fn with_forked_process() {
+    assert!(env::var_os("TEST_CASING_FORKED_TEST").is_some());
+}
+
+#[test]
+#[decorate(ForkedProcess::new(), ExpectNoOutput)]
+fn silent_test_in_forked_process() {
+    assert!(env::var_os("TEST_CASING_FORKED_TEST").is_some());
+}
+
+#[test_casing(2, [1, 2])]
+#[decorate(ForkedProcess::new(), Retry::times(1))]
+fn cases_with_forked_process(number: u32) {
+    assert!(env::var_os("TEST_CASING_FORKED_TEST").is_some());
+    assert!(number > 0);
+}
+
+#[cfg(feature = "serial_test")]
+mod serial {
+    use serial_test::serial;
+
+    use super::*;
+
+    static SERIAL_CHECKER: SequenceChecker = SequenceChecker::new();
+    static SERIAL_SEQUENCE: Sequence = Sequence::new();
+
+    #[test]
+    #[serial(integration)]
+    fn test_with_serial_attribute() {
+        let _guard = SERIAL_CHECKER.start();
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    #[test]
+    #[decorate(Serial::key("integration"))]
+    fn test_with_serial_decorator() {
+        let _guard = SERIAL_CHECKER.start();
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    #[test_casing(3, [1, 2, 3])]
+    #[decorate(Serial::keys(&["integration", "other"]), &SERIAL_SEQUENCE)]
+    fn cases_with_serial_decorator(_number: u32) {
+        let _guard = SERIAL_CHECKER.start();
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[derive(Debug, DecorateTest)]
+struct NamedDecorators {
+    timeout: Timeout,
+    retry: Retry,
+}
+
+#[derive(Debug, DecorateTest)]
+struct UnnamedDecorators<D>(&'static Sequence, D);
+
+static SEQUENCE_FOR_DERIVED: Sequence = Sequence::new();
+
+#[test]
+#[decorate(NamedDecorators { timeout: Timeout::millis(100), retry: Retry::times(1) })]
+fn with_derived_decorator() {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    if COUNTER.fetch_add(1, Ordering::Relaxed) == 0 {
+        thread::sleep(Duration::from_secs(1));
+    }
+}
+
+#[test]
+#[decorate(UnnamedDecorators(&SEQUENCE_FOR_DERIVED, Retry::times(1)))]
+fn with_generic_derived_decorator() -> Result<(), Box<dyn Error>> {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    if COUNTER.fetch_add(1, Ordering::Relaxed) == 0 {
+        Err("oops".into())
+    } else {
+        Ok(())
+    }
+}
+
+#[test]
+#[decorate(Retry::times(1))]
+#[ignore_if(cfg(all()), reason = "testing `ignore_if` with decorators")]
+fn ignored_decorated_test() {
+    panic!("should be ignored");
+}
@@ -2,9 +2,16 @@
 
 use async_std::task;
 
-use std::error::Error;
+use std::{
+    error::Error,
+    fmt,
+    net::Ipv4Addr,
+    panic,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
 
-use test_casing::{cases, test_casing, Product, TestCases};
+use test_casing::{assert_cases_cover, cases, test_casing, Expected, Product, TestCases};
 
 // Cases can be reused across multiple tests.
 const CASES: TestCases<i32> = cases!([2, 3, 5, 8]);
@@ -27,6 +34,22 @@ fn numbers_are_large(number: i32) {
     unimplemented!("implement later");
 }
 
+// Shared cases can be extended without a new `cases!` block.
+const MORE_CASES: TestCases<i32> = cases!([1, 9]);
+const EXTENDED_CASES: TestCases<i32> = CASES.chain(&MORE_CASES);
+
+#[test_casing(6, EXTENDED_CASES)]
+fn extended_numbers_are_small(number: i32) {
+    numbers_are_small(number);
+}
+
+const CONCATENATED_CASES: TestCases<i32> = TestCases::concat(&[CASES, MORE_CASES, CASES]);
+
+#[test_casing(10, CONCATENATED_CASES)]
+fn concatenated_numbers_are_small(number: i32) {
+    numbers_are_small(number);
+}
+
 #[test_casing(4, CASES)]
 fn numbers_are_small_with_errors(number: i32) -> Result<(), Box<dyn Error>> {
     if number < 10 {
@@ -51,12 +74,47 @@ fn number_can_be_converted_to_string_with_tuple_input((number, expected): (i32,
     assert_eq!(number.to_string(), expected);
 }
 
-// `Product` allows testing a Cartesian product of the contained cases of arity in 2..8.
+// `Product` allows testing a Cartesian product of the contained cases of arity in 2..=12.
 #[test_casing(12, Product((CASES, ["first", "second", "third"])))]
 fn cartesian_product(number: i32, s: &str) {
     assert_ne!(number.to_string(), s);
 }
 
+// Wide configuration matrices can be expressed as a single product.
+#[test_casing(
+    16,
+    Product(([false, true], [1_u8], [2_u16], [3_u32], [4_u64], ["a"], ['b'], [false, true],
+        [5_i8], [6_i16], [false, true], [false, true]))
+)]
+#[allow(clippy::too_many_arguments, clippy::fn_params_excessive_bools)]
+fn wide_cartesian_product(
+    flag0: bool,
+    num0: u8,
+    num1: u16,
+    num2: u32,
+    num3: u64,
+    s: &str,
+    ch: char,
+    flag1: bool,
+    num4: i8,
+    num5: i16,
+    flag2: bool,
+    flag3: bool,
+) {
+    let sum = u64::from(num0) + u64::from(num1) + u64::from(num2) + num3;
+    assert_eq!(sum, 10);
+    assert_eq!((s, ch, num4, num5), ("a", 'b', 5, 6));
+    let _ = (flag0, flag1, flag2, flag3);
+}
+
+// A product with the number of components only known at runtime.
+const FEATURES: [&str; 3] = ["tls", "compression", "cache"];
+
+#[test_casing(8, cases!(Product(vec![[false, true]; FEATURES.len()])))]
+fn dynamic_cartesian_product(#[map(ref = Vec::as_slice)] enabled_features: &[bool]) {
+    assert_eq!(enabled_features.len(), FEATURES.len());
+}
+
 // If it semantically makes sense, it's possible to borrow some of the returned case args
 // using a `#[map(ref)]` attr on the arg. An optional transform on the reference in a form
 // of a path can be specified as well. (Here, the transform is trivial and serves the purpose
@@ -94,6 +152,261 @@ async fn async_string_conversion(#[map(ref)] s: &str, expected: i32) -> Result<(
     Ok(())
 }
 
+#[test_casing(5, STRING_CASES, async_with = async_std::test)]
+async fn async_string_conversion_with_implicit_attr(#[map(ref)] s: &str, expected: i32) {
+    let expected_string = task::spawn_blocking(move || expected.to_string()).await;
+    assert_eq!(expected_string, s);
+}
+
+#[test_casing(2, CASES, tags = ["slow", "serial-db"])]
+fn tagged_cases(number: i32) {
+    let test_name = thread::current().name().unwrap().to_owned();
+    assert!(test_name.contains("tagged_cases::case_"), "{test_name}");
+    assert!(
+        test_name.contains("__tag_slow__tag_serial_db__"),
+        "{test_name}"
+    );
+    assert!(number < 5);
+}
+
+const SHARED_CASES: [i32; 3] = [1, 2, 3];
+
+// The number of cases can be specified as a constant expression. In this case, all cases
+// are tested in a single test.
+#[test_casing(SHARED_CASES.len(), SHARED_CASES)]
+fn cases_with_expr_count(number: i32) {
+    let test_name = thread::current().name().unwrap().to_owned();
+    assert!(
+        test_name.ends_with("cases_with_expr_count::all_cases"),
+        "{test_name}"
+    );
+    assert!(number > 0);
+}
+
+const SHARED_CASES_LEN: usize = 4;
+
+#[test_casing(SHARED_CASES_LEN, CASES)]
+fn cases_with_const_count(number: i32) -> Result<(), Box<dyn Error>> {
+    if number < 10 {
+        Ok(())
+    } else {
+        Err("number is too large".into())
+    }
+}
+
+#[test_casing(SHARED_CASES_LEN, STRING_CASES)]
+#[async_std::test]
+async fn async_cases_with_expr_count(
+    #[map(ref)] s: &str,
+    expected: i32,
+) -> Result<(), Box<dyn Error>> {
+    task::yield_now().await;
+    assert_eq!(s.parse::<i32>()?, expected);
+    Ok(())
+}
+
+#[cfg(unix)]
+const PLATFORM_CASES: [&str; 0] = [];
+#[cfg(not(unix))]
+const PLATFORM_CASES: [&str; 1] = ["C:\\"];
+
+// Platform-specific case sets may be empty. In this case, only an ignored marker test
+// is generated.
+#[cfg_attr(unix, test_casing(0, PLATFORM_CASES))]
+#[cfg_attr(not(unix), test_casing(1, PLATFORM_CASES))]
+fn platform_specific_cases(path: &str) {
+    assert!(!path.is_empty());
+}
+
+// Multiple `test_casing` attributes generate separate groups of cases.
+#[test_casing(4, CASES, group = curated)]
+#[test_casing(3, cases!((0..3).map(|_| rand::random::<i32>() % 10)), group = sampled)]
+fn grouped_cases(number: i32) {
+    let test_name = thread::current().name().unwrap().to_owned();
+    assert!(
+        test_name.contains("grouped_cases::curated::case_")
+            || test_name.contains("grouped_cases::sampled::case_"),
+        "{test_name}"
+    );
+    assert!(number < 10);
+}
+
+// Related functions can be selected together with a filter like `parser_tests::`.
+mod parser_tests {
+    use super::*;
+
+    #[test_casing(2, ["0", "42"])]
+    fn parsing_numbers(s: &str) {
+        let test_name = thread::current().name().unwrap().to_owned();
+        assert!(
+            test_name.contains("parser_tests::parsing_numbers::case_"),
+            "{test_name}"
+        );
+        s.parse::<u32>().unwrap();
+    }
+
+    #[test_casing(2, ["", "??"], group = invalid)]
+    fn parsing_invalid_numbers(s: &str) {
+        let test_name = thread::current().name().unwrap().to_owned();
+        assert!(
+            test_name.contains("parser_tests::parsing_invalid_numbers::invalid::case_"),
+            "{test_name}"
+        );
+        s.parse::<u32>().unwrap_err();
+    }
+}
+
+// Exported cases can be run outside of the generated tests, e.g. in benchmarks.
+#[test_casing(3, [1, 2, 3], export)]
+fn squaring_numbers(number: i32, #[default(2)] power: u32) {
+    assert!(number.pow(power) >= number);
+}
+
+const LARGE_NUMBERS: [i32; 2] = [10, 1_000];
+
+#[test_casing(2, [1, 2], group = small, export)]
+#[test_casing(LARGE_NUMBERS.len(), LARGE_NUMBERS, group = large, export)]
+fn cubing_numbers(number: i32) -> Result<(), String> {
+    number
+        .checked_pow(3)
+        .map(drop)
+        .ok_or_else(|| format!("{number}^3 overflows"))
+}
+
+#[test]
+fn running_exported_cases() {
+    assert_eq!(squaring_numbers::CASE_COUNT, 3);
+    for i in 0..squaring_numbers::CASE_COUNT {
+        squaring_numbers::run_case(i);
+    }
+
+    assert_eq!(cubing_numbers::CASE_COUNT_SMALL, 2);
+    cubing_numbers::run_case_small(1).unwrap();
+    assert_eq!(cubing_numbers::CASE_COUNT_LARGE, 2);
+    cubing_numbers::run_case_large(0).unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Radix {
+    Binary,
+    Decimal,
+    Hex,
+}
+
+impl Radix {
+    const ALL: [Self; 3] = [Self::Binary, Self::Decimal, Self::Hex];
+
+    fn value(self) -> u32 {
+        match self {
+            Self::Binary => 2,
+            Self::Decimal => 10,
+            Self::Hex => 16,
+        }
+    }
+}
+
+#[test_casing(4, [Radix::Binary, Radix::Decimal, Radix::Hex, Radix::Decimal], exhaustive = Radix::ALL)]
+fn parsing_with_radix(radix: Radix) {
+    assert_eq!(u32::from_str_radix("1", radix.value()).unwrap(), 1);
+}
+
+#[test]
+fn checking_incomplete_coverage() {
+    let err = panic::catch_unwind(|| {
+        assert_cases_cover([Radix::Hex, Radix::Hex], Radix::ALL, "[Hex, Hex]");
+    })
+    .unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+    assert!(message.contains("[Binary, Decimal]"), "{message}");
+}
+
+#[test_casing(3, cases!((1..=3).map(|len| (len, vec![u64::MAX; len * 10]))), print_args = pretty)]
+fn pretty_printed_cases(len: usize, #[map(ref = Vec::as_slice)] values: &[u64]) {
+    assert_eq!(values.len(), len * 10);
+}
+
+#[test_casing(2, ["short", "a rather long string that will be truncated"], max_arg_width = 16)]
+fn cases_with_truncated_args(s: &str) {
+    assert!(!s.is_empty());
+}
+
+// Cases are named `case_0` and `case_1` despite consisting of literals.
+#[test_casing(2, ["foo", "bar"], names = index)]
+fn cases_with_index_names(s: &str) {
+    assert_eq!(s.len(), 3);
+}
+
+#[test_casing(2, [(1, "first"), (2, "second\n")], print_args = json)]
+fn cases_with_json_output(number: u32, s: &str) {
+    assert!(s.len() > number as usize);
+}
+
+// Sensitive args can be redacted or skipped when printing cases.
+#[test_casing(2, [("alice", "secret-token", 1), ("bob", "other-token", 2)])]
+fn cases_with_redacted_args(user: &str, #[fmt(redact)] token: &str, #[fmt(skip)] id: u32) {
+    assert!(!user.is_empty());
+    assert!(token.ends_with("-token"));
+    assert!(id > 0);
+}
+
+#[allow(clippy::ptr_arg)] // signature is required by `#[fmt(with)]`
+fn fmt_len(values: &Vec<u64>, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(formatter, "{} values", values.len())
+}
+
+#[test_casing(2, [
+    (Ipv4Addr::LOCALHOST, vec![0xde_u8, 0xad], vec![1_u64, 2]),
+    (Ipv4Addr::UNSPECIFIED, vec![], vec![3_u64]),
+])]
+fn cases_with_formatted_args(
+    #[fmt(display)] addr: Ipv4Addr,
+    #[fmt(hex)]
+    #[map(ref = Vec::as_slice)]
+    bytes: &[u8],
+    #[fmt(with = fmt_len)]
+    #[map(ref = Vec::as_slice)]
+    values: &[u64],
+) {
+    assert!(addr.is_loopback() || addr.is_unspecified());
+    assert!(bytes.len() <= 2);
+    assert!(!values.is_empty());
+}
+
+// Args not implementing `Debug` can be marked as opaque.
+struct Client {
+    base_url: &'static str,
+}
+
+#[test_casing(2, [
+    ("http", Client { base_url: "http://localhost" }),
+    ("https", Client { base_url: "https://localhost" }),
+])]
+fn cases_with_opaque_args(
+    scheme: &str,
+    #[fmt(opaque)]
+    #[map(ref)]
+    client: &Client,
+) {
+    assert!(client.base_url.starts_with(scheme));
+}
+
+// Each case is produced exactly once, even if the tests run in parallel.
+static FIRST_CASE_CONSTRUCTIONS: AtomicUsize = AtomicUsize::new(0);
+
+const COUNTED_CASES: TestCases<usize> = cases!((0..3).inspect(|&i| {
+    if i == 0 {
+        FIRST_CASE_CONSTRUCTIONS.fetch_add(1, Ordering::SeqCst);
+    }
+}));
+
+#[test_casing(3, COUNTED_CASES)]
+fn cases_are_produced_once(number: usize) {
+    assert!(number < 3);
+    let constructions = FIRST_CASE_CONSTRUCTIONS.load(Ordering::SeqCst);
+    // The ignored `validate_cases` test may construct cases as well.
+    assert!(constructions <= 2, "{constructions}");
+}
+
 #[test]
 fn unit_test_detection_works() {
     assert!(option_env!("CARGO_TARGET_TMPDIR").is_some());
@@ -119,3 +432,116 @@ mod random {
         assert!(value.to_string().len() <= 10);
     }
 }
+
+#[test_casing]
+fn numbers_from_values(#[values(1, 2, 3)] number: u32, #[values("1", "2")] s: &str) {
+    let parsed: u32 = s.parse().unwrap();
+    assert!(parsed <= 2 && number <= 3);
+}
+
+#[test_casing]
+fn single_arg_from_values(#[values("foo", "bar")] s: &str) {
+    assert_eq!(s.len(), 3);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Fast,
+    Thorough,
+}
+
+#[test_casing(2, [(1, "1"), (2, "2")])]
+fn number_parsing_with_default_mode(number: u32, s: &str, #[default(Mode::Fast)] mode: Mode) {
+    assert_eq!(mode, Mode::Fast);
+    assert_eq!(s.parse::<u32>().unwrap(), number);
+}
+
+#[test_casing(2, [3, 5])]
+fn single_case_arg_with_defaults(
+    number: u32,
+    #[default(Mode::Thorough)] mode: Mode,
+    #[default("test")] s: &str,
+) {
+    assert_eq!(mode, Mode::Thorough);
+    assert_eq!(s, "test");
+    assert!(number % 2 == 1);
+}
+
+#[test_casing]
+fn values_with_default(#[values(1, 2)] number: u32, #[default(Mode::Fast)] mode: Mode) {
+    assert!(number > 0);
+    assert_eq!(mode, Mode::Fast);
+}
+
+#[derive(Debug)]
+struct ParsingCase {
+    input: &'static str,
+    expected: u32,
+    description: &'static str,
+}
+
+const PARSING_CASES: [ParsingCase; 2] = [
+    ParsingCase {
+        input: "42",
+        expected: 42,
+        description: "simple number",
+    },
+    ParsingCase {
+        input: "+7",
+        expected: 7,
+        description: "number with sign",
+    },
+];
+
+#[test_casing(2, PARSING_CASES, case_fields = ParsingCase)]
+fn parsing_with_case_fields(expected: u32, input: &str) {
+    assert_eq!(input.parse::<u32>().unwrap(), expected);
+}
+
+#[test_casing(2, PARSING_CASES, case_fields = ParsingCase)]
+fn single_case_field(#[fmt(display)] description: &str) {
+    assert!(!description.is_empty());
+}
+
+#[test_casing(inline)]
+#[case(2, "2")]
+#[case(3, "3")]
+fn inline_cases(number: u32, s: &str) {
+    assert_eq!(s.parse::<u32>().unwrap(), number);
+}
+
+#[test_casing(inline, tags = ["slow"])]
+#[case("foo")]
+#[case("bar")]
+#[ignore = "testing tags with inline cases"]
+fn ignored_inline_cases(s: &str) {
+    assert_eq!(s.len(), 3);
+}
+
+#[test_casing(2, ["foo", "bar"])]
+fn expected_output(
+    s: &str,
+    #[expected(file = "tests/expected/{fn}/{case}.txt")] expected: Expected,
+) {
+    expected.assert_eq(s.to_uppercase());
+}
+
+const ALWAYS_IGNORED: bool = true;
+
+#[test_casing(2, [1, 2])]
+#[ignore_if(ALWAYS_IGNORED, reason = "testing `ignore_if` with a constant")]
+fn ignored_by_constant(number: i32) {
+    panic!("should be ignored: {number}");
+}
+
+#[test_casing(2, [1, 2])]
+#[ignore_if(cfg(all()), reason = "testing `ignore_if` with a cfg predicate")]
+fn ignored_by_cfg(number: i32) {
+    panic!("should be ignored: {number}");
+}
+
+#[test_casing(2, [1, 2])]
+#[ignore_if(!ALWAYS_IGNORED)]
+fn not_ignored_by_constant(number: i32) {
+    assert!(number > 0);
+}
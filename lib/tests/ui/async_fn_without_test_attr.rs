@@ -0,0 +1,8 @@
+use test_casing::test_casing;
+
+#[test_casing(2, ["test", "this"])]
+async fn tested_function(_arg: &str) {
+    // Does nothing
+}
+
+fn main() {}
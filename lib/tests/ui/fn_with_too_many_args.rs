@@ -1,6 +1,6 @@
 use test_casing::test_casing;
 
-#[test_casing(1, [(1, 2, 3, 4, 5, 6, 7, 8)])]
+#[test_casing(1, [(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13)])]
 fn tested_function(
     _arg0: i32,
     _arg1: i32,
@@ -10,6 +10,11 @@ fn tested_function(
     _arg5: i32,
     _arg6: i32,
     _arg7: i32,
+    _arg8: i32,
+    _arg9: i32,
+    _arg10: i32,
+    _arg11: i32,
+    _arg12: i32,
 ) {
     // Does nothing
 }
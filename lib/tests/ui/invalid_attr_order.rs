@@ -0,0 +1,21 @@
+use test_casing::{decorate, test_casing};
+
+#[decorate(test_casing::decorators::Timeout::secs(1))]
+#[test_casing(2, ["test", "this"])]
+fn decorate_before_test_casing(_arg: &str) {
+    // Does nothing
+}
+
+#[decorate(test_casing::decorators::Timeout::secs(1))]
+fn decorated_fn_with_args(_arg: &str) {
+    // Does nothing
+}
+
+#[test_casing(2, ["test", "this"])]
+#[test]
+#[async_std::test]
+async fn duplicate_test_attrs(_arg: &str) {
+    // Does nothing
+}
+
+fn main() {}
@@ -5,8 +5,11 @@ fn tested_function(_arg: &str) {
     // Does nothing
 }
 
-#[test_casing(0, [])]
-fn other_tested_function(_arg: &str) {
+const CASES: [&str; 2] = ["test", "this"];
+
+#[test_casing(CASES.len(), CASES)]
+#[should_panic]
+fn tested_function_with_should_panic(_arg: &str) {
     // Does nothing
 }
 
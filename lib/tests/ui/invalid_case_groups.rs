@@ -0,0 +1,15 @@
+use test_casing::test_casing;
+
+#[test_casing(2, ["test", "this"], group = basic)]
+#[test_casing(2, ["other", "strings"])]
+fn tested_function(_arg: &str) {
+    // Does nothing
+}
+
+#[test_casing(2, ["test", "this"], group = basic)]
+#[test_casing(2, ["other", "strings"], group = basic)]
+fn other_tested_function(_arg: &str) {
+    // Does nothing
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use test_casing::test_casing;
+
+#[test_casing(2, ["test", "this"], tags = ["Slow"])]
+fn tested_function(_arg: &str) {
+    // Does nothing
+}
+
+#[test_casing(2, ["test", "this"], labels = ["slow"])]
+fn other_tested_function(_arg: &str) {
+    // Does nothing
+}
+
+fn main() {}
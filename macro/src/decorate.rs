@@ -1,16 +1,18 @@
 //! `decorate` proc macro implementation.
 
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{quote, ToTokens};
 use syn::{
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
-    spanned::Spanned,
-    Error as SynError, Expr, Item, ItemFn, ReturnType, Token,
+    Attribute, Error as SynError, Expr, ExprLit, Item, ItemFn, Lit, LitStr, Meta, MetaNameValue,
+    ReturnType, Token,
 };
 
 use std::fmt;
 
+use crate::ignore_if::IgnoreIf;
+
 struct DecorateAttrs {
     decorators: Vec<Expr>,
 }
@@ -34,21 +36,76 @@ impl Parse for DecorateAttrs {
 }
 
 impl DecorateAttrs {
-    fn decorate(&self, function: &ItemFn) -> syn::Result<proc_macro2::TokenStream> {
+    /// Generates a guard marking the test as expected to panic if it has a `#[should_panic]`
+    /// attribute, so that decorators (e.g., retries) can treat the expected panic as a success.
+    fn expected_panic_guard(attrs: &[Attribute]) -> syn::Result<Option<proc_macro2::TokenStream>> {
+        let Some(attr) = attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("should_panic"))
+        else {
+            return Ok(None);
+        };
+        let expected = match &attr.meta {
+            Meta::Path(_) => None,
+            Meta::NameValue(MetaNameValue {
+                value:
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(expected),
+                        ..
+                    }),
+                ..
+            }) => Some(expected.clone()),
+            Meta::List(list) => {
+                let mut expected = None;
+                list.parse_nested_meta(|nested| {
+                    if nested.path.is_ident("expected") {
+                        expected = Some(nested.value()?.parse::<LitStr>()?);
+                        Ok(())
+                    } else {
+                        Err(nested.error("unsupported `should_panic` field"))
+                    }
+                })?;
+                expected
+            }
+            Meta::NameValue(_) => {
+                let message = "unrecognized `should_panic` value; should be a string literal";
+                return Err(SynError::new_spanned(attr, message));
+            }
+        };
+
+        let cr = quote!(test_casing::decorators);
+        let expected = if let Some(expected) = expected {
+            quote!(::core::option::Option::Some(#expected))
+        } else {
+            quote!(::core::option::Option::None)
+        };
+        Ok(Some(quote! {
+            let __expected_panic_guard = #cr::ExpectedPanicGuard::new(#expected);
+        }))
+    }
+
+    fn decorate(&self, mut function: ItemFn) -> syn::Result<proc_macro2::TokenStream> {
+        // The test attribute is already expanded at this point, so a conditionally ignored test
+        // can only be skipped at runtime.
+        let ignore_if = IgnoreIf::take_from(&mut function.attrs)?;
         let ItemFn {
             attrs,
             vis,
             sig,
             block,
-        } = function;
+        } = &function;
 
-        if let Some(asyncness) = &sig.asyncness {
-            let message = "Cannot decorate an async function. Make sure that #[decorate] \
-                is applied *after* an attribute for the async test, such as #[tokio::test]";
-            return Err(SynError::new(asyncness.span(), message));
+        let test_casing_attr = attrs
+            .iter()
+            .find(|attr| attr.path().segments.last().unwrap().ident == "test_casing");
+        if let Some(test_casing_attr) = test_casing_attr {
+            let message = "#[decorate] must be placed *after* #[test_casing], so that decorators \
+                are applied to each generated test case";
+            return Err(SynError::new_spanned(test_casing_attr, message));
         }
         if !sig.inputs.is_empty() {
-            let message = "Cannot decorate a function with attributes";
+            let message = "Cannot decorate a function with arguments. If this is a parameterized \
+                test, make sure that #[decorate] is applied *after* #[test_casing]";
             return Err(SynError::new_spanned(&sig.inputs, message));
         }
 
@@ -65,18 +122,58 @@ impl DecorateAttrs {
             None
         };
 
+        let maybe_skip_test = ignore_if.map(|ignore_if| ignore_if.skip_test(&sig.output));
+        if sig.asyncness.is_some() {
+            // The async test attribute (if any) is not expanded yet, so we can wrap the test future.
+            return Ok(quote! {
+                #(#attrs)*
+                #vis #sig {
+                    #maybe_skip_test
+                    static __DECORATORS: &dyn #cr::DecorateTestAsyncFn<#ret_value_or_void> =
+                        &(#(#decorators,)*);
+                    let __test_fn = || -> #cr::TestFuture<#ret_value_or_void> {
+                        ::std::boxed::Box::pin(async move {
+                            let __output: #ret_value_or_void = #block;
+                            __output
+                        })
+                    };
+                    #cr::DecorateTestAsyncFn::decorate_and_test_async_fn(__DECORATORS, __test_fn)
+                        .await
+                }
+            });
+        }
+        let maybe_expected_panic_guard = Self::expected_panic_guard(attrs)?;
+        let run_with_default_timeout = with_default_timeout(&quote! {
+            move || #cr::DecorateTestFn::decorate_and_test_fn(__DECORATORS, __test_fn)
+        });
+
         Ok(quote! {
             #(#attrs)*
             #vis #sig {
+                #maybe_skip_test
                 static __DECORATORS: &dyn #cr::DecorateTestFn<#ret_value_or_void> =
                     &(#(#decorators,)*);
                 let __test_fn = || #ret_value #block;
-                #cr::DecorateTestFn::decorate_and_test_fn(__DECORATORS, __test_fn) #maybe_semicolon
+                #maybe_expected_panic_guard
+                #run_with_default_timeout #maybe_semicolon
             }
         })
     }
 }
 
+/// Wraps a non-async test function so that it's run with the default timeout. The default timeout
+/// is only applied if the test output is `Send`; this is resolved by the compiler.
+pub(crate) fn with_default_timeout(test_fn: &impl ToTokens) -> proc_macro2::TokenStream {
+    let cr = quote!(test_casing::decorators);
+    quote! {
+        {
+            #[allow(unused_imports)]
+            use #cr::{ApplyDefaultTimeout as _, SkipDefaultTimeout as _};
+            (&#cr::DefaultTimeoutFn::new(#test_fn)).run_with_default_timeout()
+        }
+    }
+}
+
 pub(crate) fn impl_decorate(
     attr: TokenStream,
     item: TokenStream,
@@ -84,7 +181,7 @@ pub(crate) fn impl_decorate(
     let attrs: DecorateAttrs = syn::parse(attr)?;
     let item: Item = syn::parse(item)?;
     match item {
-        Item::Fn(function) => attrs.decorate(&function),
+        Item::Fn(function) => attrs.decorate(function),
         item => {
             let message = "Item is not supported; use `#[decorate] on functions";
             Err(SynError::new_spanned(&item, message))
@@ -0,0 +1,76 @@
+//! `DecorateTest` derive macro implementation.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+    parse_quote, Data, DeriveInput, Error as SynError, GenericParam, Ident, Index, Member,
+    TypeParam,
+};
+
+pub(crate) fn impl_derive_decorate_test(
+    input: TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let input: DeriveInput = syn::parse(input)?;
+    let Data::Struct(data) = &input.data else {
+        let message = "#[derive(DecorateTest)] can only be applied to structs";
+        return Err(SynError::new_spanned(&input.ident, message));
+    };
+
+    let cr = quote!(test_casing::decorators);
+    let ret_param = Ident::new("__R", Span::call_site());
+    let members: Vec<Member> = data
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(i)),
+        })
+        .collect();
+
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    for field in &data.fields {
+        let ty = &field.ty;
+        where_clause
+            .predicates
+            .push(parse_quote!(#ty: #cr::DecorateTest<#ret_param>));
+    }
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+    let mut impl_generics = generics.clone();
+    impl_generics
+        .params
+        .insert(0, GenericParam::Type(TypeParam::from(ret_param.clone())));
+    let (impl_generics, ..) = impl_generics.split_for_impl();
+
+    let name = &input.ident;
+    Ok(quote! {
+        impl #impl_generics #cr::DecorateTest<#ret_param> for #name #ty_generics #where_clause {
+            fn decorate_and_test<__F: #cr::TestFn<#ret_param>>(
+                &'static self,
+                test_fn: __F,
+            ) -> #ret_param {
+                let process_boundaries = [
+                    #(#cr::DecorateTest::<#ret_param>::is_process_boundary(&self.#members),)*
+                ];
+                let mut __skipped = #cr::decorators_to_skip(process_boundaries).into_iter();
+                #(
+                let is_skipped = __skipped.next().unwrap();
+                let test_fn = move || {
+                    if is_skipped {
+                        test_fn()
+                    } else {
+                        #cr::DecorateTest::<#ret_param>::decorate_and_test(&self.#members, test_fn)
+                    }
+                };
+                )*
+                test_fn()
+            }
+
+            fn is_process_boundary(&self) -> bool {
+                false #(|| #cr::DecorateTest::<#ret_param>::is_process_boundary(&self.#members))*
+            }
+        }
+    })
+}
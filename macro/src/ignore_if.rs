@@ -0,0 +1,204 @@
+//! Conditional test ignoring via the `#[ignore_if(..)]` attribute.
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{
+    parenthesized,
+    parse::{Parse, ParseStream},
+    token, Attribute, Error as SynError, Expr, Ident, LitStr, ReturnType, Token, Type, TypePath,
+};
+
+use std::fmt;
+
+/// Condition under which a test is ignored.
+#[derive(Clone)]
+enum Condition {
+    /// `cfg(..)` predicate.
+    Cfg(TokenStream),
+    /// Boolean expression, e.g. a path to a constant.
+    Expr(Box<Expr>),
+}
+
+impl Parse for Condition {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        if input.peek(Ident) && input.peek2(token::Paren) {
+            let fork = input.fork();
+            let ident: Ident = fork.parse()?;
+            if ident == "cfg" {
+                input.parse::<Ident>()?;
+                let content;
+                parenthesized!(content in input);
+                return Ok(Self::Cfg(content.parse()?));
+            }
+        }
+        Ok(Self::Expr(Box::new(input.parse()?)))
+    }
+}
+
+/// Parsed `#[ignore_if(condition, reason = "..")]` attribute.
+#[derive(Clone)]
+pub(crate) struct IgnoreIf {
+    condition: Condition,
+    reason: Option<LitStr>,
+}
+
+impl fmt::Debug for IgnoreIf {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let condition = match &self.condition {
+            Condition::Cfg(_) => "cfg",
+            Condition::Expr(_) => "expr",
+        };
+        formatter
+            .debug_struct("IgnoreIf")
+            .field("condition", &condition)
+            .field("reason", &self.reason.as_ref().map(LitStr::value))
+            .finish()
+    }
+}
+
+impl Parse for IgnoreIf {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let condition = input.parse()?;
+        let mut reason = None;
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let name: Ident = input.parse()?;
+            if name != "reason" {
+                let message = "expected `reason = \"..\"`";
+                return Err(SynError::new(name.span(), message));
+            }
+            input.parse::<Token![=]>()?;
+            reason = Some(input.parse()?);
+            input.parse::<Option<Token![,]>>()?;
+        }
+        Ok(Self { condition, reason })
+    }
+}
+
+impl IgnoreIf {
+    /// Removes the `#[ignore_if(..)]` attribute from `attrs` and parses it.
+    pub fn take_from(attrs: &mut Vec<Attribute>) -> syn::Result<Option<Self>> {
+        let mut positions = attrs
+            .iter()
+            .enumerate()
+            .filter_map(|(i, attr)| attr.path().is_ident("ignore_if").then_some(i));
+        let Some(position) = positions.next() else {
+            return Ok(None);
+        };
+        if let Some(duplicate_position) = positions.next() {
+            let message = "a test function must have at most one #[ignore_if(..)] attribute";
+            return Err(SynError::new_spanned(&attrs[duplicate_position], message));
+        }
+        let attr = attrs.remove(position);
+        attr.parse_args().map(Some)
+    }
+
+    /// Returns a `bool` expression evaluating the condition.
+    pub fn condition(&self) -> TokenStream {
+        match &self.condition {
+            Condition::Cfg(predicate) => quote!(::core::cfg!(#predicate)),
+            Condition::Expr(expr) => expr.to_token_stream(),
+        }
+    }
+
+    #[cfg(feature = "nightly")]
+    pub fn reason(&self) -> Option<&LitStr> {
+        self.reason.as_ref()
+    }
+
+    /// Returns an attribute marking the test as ignored if the condition is a `cfg` predicate.
+    /// Other conditions cannot be expressed with attributes.
+    pub fn cfg_attr(&self) -> Option<TokenStream> {
+        let Condition::Cfg(predicate) = &self.condition else {
+            return None;
+        };
+        let ignore = self
+            .reason
+            .as_ref()
+            .map_or_else(|| quote!(ignore), |reason| quote!(ignore = #reason));
+        Some(quote!(#[cfg_attr(#predicate, #ignore)]))
+    }
+
+    /// Returns statements skipping the test at runtime if the condition holds. The statements
+    /// must be placed in a function with the `ret` return type, which must be `()`
+    /// or `Result<(), _>`.
+    pub fn skip_test(&self, ret: &ReturnType) -> TokenStream {
+        let condition = self.condition();
+        let message = self.reason.as_ref().map_or_else(
+            || quote!(::std::println!("Skipping ignored test")),
+            |reason| quote!(::std::println!("Skipping ignored test: {}", #reason)),
+        );
+        let return_value = skipped_test_output(ret);
+        quote! {
+            if #condition {
+                #message;
+                return #return_value;
+            }
+        }
+    }
+}
+
+/// Returns the value returned by a skipped test with the specified return type. Besides `()`
+/// and `Result`s, supports return types used by property tests (`bool` and `TestResult`).
+pub(crate) fn skipped_test_output(ret: &ReturnType) -> Option<TokenStream> {
+    let ReturnType::Type(_, ty) = ret else {
+        return None;
+    };
+    let last_segment = match ty.as_ref() {
+        Type::Path(TypePath { qself: None, path }) => path.segments.last(),
+        _ => None,
+    };
+    Some(match last_segment {
+        Some(segment) if segment.ident == "bool" => quote!(true),
+        Some(segment) if segment.ident == "TestResult" => quote!(<#ty>::discard()),
+        _ => quote!(::core::result::Result::Ok(())),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_cfg_condition() {
+        let ignore_if: IgnoreIf = syn::parse_quote!(cfg(target_os = "windows"), reason = "slow");
+        assert_eq!(ignore_if.reason.as_ref().unwrap().value(), "slow");
+        let condition = ignore_if.condition().to_string();
+        assert!(condition.contains("cfg !"), "{condition}");
+
+        let attr = ignore_if.cfg_attr().unwrap();
+        let attr: Attribute = syn::parse_quote!(#attr);
+        let expected: Attribute =
+            syn::parse_quote!(#[cfg_attr(target_os = "windows", ignore = "slow")]);
+        assert_eq!(attr, expected);
+    }
+
+    #[test]
+    fn parsing_expr_condition() {
+        let ignore_if: IgnoreIf = syn::parse_quote!(config::IS_CI);
+        assert!(ignore_if.reason.is_none());
+        assert!(ignore_if.cfg_attr().is_none());
+        assert_eq!(ignore_if.condition().to_string(), "config :: IS_CI");
+
+        let ignore_if: IgnoreIf = syn::parse_quote!(is_ci(), reason = "no GPU");
+        assert!(ignore_if.cfg_attr().is_none());
+        let skip = ignore_if.skip_test(&syn::parse_quote!(-> Result<(), String>));
+        let skip = skip.to_string();
+        assert!(skip.starts_with("if is_ci ()"), "{skip}");
+        assert!(skip.contains("Ok (())"), "{skip}");
+    }
+
+    #[test]
+    fn parsing_errors() {
+        let err = syn::parse_str::<IgnoreIf>("IS_CI, message = \"oops\"")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("expected `reason"), "{err}");
+
+        let mut attrs: Vec<Attribute> = vec![
+            syn::parse_quote!(#[ignore_if(IS_CI)]),
+            syn::parse_quote!(#[ignore_if(cfg(miri))]),
+        ];
+        let err = IgnoreIf::take_from(&mut attrs).unwrap_err().to_string();
+        assert!(err.contains("at most one"), "{err}");
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! - The `test_casing` macro from this crate flattens parameterized tests into a set of test cases.
 //! - The `decorate` macro wraps a tested function to add retries, timeouts etc.
+//! - The `DecorateTest` derive macro composes decorators in struct fields.
 //!
 //! See the [`test-casing`] crate docs for macro documentation and examples of usage.
 //!
@@ -19,9 +20,15 @@ extern crate proc_macro;
 use proc_macro::TokenStream;
 
 mod decorate;
+mod decorate_test;
+mod ignore_if;
+mod tags;
 mod test_casing;
 
-use crate::{decorate::impl_decorate, test_casing::impl_test_casing};
+use crate::{
+    decorate::impl_decorate, decorate_test::impl_derive_decorate_test,
+    test_casing::impl_test_casing,
+};
 
 #[proc_macro_attribute]
 pub fn test_casing(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -38,3 +45,11 @@ pub fn decorate(attr: TokenStream, item: TokenStream) -> TokenStream {
         Err(err) => err.into_compile_error().into(),
     }
 }
+
+#[proc_macro_derive(DecorateTest)]
+pub fn derive_decorate_test(input: TokenStream) -> TokenStream {
+    match impl_derive_decorate_test(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
@@ -0,0 +1,120 @@
+//! Test tags encoded in generated test names.
+
+use quote::quote;
+use syn::{
+    bracketed,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Error as SynError, Ident, LitStr, Token,
+};
+
+use std::fmt;
+
+/// Tags specified via the `tags = ["slow", "serial-db"]` macro option.
+#[derive(Clone, Default)]
+pub(crate) struct Tags {
+    values: Vec<String>,
+}
+
+impl fmt::Debug for Tags {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.debug_list().entries(&self.values).finish()
+    }
+}
+
+impl Tags {
+    fn validate(tag: &LitStr) -> syn::Result<String> {
+        let value = tag.value();
+        let is_valid_char = |ch: char| ch.is_ascii_lowercase() || ch.is_ascii_digit();
+        let is_valid = value
+            .split(['-', '_'])
+            .all(|part| !part.is_empty() && part.chars().all(is_valid_char));
+        if is_valid {
+            Ok(value.replace('-', "_"))
+        } else {
+            let message = "tag must consist of lowercase ASCII letters and digits, \
+                optionally separated by single `-` or `_` chars";
+            Err(SynError::new(tag.span(), message))
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the suffix to append to the test name.
+    pub fn name_suffix(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+        let mut suffix = String::new();
+        for tag in &self.values {
+            suffix.push_str("__tag_");
+            suffix.push_str(tag);
+        }
+        suffix.push_str("__");
+        suffix
+    }
+
+    /// Returns an attribute to place on a tagged test function. Double underscores used
+    /// in the tag encoding trigger the `non_snake_case` lint, so we allow it.
+    pub fn allow_attr(&self) -> Option<proc_macro2::TokenStream> {
+        (!self.is_empty()).then(|| quote!(#[allow(non_snake_case)]))
+    }
+}
+
+impl Parse for Tags {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let option: Ident = input.parse()?;
+        if option != "tags" {
+            let message = "unknown option; supported options are `tags`, `group`, `async_with`, \
+                `print_args` and `max_arg_width`";
+            return Err(SynError::new(option.span(), message));
+        }
+        input.parse::<Token![=]>()?;
+
+        let content;
+        bracketed!(content in input);
+        let tags = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+        let mut values = Vec::with_capacity(tags.len());
+        for tag in &tags {
+            let value = Self::validate(tag)?;
+            if values.contains(&value) {
+                let message = "duplicate tag";
+                return Err(SynError::new(tag.span(), message));
+            }
+            values.push(value);
+        }
+        Ok(Self { values })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_tags() {
+        let tags: Tags = syn::parse_quote!(tags = ["slow", "serial-db", "v2"]);
+        assert_eq!(tags.values, ["slow", "serial_db", "v2"]);
+        assert_eq!(tags.name_suffix(), "__tag_slow__tag_serial_db__tag_v2__");
+
+        let tags: Tags = syn::parse_quote!(tags = []);
+        assert_eq!(tags.name_suffix(), "");
+    }
+
+    #[test]
+    fn invalid_tags() {
+        let invalid_tags = [
+            quote!(tag = ["slow"]),
+            quote!(tags = ["Slow"]),
+            quote!(tags = ["slow__db"]),
+            quote!(tags = ["-slow"]),
+            quote!(tags = [""]),
+            quote!(tags = ["slow", "slow"]),
+        ];
+        for tags in invalid_tags {
+            assert!(syn::parse2::<Tags>(tags.clone()).is_err(), "{tags}");
+        }
+    }
+}
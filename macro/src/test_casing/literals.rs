@@ -0,0 +1,259 @@
+//! Compile-time evaluation of cases consisting of literals. Used to generate descriptive names
+//! for test case functions on stable Rust.
+
+use quote::ToTokens;
+use syn::{Expr, ExprArray, ExprCall, ExprLit, ExprUnary, Lit, UnOp};
+
+/// Literal arg value.
+#[derive(Debug, Clone, PartialEq)]
+struct LiteralValue {
+    /// Source code of the value, e.g. `"foo"` or `-3`.
+    source: String,
+    /// Part of the test function name corresponding to the value (not sanitized).
+    name_part: String,
+    /// Whether the value should be hidden from names and docs (e.g., because it's redacted).
+    is_hidden: bool,
+}
+
+impl LiteralValue {
+    fn new(expr: &Expr) -> Option<Self> {
+        match expr {
+            Expr::Lit(ExprLit { lit, .. }) => {
+                let name_part = match lit {
+                    Lit::Str(s) => s.value(),
+                    Lit::Char(ch) => ch.value().to_string(),
+                    Lit::Int(int) => int.base10_digits().to_owned(),
+                    Lit::Float(float) => float.base10_digits().to_owned(),
+                    Lit::Bool(bool) => bool.value.to_string(),
+                    _ => return None,
+                };
+                Some(Self {
+                    source: lit.to_token_stream().to_string(),
+                    name_part,
+                    is_hidden: false,
+                })
+            }
+            Expr::Unary(ExprUnary {
+                op: UnOp::Neg(_),
+                expr,
+                ..
+            }) if matches!(
+                expr.as_ref(),
+                Expr::Lit(ExprLit {
+                    lit: Lit::Int(_) | Lit::Float(_),
+                    ..
+                })
+            ) =>
+            {
+                let inner = Self::new(expr)?;
+                Some(Self {
+                    source: format!("-{}", inner.source),
+                    name_part: format!("neg{}", inner.name_part),
+                    is_hidden: false,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Test case with all arg values being literals.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct LiteralCase {
+    values: Vec<LiteralValue>,
+}
+
+impl LiteralCase {
+    /// Maximum length of the descriptive part of the test function name.
+    const MAX_NAME_LEN: usize = 40;
+
+    fn new(expr: &Expr, arg_count: usize) -> Option<Self> {
+        let values = if arg_count == 1 {
+            vec![LiteralValue::new(expr)?]
+        } else {
+            let Expr::Tuple(tuple) = expr else {
+                return None;
+            };
+            if tuple.elems.len() != arg_count {
+                return None;
+            }
+            let values = tuple.elems.iter().map(LiteralValue::new);
+            values.collect::<Option<_>>()?
+        };
+        Some(Self { values })
+    }
+
+    /// Evaluates cases if the cases expression is an array of literals (or tuples of literals
+    /// if there are multiple args), or a `Product` of arrays of literals. Returns `None`
+    /// if the expression has any other form.
+    pub fn from_cases_expr(expr: &Expr, arg_count: usize) -> Option<Vec<Self>> {
+        match expr {
+            Expr::Array(array) => {
+                let cases = array.elems.iter();
+                cases.map(|case| Self::new(case, arg_count)).collect()
+            }
+            Expr::Call(ExprCall { func, args, .. }) if args.len() == 1 => {
+                let Expr::Path(path) = func.as_ref() else {
+                    return None;
+                };
+                if path.path.segments.last()?.ident != "Product" {
+                    return None;
+                }
+                let Some(Expr::Tuple(components)) = args.first() else {
+                    return None;
+                };
+                if components.elems.len() != arg_count {
+                    return None;
+                }
+                let components: Vec<_> = components
+                    .elems
+                    .iter()
+                    .map(|component| {
+                        let Expr::Array(ExprArray { elems, .. }) = component else {
+                            return None;
+                        };
+                        elems
+                            .iter()
+                            .map(LiteralValue::new)
+                            .collect::<Option<Vec<_>>>()
+                    })
+                    .collect::<Option<_>>()?;
+                Some(Self::product(&components))
+            }
+            _ => None,
+        }
+    }
+
+    /// Computes the Cartesian product of values in the same order as `Product` does
+    /// (i.e., the last component changes the fastest).
+    fn product(components: &[Vec<LiteralValue>]) -> Vec<Self> {
+        let mut cases = vec![Self { values: vec![] }];
+        for component in components {
+            cases = cases
+                .into_iter()
+                .flat_map(|case| {
+                    component.iter().map(move |value| {
+                        let mut values = case.values.clone();
+                        values.push(value.clone());
+                        Self { values }
+                    })
+                })
+                .collect();
+        }
+        cases
+    }
+
+    /// Hides values of the args for which `is_hidden` returns `true` (e.g., redacted args).
+    pub fn hide_values(&mut self, mut is_hidden: impl FnMut(usize) -> bool) {
+        for (i, value) in self.values.iter_mut().enumerate() {
+            value.is_hidden = is_hidden(i);
+        }
+    }
+
+    /// Returns the descriptive part of the test function name, or `None` if the values
+    /// do not contain any chars usable in the name.
+    pub fn name_part(&self) -> Option<String> {
+        let mut name = String::new();
+        for value in self.values.iter().filter(|value| !value.is_hidden) {
+            for ch in value.name_part.chars().chain(['_']) {
+                if ch.is_ascii_alphanumeric() {
+                    name.push(ch.to_ascii_lowercase());
+                } else if !name.is_empty() && !name.ends_with('_') {
+                    name.push('_');
+                }
+            }
+        }
+        name.truncate(Self::MAX_NAME_LEN);
+        let name = name.trim_end_matches('_');
+        (!name.is_empty()).then(|| name.to_owned())
+    }
+
+    /// Returns a human-readable description of the case args.
+    #[cfg_attr(feature = "nightly", allow(dead_code))] // case args are included into test names
+    pub fn description(&self, arg_names: &[String]) -> String {
+        let args = self.values.iter().zip(arg_names);
+        let args = args.map(|(value, name)| {
+            let value = if value.is_hidden {
+                "***"
+            } else {
+                &value.source
+            };
+            format!("{name} = {value}")
+        });
+        args.collect::<Vec<_>>().join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluating_literal_arrays() {
+        let expr: Expr = syn::parse_quote!(["foo", "Bar baz", "??"]);
+        let cases = LiteralCase::from_cases_expr(&expr, 1).unwrap();
+        let names: Vec<_> = cases.iter().map(LiteralCase::name_part).collect();
+        assert_eq!(
+            names,
+            [Some("foo".to_owned()), Some("bar_baz".to_owned()), None]
+        );
+        let arg_names = ["s".to_owned()];
+        assert_eq!(cases[1].description(&arg_names), "s = \"Bar baz\"");
+
+        let expr: Expr = syn::parse_quote!([(-2, 'x'), (3_u32, 'Y'), (1.5, '-')]);
+        let cases = LiteralCase::from_cases_expr(&expr, 2).unwrap();
+        let names: Vec<_> = cases.iter().filter_map(LiteralCase::name_part).collect();
+        assert_eq!(names, ["neg2_x", "3_y", "1_5"]);
+        let arg_names = ["number".to_owned(), "ch".to_owned()];
+        assert_eq!(cases[0].description(&arg_names), "number = -2, ch = 'x'");
+        assert_eq!(cases[1].description(&arg_names), "number = 3_u32, ch = 'Y'");
+    }
+
+    #[test]
+    fn hiding_values() {
+        let expr: Expr = syn::parse_quote!([("alice", "secret", 1)]);
+        let mut cases = LiteralCase::from_cases_expr(&expr, 3).unwrap();
+        cases[0].hide_values(|i| i > 0);
+        assert_eq!(cases[0].name_part().unwrap(), "alice");
+        let arg_names = ["user".to_owned(), "token".to_owned(), "id".to_owned()];
+        assert_eq!(
+            cases[0].description(&arg_names),
+            "user = \"alice\", token = ***, id = ***"
+        );
+    }
+
+    #[test]
+    fn evaluating_literal_product() {
+        let expr: Expr = syn::parse_quote!(test_casing::Product(([1, 2], [true, false])));
+        let cases = LiteralCase::from_cases_expr(&expr, 2).unwrap();
+        let names: Vec<_> = cases.iter().filter_map(LiteralCase::name_part).collect();
+        assert_eq!(names, ["1_true", "1_false", "2_true", "2_false"]);
+    }
+
+    #[test]
+    fn truncating_long_names() {
+        let expr: Expr = syn::parse_quote!(["a very long string that does not fit into the name"]);
+        let cases = LiteralCase::from_cases_expr(&expr, 1).unwrap();
+        let name = cases[0].name_part().unwrap();
+        assert_eq!(name, "a_very_long_string_that_does_not_fit_int");
+    }
+
+    #[test]
+    fn non_literal_cases() {
+        let exprs: [Expr; 5] = [
+            syn::parse_quote!(CASES),
+            syn::parse_quote!([1, 2, CONST]),
+            syn::parse_quote!([(1, 2), (3, 4, 5)]),
+            syn::parse_quote!(Product((0..3, ["foo"]))),
+            syn::parse_quote!([b"bytes"]),
+        ];
+        for expr in &exprs {
+            let arg_count = if matches!(expr, Expr::Array(_)) { 1 } else { 2 };
+            assert!(
+                LiteralCase::from_cases_expr(expr, arg_count).is_none(),
+                "{}",
+                expr.to_token_stream()
+            );
+        }
+    }
+}
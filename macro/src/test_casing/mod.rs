@@ -1,28 +1,177 @@
 //! `test_casing` proc macro implementation.
 
 use proc_macro::TokenStream;
+use proc_macro2::TokenTree;
 use quote::{quote, ToTokens};
 use syn::{
     ext::IdentExt,
     parse::{Error as SynError, Parse, ParseStream},
+    punctuated::Punctuated,
     spanned::Spanned,
-    Attribute, Expr, FnArg, Ident, Item, ItemFn, LitInt, Pat, PatType, Path, ReturnType, Signature,
-    Token,
+    Attribute, Expr, ExprLit, FnArg, Ident, Item, ItemFn, Lit, LitInt, LitStr, Meta, Pat, PatType,
+    Path, ReturnType, Signature, Token,
 };
 
 use std::{fmt, mem};
 
+mod literals;
 #[cfg(feature = "nightly")]
 mod nightly;
 #[cfg(test)]
 mod tests;
 
+use self::literals::LiteralCase;
 #[cfg(feature = "nightly")]
 use self::nightly::NightlyData;
+use crate::{
+    decorate::with_default_timeout,
+    ignore_if::{skipped_test_output, IgnoreIf},
+    tags::Tags,
+};
+
+/// Number of test cases specified in the `test_casing` attribute.
+#[derive(Clone)]
+enum CaseCount {
+    /// Number literal; a separate test is generated for each case.
+    Literal(usize),
+    /// Constant expression (e.g., a path to a constant). Since the value of the expression
+    /// is unknown to the macro, all cases are tested in a single test.
+    Expr(Box<Expr>),
+}
+
+impl fmt::Debug for CaseCount {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Literal(count) => formatter.debug_tuple("Literal").field(count).finish(),
+            Self::Expr(_) => formatter.debug_tuple("Expr").field(&"_").finish(),
+        }
+    }
+}
+
+impl ToTokens for CaseCount {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        match self {
+            Self::Literal(count) => count.to_tokens(tokens),
+            Self::Expr(expr) => expr.to_tokens(tokens),
+        }
+    }
+}
+
+impl CaseCount {
+    fn new(expr: Expr) -> syn::Result<Self> {
+        let count_lit = match &expr {
+            Expr::Lit(ExprLit {
+                lit: Lit::Int(count_lit),
+                ..
+            }) => count_lit,
+            Expr::Lit(_) => {
+                let message = "expected integer literal or constant expression";
+                return Err(SynError::new_spanned(&expr, message));
+            }
+            _ => return Ok(Self::Expr(Box::new(expr))),
+        };
+
+        Ok(Self::Literal(count_lit.base10_parse()?))
+    }
+}
+
+/// Mode of printing case args.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum PrintMode {
+    /// Print all args on a single line.
+    #[default]
+    Compact,
+    /// Print args on separate lines, using the multi-line format for large values.
+    Pretty,
+    /// Print the case as a single-line JSON object.
+    Json,
+}
+
+impl Parse for PrintMode {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let mode: Ident = input.parse()?;
+        if mode == "compact" {
+            Ok(Self::Compact)
+        } else if mode == "pretty" {
+            Ok(Self::Pretty)
+        } else if mode == "json" {
+            Ok(Self::Json)
+        } else {
+            let message = "unknown print mode; supported modes are `compact`, `pretty` and `json`";
+            Err(SynError::new(mode.span(), message))
+        }
+    }
+}
+
+/// Naming scheme for generated test cases.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum CaseNames {
+    /// Append args to the case index if the cases consist of literals.
+    #[default]
+    Descriptive,
+    /// Use only the case index.
+    Index,
+}
+
+impl Parse for CaseNames {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let names: Ident = input.parse()?;
+        if names == "descriptive" {
+            Ok(Self::Descriptive)
+        } else if names == "index" {
+            Ok(Self::Index)
+        } else {
+            let message = "unknown case naming; supported values are `descriptive` and `index`";
+            Err(SynError::new(names.span(), message))
+        }
+    }
+}
+
+/// Options for printing case args before running a test case.
+#[derive(Debug, Clone, Copy, Default)]
+struct PrintArgs {
+    mode: PrintMode,
+    /// Maximum width of a printed arg.
+    max_width: Option<usize>,
+}
+
+impl PrintArgs {
+    fn is_default(self) -> bool {
+        self.mode == PrintMode::Compact && self.max_width.is_none()
+    }
+
+    /// Generates an expression for `PrintOptions`.
+    fn options(self) -> proc_macro2::TokenStream {
+        let cr = quote!(test_casing);
+        let maybe_mode = match self.mode {
+            PrintMode::Compact => None,
+            PrintMode::Pretty => Some(quote!(.pretty())),
+            PrintMode::Json => Some(quote!(.json())),
+        };
+        let maybe_max_width = self
+            .max_width
+            .map(|max_width| quote!(.with_max_width(#max_width)));
+        quote!(#cr::PrintOptions::new() #maybe_mode #maybe_max_width)
+    }
+}
 
+#[derive(Clone)]
 struct CaseAttrs {
-    count: usize,
+    count: CaseCount,
     expr: Expr,
+    tags: Tags,
+    /// Name of the case group; required if there are multiple `test_casing` attributes.
+    group: Option<Ident>,
+    /// Test attribute to place on generated async test functions (e.g., `tokio::test`).
+    async_with: Option<Meta>,
+    print_args: PrintArgs,
+    names: CaseNames,
+    /// Struct yielded by the case iterator, with fields bound to the args with the same names.
+    case_fields: Option<Path>,
+    /// Whether to generate functions running cases outside of tests.
+    export: bool,
+    /// Domain of values that must be covered by the cases.
+    exhaustive: Option<Expr>,
 }
 
 impl fmt::Debug for CaseAttrs {
@@ -30,41 +179,335 @@ impl fmt::Debug for CaseAttrs {
         formatter
             .debug_struct("CaseAttrs")
             .field("count", &self.count)
+            .field("tags", &self.tags)
+            .field("group", &self.group)
+            .field("has_async_with", &self.async_with.is_some())
+            .field("print_args", &self.print_args)
+            .field("names", &self.names)
+            .field("has_case_fields", &self.case_fields.is_some())
+            .field("export", &self.export)
+            .field("has_exhaustive", &self.exhaustive.is_some())
             .finish_non_exhaustive()
     }
 }
 
-impl CaseAttrs {
-    fn parse(attr: proc_macro2::TokenStream) -> syn::Result<Self> {
-        struct CaseAttrsSyntax {
-            count: LitInt,
-            _comma: Token![,],
-            expr: Expr,
+/// Raw syntax of `#[test_casing]` attribute args.
+struct CaseAttrsSyntax {
+    count: Expr,
+    _comma: Token![,],
+    expr: Expr,
+    tags: Option<Tags>,
+    group: Option<Ident>,
+    async_with: Option<Meta>,
+    print_mode: Option<PrintMode>,
+    max_arg_width: Option<LitInt>,
+    names: Option<CaseNames>,
+    case_fields: Option<Path>,
+    export: bool,
+    exhaustive: Option<Expr>,
+}
+
+impl CaseAttrsSyntax {
+    /// Parses an `option = value` pair, checking that the option is not duplicated.
+    fn parse_option<T: Parse>(
+        input: ParseStream<'_>,
+        option: &Ident,
+        value: &mut Option<T>,
+    ) -> syn::Result<()> {
+        if value.is_some() {
+            let message = format!("duplicate `{option}` option");
+            return Err(SynError::new(option.span(), message));
         }
+        input.parse::<Ident>()?;
+        input.parse::<Token![=]>()?;
+        *value = Some(input.parse()?);
+        Ok(())
+    }
 
-        impl Parse for CaseAttrsSyntax {
-            fn parse(input: ParseStream) -> syn::Result<Self> {
-                Ok(Self {
-                    count: input.parse()?,
-                    _comma: input.parse()?,
-                    expr: input.parse()?,
-                })
-            }
+    /// Parses a flag option, checking that the option is not duplicated.
+    fn parse_flag(input: ParseStream<'_>, option: &Ident, value: &mut bool) -> syn::Result<()> {
+        if *value {
+            let message = format!("duplicate `{option}` option");
+            return Err(SynError::new(option.span(), message));
         }
+        input.parse::<Ident>()?;
+        *value = true;
+        Ok(())
+    }
+}
 
-        let syntax: CaseAttrsSyntax = syn::parse2(attr)?;
-        let count: usize = syntax.count.base10_parse()?;
-        if count == 0 {
-            let message = "number of test cases must be positive";
-            return Err(SynError::new(syntax.count.span(), message));
+impl Parse for CaseAttrsSyntax {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let count = input.parse()?;
+        let comma = input.parse()?;
+        let expr = input.parse()?;
+        let mut tags = None;
+        let mut group = None;
+        let mut async_with = None;
+        let mut print_mode = None;
+        let mut max_arg_width = None;
+        let mut names = None;
+        let mut case_fields = None;
+        let mut export = false;
+        let mut exhaustive = None;
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let option: Ident = input.fork().parse()?;
+            if option == "group" {
+                Self::parse_option(input, &option, &mut group)?;
+            } else if option == "async_with" {
+                Self::parse_option(input, &option, &mut async_with)?;
+            } else if option == "print_args" {
+                Self::parse_option(input, &option, &mut print_mode)?;
+            } else if option == "max_arg_width" {
+                Self::parse_option(input, &option, &mut max_arg_width)?;
+            } else if option == "names" {
+                Self::parse_option(input, &option, &mut names)?;
+            } else if option == "case_fields" {
+                Self::parse_option(input, &option, &mut case_fields)?;
+            } else if option == "export" {
+                Self::parse_flag(input, &option, &mut export)?;
+            } else if option == "exhaustive" {
+                Self::parse_option(input, &option, &mut exhaustive)?;
+            } else {
+                if tags.is_some() {
+                    return Err(SynError::new(option.span(), "duplicate `tags` option"));
+                }
+                tags = Some(input.parse()?);
+            }
         }
         Ok(Self {
             count,
+            _comma: comma,
+            expr,
+            tags,
+            group,
+            async_with,
+            print_mode,
+            max_arg_width,
+            names,
+            case_fields,
+            export,
+            exhaustive,
+        })
+    }
+}
+
+impl CaseAttrs {
+    fn parse(attr: proc_macro2::TokenStream) -> syn::Result<Self> {
+        let syntax: CaseAttrsSyntax = syn::parse2(attr)?;
+        Ok(Self {
+            count: CaseCount::new(syntax.count)?,
             expr: syntax.expr,
+            tags: syntax.tags.unwrap_or_default(),
+            group: syntax.group,
+            async_with: syntax.async_with,
+            print_args: PrintArgs {
+                mode: syntax.print_mode.unwrap_or_default(),
+                max_width: syntax
+                    .max_arg_width
+                    .map(|width| width.base10_parse())
+                    .transpose()?,
+            },
+            names: syntax.names.unwrap_or_default(),
+            case_fields: syntax.case_fields,
+            export: syntax.export,
+            exhaustive: syntax.exhaustive,
         })
     }
+
+    /// Creates attributes from `#[values(..)]` attributes on the tested function args.
+    /// The cases are the Cartesian product of the values for all args.
+    fn from_arg_values(function: &mut ItemFn) -> syn::Result<Self> {
+        let mut values_per_arg = Vec::with_capacity(function.sig.inputs.len());
+        for arg in &mut function.sig.inputs {
+            let attrs = match arg {
+                FnArg::Receiver(receiver) => &mut receiver.attrs,
+                FnArg::Typed(typed) => &mut typed.attrs,
+            };
+            if attrs.iter().any(ArgDefault::is_default_attr) {
+                continue;
+            }
+            let Some(idx) = attrs.iter().position(Self::is_values_attr) else {
+                let message = "arg must have a #[values(..)] attribute if #[test_casing] \
+                    has no args";
+                return Err(SynError::new_spanned(arg, message));
+            };
+            let attr = attrs.remove(idx);
+            let values = attr.parse_args_with(Punctuated::<Expr, Token![,]>::parse_terminated)?;
+            if values.is_empty() {
+                let message = "#[values(..)] must contain at least one value";
+                return Err(SynError::new_spanned(attr, message));
+            }
+            values_per_arg.push(values);
+        }
+        if values_per_arg.is_empty() {
+            let message = "tested function must have at least one arg";
+            return Err(SynError::new_spanned(&function.sig, message));
+        }
+
+        let count = values_per_arg.iter().map(Punctuated::len).product();
+        let arrays = values_per_arg.iter().map(|values| {
+            let values = values.iter();
+            quote!([#(#values,)*])
+        });
+        let expr = if values_per_arg.len() == 1 {
+            syn::parse_quote!(#(#arrays)*)
+        } else {
+            syn::parse_quote!(test_casing::Product((#(#arrays,)*)))
+        };
+        Ok(Self {
+            count: CaseCount::Literal(count),
+            expr,
+            tags: Tags::default(),
+            group: None,
+            async_with: None,
+            print_args: PrintArgs::default(),
+            names: CaseNames::default(),
+            case_fields: None,
+            export: false,
+            exhaustive: None,
+        })
+    }
+
+    /// Expands `inline` attribute args into the number of cases and the case expression
+    /// constructed from `#[case(..)]` attributes on the tested function. Returns `None`
+    /// if the attribute args don't start with `inline`.
+    fn expand_inline(
+        attr: &proc_macro2::TokenStream,
+        function: &mut ItemFn,
+    ) -> syn::Result<Option<proc_macro2::TokenStream>> {
+        let mut tokens = attr.clone().into_iter();
+        let is_inline = match (tokens.next(), tokens.clone().next()) {
+            (Some(TokenTree::Ident(ident)), None) => ident == "inline",
+            (Some(TokenTree::Ident(ident)), Some(TokenTree::Punct(punct))) => {
+                ident == "inline" && punct.as_char() == ','
+            }
+            _ => false,
+        };
+        if !is_inline {
+            return Ok(None);
+        }
+
+        let (case_attrs, other_attrs) = mem::take(&mut function.attrs)
+            .into_iter()
+            .partition::<Vec<_>, _>(Self::is_case_attr);
+        function.attrs = other_attrs;
+        if case_attrs.is_empty() {
+            let message = "#[test_casing(inline)] requires at least one #[case(..)] attribute \
+                placed after it";
+            return Err(SynError::new_spanned(attr, message));
+        }
+
+        let arg_count = function
+            .sig
+            .inputs
+            .iter()
+            .filter(|arg| {
+                let attrs = match arg {
+                    FnArg::Receiver(receiver) => &receiver.attrs,
+                    FnArg::Typed(typed) => &typed.attrs,
+                };
+                !attrs.iter().any(ArgDefault::is_default_attr)
+            })
+            .count();
+        let cases = case_attrs.iter().map(|attr| {
+            let values = attr.parse_args_with(Punctuated::<Expr, Token![,]>::parse_terminated)?;
+            if values.len() != arg_count {
+                let message = format!(
+                    "expected {arg_count} value(s) in #[case(..)], one per arg without #[default(..)]"
+                );
+                return Err(SynError::new_spanned(attr, message));
+            }
+            let values = values.iter();
+            Ok(if arg_count == 1 {
+                quote!(#(#values)*)
+            } else {
+                quote!((#(#values,)*))
+            })
+        });
+        let cases = cases.collect::<syn::Result<Vec<_>>>()?;
+        let case_count = cases.len();
+        let other_args: proc_macro2::TokenStream = tokens.collect();
+        Ok(Some(quote!(#case_count, [#(#cases,)*] #other_args)))
+    }
+
+    fn is_case_attr(attr: &Attribute) -> bool {
+        attr.path().is_ident("case")
+    }
+
+    /// Checks that the tested function has no `#[case(..)]` attributes outside the inline mode.
+    fn check_no_inline_cases(function: &ItemFn) -> syn::Result<()> {
+        if let Some(attr) = function.attrs.iter().find(|attr| Self::is_case_attr(attr)) {
+            let message = "#[case(..)] attributes can only be used with #[test_casing(inline)]";
+            return Err(SynError::new_spanned(attr, message));
+        }
+        Ok(())
+    }
+
+    fn is_values_attr(attr: &Attribute) -> bool {
+        attr.path().is_ident("values")
+    }
+
+    /// Checks that the tested function args don't have `#[values(..)]` attributes.
+    fn check_no_arg_values(function: &ItemFn) -> syn::Result<()> {
+        for arg in &function.sig.inputs {
+            let attrs = match arg {
+                FnArg::Receiver(receiver) => &receiver.attrs,
+                FnArg::Typed(typed) => &typed.attrs,
+            };
+            if let Some(attr) = attrs.iter().find(|attr| Self::is_values_attr(attr)) {
+                let message = "#[values(..)] attributes on args can only be used \
+                    with a single #[test_casing] attribute without args";
+                return Err(SynError::new_spanned(attr, message));
+            }
+        }
+        Ok(())
+    }
+
+    fn is_test_casing_attr(attr: &Attribute) -> bool {
+        attr.path().segments.last().unwrap().ident == "test_casing"
+    }
+
+    /// Extracts additional `test_casing` attributes on the tested function. These attributes
+    /// are not expanded yet since they are placed after the attribute being expanded.
+    fn take_from(attrs: &mut Vec<Attribute>) -> syn::Result<Vec<Self>> {
+        let (case_attrs, other_attrs) = mem::take(attrs)
+            .into_iter()
+            .partition::<Vec<_>, _>(Self::is_test_casing_attr);
+        *attrs = other_attrs;
+        case_attrs
+            .iter()
+            .map(|attr| Self::parse(attr.meta.require_list()?.tokens.clone()))
+            .collect()
+    }
+
+    /// Checks that case groups are named uniquely if there are multiple groups.
+    fn check_groups(groups: &[Self]) -> syn::Result<()> {
+        if groups.len() < 2 {
+            return Ok(());
+        }
+        let mut names = Vec::with_capacity(groups.len());
+        for group in groups {
+            let Some(name) = &group.group else {
+                let message = "multiple #[test_casing] attributes on a function must each \
+                    specify a unique group name, e.g. `group = basic`";
+                return Err(SynError::new_spanned(&group.expr, message));
+            };
+            if names.contains(&name) {
+                return Err(SynError::new(name.span(), "duplicate group name"));
+            }
+            names.push(name);
+        }
+        Ok(())
+    }
 }
 
+#[derive(Clone)]
 struct MapAttrs {
     path: Option<Path>,
 }
@@ -120,6 +563,168 @@ impl Parse for MapAttrs {
     }
 }
 
+/// Format of a case arg specified via the `#[fmt(..)]` attribute.
+#[derive(Clone)]
+enum ArgFormat {
+    /// Arg is printed as `***`.
+    Redact,
+    /// Arg is not printed at all.
+    Skip,
+    /// Arg is printed as `<opaque>`; used for args not implementing `Debug`.
+    Opaque,
+    /// Arg is printed as a hex string of its bytes.
+    Hex,
+    /// Arg is printed using its `Display` implementation.
+    Display,
+    /// Arg is printed using the specified function.
+    With(Path),
+}
+
+impl fmt::Debug for ArgFormat {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Redact => formatter.write_str("Redact"),
+            Self::Skip => formatter.write_str("Skip"),
+            Self::Opaque => formatter.write_str("Opaque"),
+            Self::Hex => formatter.write_str("Hex"),
+            Self::Display => formatter.write_str("Display"),
+            Self::With(_) => formatter.debug_tuple("With").field(&"_").finish(),
+        }
+    }
+}
+
+impl ArgFormat {
+    /// Returns the printed value for an arg, or `None` if the arg should not be printed.
+    fn print_arg(format: Option<&Self>, arg: &Ident) -> Option<proc_macro2::TokenStream> {
+        let cr = quote!(test_casing);
+        let printed = match format {
+            None => quote!(#arg),
+            Some(Self::Redact) => quote!(&#cr::Redacted),
+            Some(Self::Skip) => return None,
+            Some(Self::Opaque) => quote!(&#cr::Opaque),
+            Some(Self::Hex) => quote!(&#cr::FormatHex(#arg)),
+            Some(Self::Display) => quote!(&#cr::FormatDisplay(#arg)),
+            Some(Self::With(path)) => quote!(&#cr::FormatWith(#arg, #path)),
+        };
+        Some(quote!(#printed as &dyn ::core::fmt::Debug))
+    }
+}
+
+impl Parse for ArgFormat {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let format: Ident = input.parse()?;
+        if format == "redact" {
+            Ok(Self::Redact)
+        } else if format == "skip" {
+            Ok(Self::Skip)
+        } else if format == "opaque" {
+            Ok(Self::Opaque)
+        } else if format == "hex" {
+            Ok(Self::Hex)
+        } else if format == "display" {
+            Ok(Self::Display)
+        } else if format == "with" {
+            input.parse::<Token![=]>()?;
+            Ok(Self::With(input.parse()?))
+        } else {
+            let message = "unknown arg format; supported formats are `redact`, `skip`, `opaque`, \
+                `hex`, `display` and `with = path::to::function`";
+            Err(SynError::new(format.span(), message))
+        }
+    }
+}
+
+/// Attributes of an arg with expected output loaded from a file (`#[expected(file = "..")]`).
+#[derive(Clone)]
+struct ExpectedAttrs {
+    file: LitStr,
+}
+
+impl ExpectedAttrs {
+    const PLACEHOLDERS: [&'static str; 3] = ["fn", "case", "index"];
+
+    fn check_placeholders(file: &LitStr) -> syn::Result<()> {
+        let template = file.value();
+        let mut rest = template.as_str();
+        while let Some(start) = rest.find('{') {
+            let Some(len) = rest[start..].find('}') else {
+                let message = "unclosed placeholder in the file template";
+                return Err(SynError::new(file.span(), message));
+            };
+            let placeholder = &rest[start + 1..start + len];
+            if !Self::PLACEHOLDERS.contains(&placeholder) {
+                let message = format!(
+                    "unknown placeholder `{{{placeholder}}}` in the file template; \
+                     expected one of `{{fn}}`, `{{case}}` or `{{index}}`"
+                );
+                return Err(SynError::new(file.span(), message));
+            }
+            rest = &rest[start + len + 1..];
+        }
+        Ok(())
+    }
+}
+
+impl Parse for ExpectedAttrs {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let name: Ident = input.parse()?;
+        if name != "file" {
+            let message = "expected `file = \"path/to/{case}.txt\"`";
+            return Err(SynError::new(name.span(), message));
+        }
+        input.parse::<Token![=]>()?;
+        let file: LitStr = input.parse()?;
+        Self::check_placeholders(&file)?;
+        Ok(Self { file })
+    }
+}
+
+/// Value of a trailing arg not provided by the cases.
+#[derive(Clone)]
+enum ArgDefault {
+    /// Value specified via `#[default(..)]`.
+    Expr(Box<Expr>),
+    /// Expected output loaded from a file specified via `#[expected(..)]`.
+    Expected(ExpectedAttrs),
+}
+
+impl ArgDefault {
+    const ATTR_NAMES: [&'static str; 2] = ["default", "expected"];
+
+    /// Checks whether the attribute specifies a default value for the arg.
+    fn is_default_attr(attr: &Attribute) -> bool {
+        Self::ATTR_NAMES
+            .iter()
+            .any(|&name| attr.path().is_ident(name))
+    }
+
+    fn take_from(attrs: &mut Vec<Attribute>) -> syn::Result<Option<Self>> {
+        let default = FunctionWrapper::take_arg_attr::<Expr>(attrs, "default")?;
+        let expected = FunctionWrapper::take_arg_attr::<ExpectedAttrs>(attrs, "expected")?;
+        Ok(match (default, expected) {
+            (Some(default), None) => Some(Self::Expr(Box::new(default))),
+            (None, Some(expected)) => Some(Self::Expected(expected)),
+            (None, None) => None,
+            (Some(default), Some(_)) => {
+                let message = "an arg cannot have both #[default(..)] and #[expected(..)]";
+                return Err(SynError::new_spanned(default, message));
+            }
+        })
+    }
+
+    fn value(&self, fn_name: &Ident, index: &impl ToTokens) -> proc_macro2::TokenStream {
+        match self {
+            Self::Expr(expr) => quote!(#expr),
+            Self::Expected(ExpectedAttrs { file }) => {
+                let cr = quote!(test_casing);
+                let fn_name = fn_name.to_string();
+                quote!(#cr::Expected::load(#file, #fn_name, #index))
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
 struct FunctionWrapper {
     #[cfg(feature = "nightly")]
     nightly: NightlyData,
@@ -128,6 +733,14 @@ struct FunctionWrapper {
     fn_attrs: Vec<Attribute>,
     fn_sig: Signature,
     arg_mappings: Vec<Option<MapAttrs>>,
+    arg_formats: Vec<Option<ArgFormat>>,
+    /// Default values for trailing args not provided by the cases.
+    arg_defaults: Vec<ArgDefault>,
+    /// Args provided by the property test attribute (e.g., `#[proptest]`) rather than by cases.
+    /// The args retain their attributes (e.g., strategies), which are forwarded
+    /// to the generated case functions.
+    property_args: Vec<PatType>,
+    ignore_if: Option<IgnoreIf>,
 }
 
 impl fmt::Debug for FunctionWrapper {
@@ -142,57 +755,100 @@ impl fmt::Debug for FunctionWrapper {
 }
 
 impl FunctionWrapper {
-    const MAX_ARGS: usize = 7;
+    const MAX_ARGS: usize = 12;
 
     fn new(attrs: CaseAttrs, function: &mut ItemFn) -> syn::Result<Self> {
-        if function.sig.inputs.is_empty() {
-            let message = "tested function must have at least one arg";
-            return Err(SynError::new_spanned(&function.sig, message));
-        } else if function.sig.inputs.len() > Self::MAX_ARGS {
-            let message = format!(
-                "tested function must have no more than {} args",
-                Self::MAX_ARGS
-            );
-            return Err(SynError::new_spanned(&function.sig, message));
-        }
+        Self::check_signature(&function.sig)?;
 
-        let generic_params = &function.sig.generics.params;
-        if !generic_params.is_empty() {
-            let message = "generic tested functions are not supported";
-            return Err(SynError::new_spanned(generic_params, message));
-        }
+        let mut mappings = Vec::with_capacity(function.sig.inputs.len());
+        let mut formats = Vec::with_capacity(function.sig.inputs.len());
+        let mut defaults = vec![];
+        let mut property_args = vec![];
+        for arg in &mut function.sig.inputs {
+            if let Some(property_arg) = Self::take_property_arg(arg)? {
+                if !defaults.is_empty() {
+                    let message = "args with #[default(..)] or #[expected(..)] must be placed \
+                        after all other args";
+                    return Err(SynError::new_spanned(arg, message));
+                }
+                property_args.push(property_arg);
+                continue;
+            }
 
-        let mappings = function.sig.inputs.iter_mut().map(|arg| {
             let attrs = match arg {
                 FnArg::Receiver(receiver) => &mut receiver.attrs,
                 FnArg::Typed(typed) => &mut typed.attrs,
             };
-            let map_attr = attrs
-                .iter()
-                .enumerate()
-                .find(|(_, attr)| attr.path().is_ident("map"));
-            let Some((idx, map_attr)) = map_attr else {
-                return Ok(None);
-            };
-            let map_attr = map_attr.parse_args::<MapAttrs>()?;
-            attrs.remove(idx);
-            Ok(Some(map_attr))
-        });
-        let mappings: syn::Result<Vec<_>> = mappings.collect();
-        let mappings = mappings?;
+            let mapping = Self::take_arg_attr::<MapAttrs>(attrs, "map")?;
+            let format = Self::take_arg_attr::<ArgFormat>(attrs, "fmt")?;
+            let default = ArgDefault::take_from(attrs)?;
+            if let Some(default) = default {
+                if mapping.is_some() || format.is_some() {
+                    let message = "args with #[default(..)] or #[expected(..)] are not provided \
+                        by cases, so they cannot have #[map(..)] or #[fmt(..)] attributes";
+                    return Err(SynError::new_spanned(arg, message));
+                }
+                defaults.push(default);
+            } else if !defaults.is_empty() {
+                let message = "args with #[default(..)] or #[expected(..)] must be placed \
+                    after all other args";
+                return Err(SynError::new_spanned(arg, message));
+            } else if let Some(property_arg) = property_args.first() {
+                let message = "args with #[property] must be placed after all args \
+                    provided by cases";
+                return Err(SynError::new_spanned(property_arg, message));
+            } else {
+                mappings.push(mapping);
+                formats.push(format);
+            }
+        }
+        if mappings.is_empty() {
+            let message = "tested function must have at least one arg without #[default(..)] \
+                or #[expected(..)]";
+            return Err(SynError::new_spanned(&function.sig, message));
+        }
 
+        let ignore_if = IgnoreIf::take_from(&mut function.attrs)?;
         let (retained_attrs, mut fn_attrs) = mem::take(&mut function.attrs)
             .into_iter()
             .partition(Self::should_be_retained);
         function.attrs = retained_attrs;
+        let mut test_attrs = fn_attrs
+            .iter()
+            .filter(|attr| attr.path().segments.last().unwrap().ident == "test");
+        if let (Some(_), Some(duplicate_attr)) = (test_attrs.next(), test_attrs.next()) {
+            let message = "duplicate test attribute; a test function must have at most one \
+                test attribute (e.g., #[test] or #[tokio::test]) placed after #[test_casing]";
+            return Err(SynError::new_spanned(duplicate_attr, message));
+        }
+        if matches!(attrs.count, CaseCount::Expr(_)) {
+            let should_panic = fn_attrs
+                .iter()
+                .find(|attr| attr.path().is_ident("should_panic"));
+            if let Some(should_panic) = should_panic {
+                let message = "#[should_panic] is not supported if the number of cases \
+                    is not a literal: all cases are tested in a single test, which would pass \
+                    if any case panics";
+                return Err(SynError::new_spanned(should_panic, message));
+            }
+        }
+        let has_property_attr =
+            Self::check_property_attr(&fn_attrs, &property_args, &function.sig)?;
+
         let test_attr_position = fn_attrs
             .iter()
             .position(|attr| attr.path().is_ident("test"));
         if cfg!(feature = "nightly") {
+            // The attribute is added back for the tests not using custom test cases
+            // (e.g., in `Self::all_cases_fn()`).
             if let Some(position) = test_attr_position {
                 fn_attrs.remove(position);
             }
-        } else if test_attr_position.is_none() && function.sig.asyncness.is_none() {
+        } else if test_attr_position.is_none()
+            && function.sig.asyncness.is_none()
+            && !has_property_attr
+        {
+            // Property test attributes generate a test attribute themselves.
             let test_attr = syn::parse_quote!(#[::core::prelude::v1::test]);
             fn_attrs.insert(0, test_attr);
         }
@@ -205,9 +861,131 @@ impl FunctionWrapper {
             fn_attrs,
             fn_sig: function.sig.clone(),
             arg_mappings: mappings,
+            arg_formats: formats,
+            arg_defaults: defaults,
+            property_args,
+            ignore_if,
         })
     }
 
+    fn check_signature(sig: &Signature) -> syn::Result<()> {
+        if sig.inputs.is_empty() {
+            let message = "tested function must have at least one arg";
+            return Err(SynError::new_spanned(sig, message));
+        } else if sig.inputs.len() > Self::MAX_ARGS {
+            let message = format!(
+                "tested function must have no more than {} args",
+                Self::MAX_ARGS
+            );
+            return Err(SynError::new_spanned(sig, message));
+        }
+
+        let generic_params = &sig.generics.params;
+        if !generic_params.is_empty() {
+            let message = "generic tested functions are not supported";
+            return Err(SynError::new_spanned(generic_params, message));
+        }
+        Ok(())
+    }
+
+    /// Checks that property args are accompanied by a property test attribute, and that
+    /// this attribute is applicable. Returns whether the attribute is present.
+    fn check_property_attr(
+        fn_attrs: &[Attribute],
+        property_args: &[PatType],
+        sig: &Signature,
+    ) -> syn::Result<bool> {
+        let has_property_attr = fn_attrs.iter().any(Self::is_property_attr);
+        if let Some(property_arg) = property_args.first() {
+            if !has_property_attr {
+                let message = "args with #[property] require a property test attribute \
+                    (#[proptest] or #[quickcheck]) placed after #[test_casing]";
+                return Err(SynError::new_spanned(property_arg, message));
+            }
+        }
+        if has_property_attr {
+            if let Some(asyncness) = &sig.asyncness {
+                let message = "property test attributes (#[proptest] or #[quickcheck]) \
+                    are not supported for async functions";
+                return Err(SynError::new(asyncness.span(), message));
+            }
+        }
+        Ok(has_property_attr)
+    }
+
+    /// Checks whether the attribute is a property test attribute, such as `#[proptest]`
+    /// from the `test-strategy` crate or `#[quickcheck]` from the `quickcheck_macros` crate.
+    fn is_property_attr(attr: &Attribute) -> bool {
+        let last_segment = &attr.path().segments.last().unwrap().ident;
+        last_segment == "proptest" || last_segment == "quickcheck"
+    }
+
+    /// Takes an arg marked with the `#[property]` attribute. All attributes are removed from
+    /// the tested function arg and are retained in the returned arg, so that they can be
+    /// forwarded to the generated case functions.
+    fn take_property_arg(arg: &mut FnArg) -> syn::Result<Option<PatType>> {
+        let attrs = match arg {
+            FnArg::Receiver(receiver) => &receiver.attrs,
+            FnArg::Typed(typed) => &typed.attrs,
+        };
+        let Some(idx) = attrs
+            .iter()
+            .position(|attr| attr.path().is_ident("property"))
+        else {
+            return Ok(None);
+        };
+        attrs[idx].meta.require_path_only()?;
+
+        let FnArg::Typed(typed) = arg else {
+            let message = "#[property] cannot be placed on the receiver";
+            return Err(SynError::new_spanned(arg, message));
+        };
+        let Pat::Ident(pat) = typed.pat.as_ref() else {
+            let message = "args with #[property] must be simple identifiers";
+            return Err(SynError::new_spanned(&typed.pat, message));
+        };
+        let own_attrs = ["map", "fmt", "default", "expected", "values"];
+        if let Some(attr) = typed
+            .attrs
+            .iter()
+            .find(|attr| own_attrs.iter().any(|name| attr.path().is_ident(name)))
+        {
+            let message = "args with #[property] are not provided by cases, so they cannot \
+                have #[map(..)], #[fmt(..)], #[default(..)], #[expected(..)] or #[values(..)] \
+                attributes";
+            return Err(SynError::new_spanned(attr, message));
+        }
+
+        let mut attrs = mem::take(&mut typed.attrs);
+        attrs.remove(idx);
+        // Mutability is only relevant for the tested function.
+        let mut pat = pat.clone();
+        pat.mutability = None;
+        Ok(Some(PatType {
+            attrs,
+            pat: Box::new(Pat::Ident(pat)),
+            ..typed.clone()
+        }))
+    }
+
+    /// Returns idents of the args provided by the property test attribute.
+    fn property_arg_idents(&self) -> impl Iterator<Item = &Ident> + '_ {
+        self.property_args.iter().map(|arg| match arg.pat.as_ref() {
+            Pat::Ident(pat) => &pat.ident,
+            _ => unreachable!("checked when creating the wrapper"),
+        })
+    }
+
+    /// Removes an attribute with the specified name from arg attributes and parses its args.
+    fn take_arg_attr<T: Parse>(attrs: &mut Vec<Attribute>, name: &str) -> syn::Result<Option<T>> {
+        let Some(idx) = attrs.iter().position(|attr| attr.path().is_ident(name)) else {
+            return Ok(None);
+        };
+        let parsed = attrs[idx].parse_args::<T>()?;
+        attrs.remove(idx);
+        Ok(Some(parsed))
+    }
+
     // FIXME: this is extremely hacky. Ideally, we'd want to partition attrs by their location
     //   before / after `#[test_casing]`, but this seems impossible on stable Rust (span locations
     //   are unstable).
@@ -218,33 +996,175 @@ impl FunctionWrapper {
             || attr.path().is_ident("forbid")
     }
 
+    /// Returns the number of args provided by cases, i.e., args without defaults
+    /// and not provided by the property test attribute.
+    fn case_arg_count(&self) -> usize {
+        self.fn_sig.inputs.len() - self.arg_defaults.len() - self.property_args.len()
+    }
+
     fn arg_names(&self) -> impl ToTokens {
-        let arg_count = self.fn_sig.inputs.len();
-        let arg_names = self
-            .fn_sig
-            .inputs
-            .iter()
-            .enumerate()
-            .map(|(i, arg)| match arg {
-                FnArg::Receiver(_) => String::from("self"),
-                FnArg::Typed(PatType { pat, .. }) => {
-                    if let Pat::Ident(ident) = pat.as_ref() {
-                        ident.ident.to_string()
-                    } else {
-                        format!("(arg {i})")
-                    }
-                }
-            });
+        let arg_count = self.case_arg_count();
+        let arg_names = self.arg_name_strings();
         quote! {
             const __ARG_NAMES: [&'static str; #arg_count] = [#(#arg_names,)*];
         }
     }
 
-    fn test_cases_iter(&self) -> impl ToTokens {
+    fn arg_name_strings(&self) -> Vec<String> {
+        let args = self.fn_sig.inputs.iter().take(self.case_arg_count());
+        let args = args.enumerate();
+        args.map(|(i, arg)| match arg {
+            FnArg::Receiver(_) => String::from("self"),
+            FnArg::Typed(PatType { pat, .. }) => {
+                if let Pat::Ident(ident) = pat.as_ref() {
+                    ident.ident.to_string()
+                } else {
+                    format!("(arg {i})")
+                }
+            }
+        })
+        .collect()
+    }
+
+    /// Generates an expression printing args of a case provided as a reference.
+    fn print_args(
+        &self,
+        case_ref: &impl ToTokens,
+        print_args: PrintArgs,
+    ) -> proc_macro2::TokenStream {
+        let cr = quote!(test_casing);
+        if self.arg_formats.iter().all(Option::is_none) {
+            // Struct cases are converted to tuples of field references.
+            let (maybe_destructuring, case_ref) = if self.attrs.case_fields.is_some() {
+                let args = self.case_arg_idents();
+                let pattern = self.case_pattern(args.iter().map(|arg| quote!(#arg)));
+                let args_ref = if args.len() == 1 {
+                    quote!(#(#args)*)
+                } else {
+                    quote!(&(#(#args,)*))
+                };
+                (Some(quote!(let #pattern = #case_ref;)), args_ref)
+            } else {
+                (None, quote!(#case_ref))
+            };
+            let print_call = if print_args.is_default() {
+                quote!(#cr::ArgNames::print_with_args(__ARG_NAMES, #case_ref))
+            } else {
+                let options = print_args.options();
+                quote!(#cr::ArgNames::print_with_options(__ARG_NAMES, #case_ref, #options))
+            };
+            return if let Some(destructuring) = maybe_destructuring {
+                quote!({ #destructuring #print_call })
+            } else {
+                print_call
+            };
+        }
+
+        let printed_args: Vec<_> = self
+            .case_arg_idents()
+            .into_iter()
+            .zip(self.arg_name_strings())
+            .zip(&self.arg_formats)
+            .map(|((arg, name), format)| {
+                let printed_arg = ArgFormat::print_arg(format.as_ref(), &arg)?;
+                Some((arg, quote!((#name, #printed_arg))))
+            })
+            .collect();
+
+        let bindings = printed_args.iter().map(|printed_arg| {
+            printed_arg
+                .as_ref()
+                .map_or_else(|| quote!(_), |(arg, _)| quote!(#arg))
+        });
+        let binding = self.case_pattern(bindings);
+        let printed_args = printed_args.iter().flatten().map(|(_, printed)| printed);
+        let options = print_args.options();
+        quote! {{
+            let #binding = #case_ref;
+            #options.print(&[#(#printed_args,)*])
+        }}
+    }
+
+    /// Generates a statement printing the case with the specified index.
+    fn print_case(&self, index: &impl ToTokens) -> proc_macro2::TokenStream {
+        let cr = quote!(test_casing);
+        let print_args = self.attrs.print_args;
+        let printed_args = self.print_args(&quote!(&__case), print_args);
+        let message = match print_args.mode {
+            PrintMode::Compact => quote! {
+                format!("Testing case #{}: {}", #index, #printed_args)
+            },
+            PrintMode::Pretty => quote! {
+                format!("Testing case #{}:\n{}", #index, #printed_args)
+            },
+            PrintMode::Json => quote! {
+                format!(
+                    "{{\"test\":\"{}\",\"case\":{},\"args\":{}}}",
+                    ::core::module_path!(),
+                    #index,
+                    #printed_args
+                )
+            },
+        };
+        quote! {
+            #cr::print_case(|| #message);
+        }
+    }
+
+    /// Returns the rendered cases expression used in diagnostics.
+    fn cases_source(&self) -> String {
+        self.attrs.expr.to_token_stream().to_string()
+    }
+
+    /// Generates an expression obtaining the case with the specified index.
+    fn get_case(
+        &self,
+        index: &impl ToTokens,
+        declared_count: &impl ToTokens,
+    ) -> proc_macro2::TokenStream {
+        let cr = quote!(test_casing);
+        let cases_expr = &self.attrs.expr;
+        let source = self.cases_source();
+        quote!(#cr::case(#cases_expr, #index, #declared_count, #source))
+    }
+
+    /// Generates an expression taking the case with the specified index from the shared
+    /// `__CASES` cache, so that the cases iterator is only advanced once for all cases.
+    fn take_case(&self, index: &impl ToTokens) -> proc_macro2::TokenStream {
+        let cases_expr = &self.attrs.expr;
+        let count = &self.attrs.count;
+        let source = self.cases_source();
+        quote!(__CASES.take(|| #cases_expr, #index, #count, #source))
+    }
+
+    /// Generates a statement creating the `__cases` iterator, and an expression obtaining
+    /// the next case from it. Used in tests iterating over all cases.
+    fn iterate_cases(
+        &self,
+        declared_count: &impl ToTokens,
+    ) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
         let cr = quote!(test_casing);
-        let name = &self.name;
         let cases_expr = &self.attrs.expr;
-        let (case_binding, case_args) = self.case_binding();
+        let source = self.cases_source();
+        let init = quote! {
+            let mut __cases = ::core::iter::IntoIterator::into_iter(#cases_expr);
+        };
+        let next = quote!(#cr::next_case(&mut __cases, __index, #declared_count, #source));
+        (init, next)
+    }
+
+    /// Generates statements binding `__case` to args and calling the tested function
+    /// without awaiting or otherwise using its output. Used to check types.
+    fn call_with_case(&self) -> proc_macro2::TokenStream {
+        let name = &self.name;
+        // The case index is irrelevant since the call is only used to check types.
+        // Likewise, property args are never evaluated.
+        let property_args: Vec<_> = self
+            .property_args
+            .iter()
+            .map(|_| quote!((|| ::core::unreachable!())()))
+            .collect();
+        let (case_binding, case_args) = self.case_binding(&0_usize, &property_args);
         let maybe_output_binding = match (&self.fn_sig.asyncness, &self.fn_sig.output) {
             (None, ReturnType::Default) => None,
             _ => Some(quote!(let _ = )),
@@ -252,36 +1172,397 @@ impl FunctionWrapper {
         // ^ Using `let _ = ` on the `()` return type triggers https://rust-lang.github.io/rust-clippy/master/index.html#/ignored_unit_patterns
         // in Rust 1.73+.
 
+        quote! {
+            let #case_binding = __case;
+            #maybe_output_binding #name(#case_args);
+        }
+    }
+
+    fn test_cases_iter(&self) -> impl ToTokens {
+        let get_case = self.get_case(&quote!(0), &self.attrs.count);
+        let call_with_case = self.call_with_case();
+
         quote! {
             const _: () = {
                 #[allow(dead_code, clippy::no_effect_underscore_binding)]
                 fn __test_cases_iterator() {
-                    let #case_binding = #cr::case(#cases_expr, 0);
-                    #maybe_output_binding #name(#case_args);
+                    let __case = #get_case;
+                    #call_with_case
                 }
             };
         }
     }
 
-    fn wrap(&self) -> impl ToTokens {
-        let name = &self.name;
-        let test_cases_iter = self.test_cases_iter();
-        let arg_names = self.arg_names();
-        let index_width = (self.attrs.count - 1).to_string().len();
-        let cases = (0..self.attrs.count).map(|i| self.case(i, index_width));
+    /// Returns the test attribute specified via the `async_with` option, provided that
+    /// the tested function is async and doesn't have a test attribute already.
+    fn async_test_attr(&self) -> Option<proc_macro2::TokenStream> {
+        let async_with = self.attrs.async_with.as_ref()?;
+        self.fn_sig.asyncness?;
+        let has_test_attr = self
+            .fn_attrs
+            .iter()
+            .any(|attr| attr.path().segments.last().unwrap().ident == "test");
+        (!has_test_attr).then(|| quote!(#[#async_with]))
+    }
+
+    /// Checks that an async tested function will get an async test attribute. Without it,
+    /// generated test cases would be silently ignored, or fail with confusing errors.
+    fn check_async_test_attr(&self) -> syn::Result<()> {
+        let Some(asyncness) = &self.fn_sig.asyncness else {
+            return Ok(());
+        };
+        if self.attrs.async_with.is_some() {
+            return Ok(());
+        }
+        // Use a loose check to support attributes like `#[wasm_bindgen_test]`.
+        let has_test_attr = self.fn_attrs.iter().any(|attr| {
+            let last_segment = &attr.path().segments.last().unwrap().ident;
+            last_segment.to_string().contains("test")
+        });
+        if has_test_attr {
+            Ok(())
+        } else {
+            let message = "async test function is missing an async test attribute. Place \
+                an attribute like #[tokio::test] or #[async_std::test] *after* #[test_casing], \
+                or specify it via the `async_with` argument (e.g., `async_with = tokio::test`)";
+            Err(SynError::new(asyncness.span(), message))
+        }
+    }
+
+    fn with_attrs(&self, attrs: CaseAttrs) -> Self {
+        Self {
+            attrs,
+            ..self.clone()
+        }
+    }
+
+    /// Wraps the tested function for one or more groups of cases.
+    fn wrap(wrappers: &[Self]) -> proc_macro2::TokenStream {
+        let name = &wrappers[0].name;
+        let test_cases_iters = wrappers.iter().map(Self::test_cases_iter);
+        let contents = wrappers.iter().map(|wrapper| {
+            if let Some(group) = &wrapper.attrs.group {
+                let contents = wrapper.module_contents(&quote!(super::super));
+                quote! {
+                    mod #group {
+                        #contents
+                    }
+                }
+            } else {
+                wrapper.module_contents(&quote!(super))
+            }
+        });
+
+        let exports = Self::exports(wrappers);
+        let (maybe_vis, maybe_non_test_module) = if let Some(exports) = &exports {
+            let non_test_module = quote! {
+                #[cfg(not(test))]
+                pub(crate) mod #name {
+                    #exports
+                }
+            };
+            (Some(quote!(pub(crate))), Some(non_test_module))
+        } else {
+            (None, None)
+        };
 
         quote! {
             // Access the iterator to ensure it works even if not building for tests.
-            #test_cases_iter
+            #(#test_cases_iters)*
 
             #[cfg(test)]
             #[allow(clippy::no_effect_underscore_binding)]
             // ^ We use `__ident`s to not alias user-defined idents accidentally. Unfortunately,
             // this triggers this lint on Rust 1.76+.
-            mod #name {
-                use super::*;
-                #arg_names
-                #(#cases)*
+            #maybe_vis mod #name {
+                #(#contents)*
+                #exports
+            }
+            #maybe_non_test_module
+        }
+    }
+
+    /// Generates items running cases for groups with the `export` option, which are available
+    /// both in test and non-test builds. Returns `None` if no groups are exported.
+    fn exports(wrappers: &[Self]) -> Option<proc_macro2::TokenStream> {
+        let exported: Vec<_> = wrappers
+            .iter()
+            .filter(|wrapper| wrapper.attrs.export)
+            .collect();
+        if exported.is_empty() {
+            return None;
+        }
+
+        let items = exported.iter().map(|wrapper| wrapper.exported_items());
+        let names = exported.iter().flat_map(|wrapper| {
+            let (count_name, fn_name) = wrapper.exported_names();
+            [count_name, fn_name]
+        });
+        Some(quote! {
+            #[allow(dead_code, clippy::no_effect_underscore_binding)]
+            mod __exports {
+                use super::super::*;
+                #(#items)*
+            }
+            pub(crate) use self::__exports::{#(#names,)*};
+        })
+    }
+
+    /// Returns names of the exported constant with the number of cases and of the function
+    /// running a case. Names for a group are suffixed with the group name.
+    fn exported_names(&self) -> (Ident, Ident) {
+        let span = self.name.span();
+        match &self.attrs.group {
+            Some(group) => {
+                let group = group.to_string();
+                let count_name = format!("CASE_COUNT_{}", group.to_uppercase());
+                (
+                    Ident::new(&count_name, span),
+                    Ident::new(&format!("run_case_{group}"), span),
+                )
+            }
+            None => (Ident::new("CASE_COUNT", span), Ident::new("run_case", span)),
+        }
+    }
+
+    /// Generates a constant with the number of cases and a function running a case
+    /// with the specified index, which can be used outside of tests (e.g., in benchmarks).
+    fn exported_items(&self) -> proc_macro2::TokenStream {
+        let name = &self.name;
+        let (count_name, fn_name) = self.exported_names();
+        let count = &self.attrs.count;
+        let get_case = self.get_case(&quote!(__index), &count_name);
+        let property_args: Vec<_> = self.property_arg_idents().map(|arg| quote!(#arg)).collect();
+        let (case_binding, case_args) = self.case_binding(&quote!(__index), &property_args);
+        // Attributes of property args (e.g., strategies) are not applicable outside property tests.
+        let fn_args = self.property_args.iter().map(|arg| PatType {
+            attrs: vec![],
+            ..arg.clone()
+        });
+        let maybe_async = &self.fn_sig.asyncness;
+        let maybe_await = maybe_async.as_ref().map(|_| quote!(.await));
+        let maybe_track_caller = maybe_async.is_none().then(|| quote!(#[track_caller]));
+        let ret = &self.fn_sig.output;
+        let count_doc = format!("Number of test cases for [`{name}()`](super::{name}).");
+        let fn_doc = format!(
+            "Runs [`{name}()`](super::{name}) with the test case with the specified zero-based index."
+        );
+
+        quote! {
+            #[doc = #count_doc]
+            pub(crate) const #count_name: usize = #count;
+
+            #[doc = #fn_doc]
+            #maybe_track_caller
+            pub(crate) #maybe_async fn #fn_name(__index: usize, #(#fn_args),*) #ret {
+                let #case_binding = #get_case;
+                #name(#case_args) #maybe_await
+            }
+        }
+    }
+
+    /// Generates contents of the module with test cases. `parent_module` is the path
+    /// to the module containing the tested function.
+    fn module_contents(
+        &self,
+        parent_module: &proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let cr = quote!(test_casing);
+        let arg_names = self.arg_names();
+        match &self.attrs.count {
+            CaseCount::Literal(0) => self.marker_test(),
+            CaseCount::Literal(count) => {
+                let index_width = (count - 1).to_string().len();
+                let literal_cases = self.literal_cases(*count);
+                let cases = (0..*count).map(|i| {
+                    let literal_case = literal_cases.as_ref().map(|cases| &cases[i]);
+                    self.case(i, index_width, literal_case)
+                });
+                let validate_fn = self.validate_cases_fn(&self.attrs.count);
+                let coverage_fn = self.check_coverage_fn(&self.attrs.count);
+                quote! {
+                    use #parent_module::*;
+                    #arg_names
+                    static __CASES: #cr::CaseCache = #cr::CaseCache::new();
+                    #(#cases)*
+                    #validate_fn
+                    #coverage_fn
+                }
+            }
+            CaseCount::Expr(count_expr) => {
+                let all_cases_fn = self.all_cases_fn();
+                let validate_fn = self.validate_cases_fn(&quote!(__CASE_COUNT));
+                let coverage_fn = self.check_coverage_fn(&quote!(__CASE_COUNT));
+                quote! {
+                    use #parent_module::*;
+                    #arg_names
+                    const __CASE_COUNT: usize = #count_expr;
+                    #all_cases_fn
+                    #validate_fn
+                    #coverage_fn
+                }
+            }
+        }
+    }
+
+    /// Generates a marker test for an empty set of cases (e.g., if the cases are platform-specific).
+    fn marker_test(&self) -> proc_macro2::TokenStream {
+        let maybe_allow_non_snake_case = self.attrs.tags.allow_attr();
+        let tags_suffix = self.attrs.tags.name_suffix();
+        let fn_name = Ident::new(&format!("no_cases{tags_suffix}"), self.name.span());
+
+        quote! {
+            #[::core::prelude::v1::test]
+            #[ignore = "the set of test cases is empty"]
+            #maybe_allow_non_snake_case
+            fn #fn_name() {
+                // Does nothing
+            }
+        }
+    }
+
+    /// Generates an ignored test that constructs all cases and prints their args
+    /// without running the tested function.
+    fn validate_cases_fn(&self, declared_count: &impl ToTokens) -> proc_macro2::TokenStream {
+        let maybe_allow_non_snake_case = self.attrs.tags.allow_attr();
+        let tags_suffix = self.attrs.tags.name_suffix();
+        let fn_name = Ident::new(&format!("validate_cases{tags_suffix}"), self.name.span());
+        let cr = quote!(test_casing);
+        let (init_cases, next_case) = self.iterate_cases(declared_count);
+        let printed_args = self.print_args(&quote!(&__case), self.attrs.print_args);
+
+        quote! {
+            #[::core::prelude::v1::test]
+            #[ignore = "validates test cases without running the test"]
+            #maybe_allow_non_snake_case
+            fn #fn_name() {
+                #init_cases
+                for __index in 0..#declared_count {
+                    let __case = #next_case;
+                    let __printed_args: ::std::string::String = #printed_args;
+                    #cr::print_case(|| format!("Case #{} is valid: {}", __index, __printed_args));
+                }
+            }
+        }
+    }
+
+    /// Generates a test checking that the cases cover the domain specified via the `exhaustive`
+    /// option. Returns `None` if the option is not specified.
+    fn check_coverage_fn(
+        &self,
+        declared_count: &impl ToTokens,
+    ) -> Option<proc_macro2::TokenStream> {
+        let domain = self.attrs.exhaustive.as_ref()?;
+        let maybe_allow_non_snake_case = self.attrs.tags.allow_attr();
+        let tags_suffix = self.attrs.tags.name_suffix();
+        let fn_name = Ident::new(&format!("check_coverage{tags_suffix}"), self.name.span());
+        let cr = quote!(test_casing);
+        let cases_expr = &self.attrs.expr;
+        let source = self.cases_source();
+
+        Some(quote! {
+            #[::core::prelude::v1::test]
+            #maybe_allow_non_snake_case
+            fn #fn_name() {
+                let __cases = ::core::iter::Iterator::take(
+                    ::core::iter::IntoIterator::into_iter(#cases_expr),
+                    #declared_count,
+                );
+                #cr::assert_cases_cover(__cases, #domain, #source);
+            }
+        })
+    }
+
+    /// Generates a single test iterating over all cases. Used if the number of cases
+    /// is not known to the macro. All cases are run even if some of them fail; failures
+    /// are reported together once all cases are run.
+    fn all_cases_fn(&self) -> proc_macro2::TokenStream {
+        let cr = quote!(test_casing);
+        let name = &self.name;
+        let attrs = &self.fn_attrs;
+        let maybe_allow_non_snake_case = self.attrs.tags.allow_attr();
+        let tags_suffix = self.attrs.tags.name_suffix();
+        let fn_name = Ident::new(&format!("all_cases{tags_suffix}"), self.name.span());
+        let maybe_test_attr = if self.fn_sig.asyncness.is_some() {
+            self.async_test_attr()
+        } else {
+            // Property test attributes generate a test attribute themselves.
+            let has_property_attr = self.fn_attrs.iter().any(Self::is_property_attr);
+            (cfg!(feature = "nightly") && !has_property_attr)
+                .then(|| quote!(#[::core::prelude::v1::test]))
+        };
+
+        let maybe_async = &self.fn_sig.asyncness;
+        let maybe_await = maybe_async.as_ref().map(|_| quote!(.await));
+        let ret = &self.fn_sig.output;
+        let maybe_ok = match ret {
+            ReturnType::Default => None,
+            ReturnType::Type { .. } => Some(quote!(Ok(()))),
+        };
+        let (init_cases, next_case) = self.iterate_cases(&quote!(__CASE_COUNT));
+        // Property args are used by all cases, so they are cloned.
+        let property_args: Vec<_> = self
+            .property_arg_idents()
+            .map(|arg| quote!(::core::clone::Clone::clone(&#arg)))
+            .collect();
+        let (case_binding, case_args) = self.case_binding(&quote!(__index), &property_args);
+        let fn_args = &self.property_args;
+        let print_case = self.print_case(&quote!(__index));
+        let test_name = fn_name.to_string();
+        let skip_case = Self::skip_case(&quote!(__index), &test_name, &quote!(continue;));
+        let failure_guard = self.failure_guard(&quote!(__index), &test_name);
+        let call = self.call_and_finish(
+            &quote!(#name(#case_args) #maybe_await),
+            failure_guard.is_some(),
+        );
+
+        let run_case = if maybe_async.is_some() {
+            quote! {
+                __runner.run_async(__index, async {
+                    #failure_guard
+                    let #case_binding = __case;
+                    #call
+                }).await;
+            }
+        } else {
+            quote! {
+                __runner.run(__index, || {
+                    #failure_guard
+                    let #case_binding = __case;
+                    #call
+                });
+            }
+        };
+
+        let (maybe_ignore_attr, maybe_skip_test) = self.ignore_if();
+        let body = quote! {
+            #init_cases
+            let mut __runner = #cr::AllCasesRunner::new(
+                ::core::concat!(::core::module_path!(), "::", #test_name),
+            );
+            for __index in 0..__CASE_COUNT {
+                let __case = #next_case;
+                #skip_case
+                #print_case
+                #run_case
+            }
+            __runner.finish();
+            #maybe_ok
+        };
+        let body = if maybe_async.is_none() && self.property_args.is_empty() {
+            with_default_timeout(&quote!(|| #ret { #body }))
+        } else {
+            body
+        };
+
+        quote! {
+            #maybe_test_attr
+            #(#attrs)*
+            #maybe_ignore_attr
+            #maybe_allow_non_snake_case
+            #maybe_async fn #fn_name(#(#fn_args),*) #ret {
+                #maybe_skip_test
+                #body
             }
         }
     }
@@ -290,9 +1571,27 @@ impl FunctionWrapper {
     fn declare_test_case(&self, index: usize, test_fn_name: &Ident) -> impl ToTokens {
         let cr = quote!(test_casing);
         let cases_expr = &self.attrs.expr;
+        let count = &self.attrs.count;
+        let source = self.cases_source();
+        let call_with_case = self.call_with_case();
         let test_case_name = format!("__TEST_CASE_{index}");
         let test_case_name = Ident::new(&test_case_name, self.name.span());
         let additional_args = self.nightly.macro_args();
+        let ignore_if = self.ignore_if.as_ref().map(|ignore_if| {
+            let condition = ignore_if.condition();
+            let reason = ignore_if.reason().map_or_else(
+                || quote!(::core::option::Option::None),
+                |reason| quote!(::core::option::Option::Some(#reason)),
+            );
+            quote!(ignore_if: (#condition, #reason),)
+        });
+        let tags_suffix = self.attrs.tags.name_suffix();
+        // Test names must be human-readable and single-line, so the compact mode is always used.
+        let name_print_args = PrintArgs {
+            mode: PrintMode::Compact,
+            ..self.attrs.print_args
+        };
+        let print_args = self.print_args(&quote!(__case), name_print_args);
 
         let span_start = self.name.span().start();
         let start_line = span_start.line;
@@ -310,17 +1609,60 @@ impl FunctionWrapper {
                 start_col: #start_col,
                 end_line: #end_line,
                 end_col: #end_col,
-                arg_names: __ARG_NAMES,
-                cases: #cases_expr,
+                describe_case: |__index| __CASES.print(
+                    || #cases_expr,
+                    __index,
+                    #count,
+                    #source,
+                    |__case| { #call_with_case },
+                    |__case| #print_args,
+                ),
                 index: #index,
+                name_suffix: #tags_suffix,
                 #additional_args
+                #ignore_if
                 testfn: #test_fn_name
             );
         }
     }
 
-    fn case(&self, index: usize, index_width: usize) -> impl ToTokens {
-        let case_name = format!("case_{index:0>index_width$}");
+    /// Evaluates cases during macro expansion if they consist of literals and descriptive
+    /// case names are enabled.
+    fn literal_cases(&self, count: usize) -> Option<Vec<LiteralCase>> {
+        if self.attrs.names == CaseNames::Index {
+            return None;
+        }
+        let mut cases = LiteralCase::from_cases_expr(&self.attrs.expr, self.case_arg_count())?;
+        if cases.len() < count {
+            return None;
+        }
+        cases.truncate(count);
+        for case in &mut cases {
+            case.hide_values(|i| {
+                matches!(
+                    self.arg_formats.get(i),
+                    Some(Some(
+                        ArgFormat::Redact | ArgFormat::Skip | ArgFormat::Opaque
+                    ))
+                )
+            });
+        }
+        Some(cases)
+    }
+
+    /// Generates a test for a single case. If the case consists of literals, the case function
+    /// name and docs describe the case args.
+    fn case(
+        &self,
+        index: usize,
+        index_width: usize,
+        literal_case: Option<&LiteralCase>,
+    ) -> impl ToTokens {
+        let tags_suffix = self.attrs.tags.name_suffix();
+        let name_part = literal_case
+            .and_then(LiteralCase::name_part)
+            .map_or_else(String::new, |part| format!("_{part}"));
+        let case_name = format!("case_{index:0>index_width$}{name_part}{tags_suffix}");
         let case_name = Ident::new(&case_name, self.name.span());
 
         #[cfg(feature = "nightly")]
@@ -328,7 +1670,12 @@ impl FunctionWrapper {
             let case_fn = self.case_fn(index, &case_name);
             let test_fn_name = format!("__TEST_FN_{index}");
             let test_fn_name = Ident::new(&test_fn_name, self.name.span());
-            let ret = &self.fn_sig.output;
+            // Property test attributes generate test functions returning `()`.
+            let ret = if self.property_args.is_empty() {
+                self.fn_sig.output.clone()
+            } else {
+                ReturnType::Default
+            };
             let case_decl = self.declare_test_case(index, &test_fn_name);
 
             quote! {
@@ -345,13 +1692,27 @@ impl FunctionWrapper {
         }
 
         #[cfg(not(feature = "nightly"))]
-        self.case_fn(index, &case_name)
+        {
+            let case_fn = self.case_fn(index, &case_name);
+            let maybe_doc = literal_case.map(|case| {
+                let doc = format!(
+                    "Test case #{index}: {}",
+                    case.description(&self.arg_name_strings())
+                );
+                quote!(#[doc = #doc])
+            });
+            quote! {
+                #maybe_doc
+                #case_fn
+            }
+        }
     }
 
     fn case_fn(&self, index: usize, case_name: &Ident) -> proc_macro2::TokenStream {
-        let cr = quote!(test_casing);
         let name = &self.name;
         let attrs = &self.fn_attrs;
+        let maybe_async_test_attr = self.async_test_attr();
+        let maybe_allow_non_snake_case = self.attrs.tags.allow_attr();
 
         let maybe_async = &self.fn_sig.asyncness;
         let maybe_await = maybe_async.as_ref().map(|_| quote!(.await));
@@ -360,56 +1721,262 @@ impl FunctionWrapper {
             ReturnType::Default => Some(quote!(;)),
             ReturnType::Type { .. } => None,
         };
-        let cases_expr = &self.attrs.expr;
-        let (case_binding, case_args) = self.case_binding();
+        let get_case = self.take_case(&index);
+        let property_args: Vec<_> = self.property_arg_idents().map(|arg| quote!(#arg)).collect();
+        let (case_binding, case_args) = self.case_binding(&index, &property_args);
+        let fn_args = &self.property_args;
+        let on_skip = skipped_test_output(ret);
+        let on_skip = quote!(return #on_skip;);
+        let test_name = case_name.to_string();
+        let skip_case = Self::skip_case(&index, &test_name, &on_skip);
 
-        let case_assignment = if cfg!(feature = "nightly") {
+        // With the nightly feature, case args are included into the test name, so they
+        // are only printed if this is explicitly requested by machine-readable output.
+        let print_in_nightly = self.attrs.print_args.mode == PrintMode::Json;
+        let print_case = (!cfg!(feature = "nightly") || print_in_nightly)
+            .then(|| self.print_once(self.print_case(&index)));
+        let failure_guard = self.failure_guard(&index, &test_name);
+        let case_assignment = if print_case.is_none() && failure_guard.is_none() {
             quote! {
-                let #case_binding = #cr::case(#cases_expr, #index);
+                let #case_binding = #get_case;
             }
         } else {
             quote! {
-                let __case = #cr::case(#cases_expr, #index);
-                println!(
-                    "Testing case #{}: {}",
-                    #index,
-                    #cr::ArgNames::print_with_args(__ARG_NAMES, &__case)
-                );
+                let __case = #get_case;
+                #print_case
+                #failure_guard
                 let #case_binding = __case;
             }
         };
+        let call = self.call_and_finish(
+            &quote!(#name(#case_args) #maybe_await),
+            failure_guard.is_some(),
+        );
+
+        // With the nightly feature, `#[ignore_if]` is handled when declaring the test case.
+        let (maybe_ignore_attr, maybe_skip_test) = if cfg!(feature = "nightly") {
+            (None, None)
+        } else {
+            self.ignore_if()
+        };
+
+        let body = quote! {
+            #skip_case
+            #case_assignment
+            #call
+        };
+        // The default timeout requires running the test on a separate thread, so it's not applied
+        // to async tests and property tests.
+        let body = if maybe_async.is_none() && self.property_args.is_empty() {
+            with_default_timeout(&quote!(|| #ret { #body }))
+        } else {
+            body
+        };
 
         quote! {
+            #maybe_async_test_attr
             #(#attrs)*
-            #maybe_async fn #case_name() #ret {
-                #case_assignment
-                #name(#case_args) #maybe_await #maybe_semicolon
+            #maybe_ignore_attr
+            #maybe_allow_non_snake_case
+            #maybe_async fn #case_name(#(#fn_args),*) #ret {
+                #maybe_skip_test
+                #body #maybe_semicolon
             }
         }
     }
 
+    /// Wraps the statement printing a case so that it's executed once if the tested function
+    /// has property args (i.e., is run multiple times per case).
+    fn print_once(&self, print_case: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        if self.property_args.is_empty() {
+            return print_case;
+        }
+        quote! {
+            static __PRINT_ONCE: ::std::sync::Once = ::std::sync::Once::new();
+            __PRINT_ONCE.call_once(|| { #print_case });
+        }
+    }
+
+    /// Generates a statement creating `__failure_guard` recording the case outcome
+    /// into the failure summary. Returns `None` if the outcome cannot be determined
+    /// by the generated code, i.e., for tests expected to panic and for property tests.
+    fn failure_guard(
+        &self,
+        index: &impl ToTokens,
+        test_name: &str,
+    ) -> Option<proc_macro2::TokenStream> {
+        let should_panic = self
+            .fn_attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("should_panic"));
+        if should_panic || !self.property_args.is_empty() {
+            return None;
+        }
+
+        let cr = quote!(test_casing);
+        let print_args = PrintArgs {
+            mode: PrintMode::Compact,
+            ..self.attrs.print_args
+        };
+        let printed_args = self.print_args(&quote!(&__case), print_args);
+        Some(quote! {
+            let __failure_guard = #cr::CaseFailureGuard::new(
+                ::core::concat!(::core::module_path!(), "::", #test_name),
+                #index,
+                || #printed_args,
+            );
+        })
+    }
+
+    /// Generates a call of the tested function, finishing `__failure_guard`
+    /// (if it's present) with the call output.
+    fn call_and_finish(
+        &self,
+        call: &proc_macro2::TokenStream,
+        has_failure_guard: bool,
+    ) -> proc_macro2::TokenStream {
+        match &self.fn_sig.output {
+            ReturnType::Type { .. } if has_failure_guard => quote! {{
+                let __output = #call;
+                __failure_guard.finish(&__output);
+                __output
+            }},
+            _ => call.clone(),
+        }
+    }
+
+    /// Returns an attribute ignoring a generated test and / or statements skipping the test
+    /// at runtime according to the `#[ignore_if(..)]` attribute. Only `cfg(..)` conditions
+    /// can be expressed with an attribute.
+    fn ignore_if(
+        &self,
+    ) -> (
+        Option<proc_macro2::TokenStream>,
+        Option<proc_macro2::TokenStream>,
+    ) {
+        let Some(ignore_if) = &self.ignore_if else {
+            return (None, None);
+        };
+        match ignore_if.cfg_attr() {
+            Some(attr) => (Some(attr), None),
+            None => (None, Some(ignore_if.skip_test(&self.fn_sig.output))),
+        }
+    }
+
+    /// Generates a check whether the case is filtered out via the env variable;
+    /// `on_skip` is executed for filtered-out cases.
+    fn skip_case(
+        index: &impl ToTokens,
+        test_name: &str,
+        on_skip: &proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let cr = quote!(test_casing);
+        quote! {
+            if #cr::is_case_skipped(
+                #index,
+                ::core::concat!(::core::module_path!(), "::", #test_name),
+            ) {
+                #on_skip
+            }
+        }
+    }
+
+    /// Returns idents for args provided by cases.
+    fn case_arg_idents(&self) -> Vec<Ident> {
+        let arg_count = self.case_arg_count();
+        let args = self.fn_sig.inputs.iter().take(arg_count).enumerate();
+        args.map(|(idx, arg)| {
+            let name = if arg_count == 1 {
+                "__case_arg".to_owned()
+            } else {
+                format!("__case_arg{idx}")
+            };
+            Ident::new(&name, arg.span())
+        })
+        .collect()
+    }
+
+    /// Returns a pattern destructuring a case into the specified bindings for case args.
+    fn case_pattern(
+        &self,
+        bindings: impl Iterator<Item = proc_macro2::TokenStream>,
+    ) -> proc_macro2::TokenStream {
+        if let Some(path) = &self.attrs.case_fields {
+            let fields = self.case_arg_fields();
+            quote!(#path { #(#fields: #bindings,)* .. })
+        } else if self.case_arg_count() == 1 {
+            quote!(#(#bindings)*)
+        } else {
+            quote!((#(#bindings,)*))
+        }
+    }
+
+    /// Returns names of the case args, which must be simple idents. Used to bind fields
+    /// of struct cases.
+    fn case_arg_fields(&self) -> impl Iterator<Item = &Ident> + '_ {
+        let args = self.fn_sig.inputs.iter().take(self.case_arg_count());
+        args.filter_map(|arg| match arg {
+            FnArg::Typed(PatType { pat, .. }) => match pat.as_ref() {
+                Pat::Ident(ident) => Some(&ident.ident),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+    }
+
+    /// Checks that case args can be bound to fields of struct cases.
+    fn check_case_fields(&self) -> syn::Result<()> {
+        if self.attrs.case_fields.is_none() {
+            return Ok(());
+        }
+        let args = self.fn_sig.inputs.iter().take(self.case_arg_count());
+        for arg in args {
+            let is_ident = matches!(
+                arg,
+                FnArg::Typed(PatType { pat, .. }) if matches!(pat.as_ref(), Pat::Ident(_))
+            );
+            if !is_ident {
+                let message = "args must be simple identifiers matching case struct fields \
+                    if the `case_fields` option is specified";
+                return Err(SynError::new_spanned(arg, message));
+            }
+        }
+        Ok(())
+    }
+
     /// Returns the binding of args supplied to the test case and potentially mapped args
-    /// to provide to the test function.
-    fn case_binding(&self) -> (impl ToTokens, impl ToTokens) {
-        if self.fn_sig.inputs.len() == 1 {
-            let arg = self.fn_sig.inputs.first().unwrap();
-            let arg = Ident::new("__case_arg", arg.span());
+    /// to provide to the test function. `property_args` are expressions for the args
+    /// provided by the property test attribute.
+    fn case_binding(
+        &self,
+        index: &impl ToTokens,
+        property_args: &[proc_macro2::TokenStream],
+    ) -> (impl ToTokens, impl ToTokens) {
+        let defaults: Vec<_> = self
+            .arg_defaults
+            .iter()
+            .map(|default| default.value(&self.name, index))
+            .collect();
+        let args = self.case_arg_idents();
+        let case_binding = self.case_pattern(args.iter().map(|arg| quote!(#arg)));
+        if args.len() == 1 {
+            let arg = &args[0];
             let mapped_arg = self.arg_mappings[0]
                 .as_ref()
-                .map_or_else(|| quote!(#arg), |mapping| mapping.map_arg(&arg));
-            (quote!(#arg), mapped_arg)
+                .map_or_else(|| quote!(#arg), |mapping| mapping.map_arg(arg));
+            let case_args = if defaults.is_empty() && property_args.is_empty() {
+                mapped_arg
+            } else {
+                quote!(#mapped_arg, #(#property_args,)* #(#defaults,)*)
+            };
+            (case_binding, case_args)
         } else {
-            let args = self.fn_sig.inputs.iter().enumerate();
-            let args = args.map(|(idx, arg)| Ident::new(&format!("__case_arg{idx}"), arg.span()));
-            let binding_args = args.clone();
-            let case_binding = quote!((#(#binding_args,)*));
-
-            let args = args.zip(&self.arg_mappings).map(|(arg, mapping)| {
+            let args = args.iter().zip(&self.arg_mappings).map(|(arg, mapping)| {
                 mapping
                     .as_ref()
-                    .map_or_else(|| quote!(#arg), |mapping| mapping.map_arg(&arg))
+                    .map_or_else(|| quote!(#arg), |mapping| mapping.map_arg(arg))
             });
-            let case_args = quote!(#(#args,)*);
+            let case_args = quote!(#(#args,)* #(#property_args,)* #(#defaults,)*);
             (case_binding, case_args)
         }
     }
@@ -419,12 +1986,55 @@ pub(crate) fn impl_test_casing(
     attr: TokenStream,
     item: TokenStream,
 ) -> syn::Result<proc_macro2::TokenStream> {
-    let attrs = CaseAttrs::parse(attr.into())?;
+    let attr = proc_macro2::TokenStream::from(attr);
     let item: Item = syn::parse(item)?;
     match item {
         Item::Fn(mut function) => {
-            let wrapper = FunctionWrapper::new(attrs, &mut function)?;
-            let wrapper = wrapper.wrap();
+            let mut groups = vec![];
+            if attr.is_empty() {
+                groups.push(CaseAttrs::from_arg_values(&mut function)?);
+                groups.extend(CaseAttrs::take_from(&mut function.attrs)?);
+                if let Some(other_group) = groups.get(1) {
+                    let message = "#[test_casing] without args cannot be combined \
+                        with other #[test_casing] attributes";
+                    return Err(SynError::new_spanned(&other_group.expr, message));
+                }
+            } else if let Some(attr) = CaseAttrs::expand_inline(&attr, &mut function)? {
+                groups.push(CaseAttrs::parse(attr)?);
+                groups.extend(CaseAttrs::take_from(&mut function.attrs)?);
+                if let Some(other_group) = groups.get(1) {
+                    let message = "#[test_casing(inline)] cannot be combined \
+                        with other #[test_casing] attributes";
+                    return Err(SynError::new_spanned(&other_group.expr, message));
+                }
+                CaseAttrs::check_no_arg_values(&function)?;
+            } else {
+                groups.push(CaseAttrs::parse(attr)?);
+                groups.extend(CaseAttrs::take_from(&mut function.attrs)?);
+                CaseAttrs::check_no_arg_values(&function)?;
+            }
+            CaseAttrs::check_no_inline_cases(&function)?;
+            CaseAttrs::check_groups(&groups)?;
+            if function.sig.asyncness.is_none() {
+                if let Some(async_with) = groups.iter().find_map(|attrs| attrs.async_with.as_ref())
+                {
+                    let message = "`async_with` option is only applicable to async functions";
+                    return Err(SynError::new_spanned(async_with, message));
+                }
+            }
+
+            let mut groups = groups.into_iter();
+            let wrapper = FunctionWrapper::new(groups.next().unwrap(), &mut function)?;
+            let mut wrappers = vec![];
+            for attrs in groups {
+                wrappers.push(wrapper.with_attrs(attrs));
+            }
+            wrappers.insert(0, wrapper);
+            for wrapper in &wrappers {
+                wrapper.check_async_test_attr()?;
+                wrapper.check_case_fields()?;
+            }
+            let wrapper = FunctionWrapper::wrap(&wrappers);
             Ok(quote!(#function #wrapper))
         }
         item => {
@@ -5,6 +5,7 @@ use syn::{parse::Error as SynError, Attribute, Expr, ExprLit, Lit, Meta, MetaLis
 
 use std::fmt;
 
+#[derive(Clone)]
 pub(crate) enum AttrValue {
     Empty,
     Str(syn::LitStr),
@@ -73,7 +74,7 @@ impl AttrValue {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct NightlyData {
     pub ignore: Option<AttrValue>,
     pub should_panic: Option<AttrValue>,
@@ -10,8 +10,482 @@ use super::*;
 fn parsing_case_attrs() {
     let attr = quote!(3, ["test", "this", "str"]);
     let attrs = CaseAttrs::parse(attr).unwrap();
-    assert_eq!(attrs.count, 3);
+    assert_matches!(attrs.count, CaseCount::Literal(3));
     assert_eq!(attrs.expr, syn::parse_quote!(["test", "this", "str"]));
+    assert!(attrs.tags.is_empty());
+}
+
+#[test]
+fn parsing_case_attrs_with_tags() {
+    let attr = quote!(2, CASES, tags = ["slow", "serial-db"],);
+    let attrs = CaseAttrs::parse(attr).unwrap();
+    assert_matches!(attrs.count, CaseCount::Literal(2));
+    assert_eq!(attrs.expr, syn::parse_quote!(CASES));
+    assert_eq!(attrs.tags.name_suffix(), "__tag_slow__tag_serial_db__");
+
+    let attr = quote!(2, CASES, tags = ["Slow"]);
+    let err = CaseAttrs::parse(attr).unwrap_err().to_string();
+    assert!(err.contains("lowercase ASCII letters"), "{err}");
+}
+
+#[test]
+fn parsing_case_attrs_with_expr_count() {
+    let attr = quote!(CASES_LEN, CASES);
+    let attrs = CaseAttrs::parse(attr).unwrap();
+    assert_matches!(attrs.count, CaseCount::Expr(expr) if matches!(*expr, Expr::Path(_)));
+
+    let attr = quote!(ARRAY.len(), ARRAY);
+    let attrs = CaseAttrs::parse(attr).unwrap();
+    assert_matches!(attrs.count, CaseCount::Expr(expr) if matches!(*expr, Expr::MethodCall(_)));
+
+    let attr = quote!("2", CASES);
+    let err = CaseAttrs::parse(attr).unwrap_err().to_string();
+    assert!(err.contains("expected integer literal"), "{err}");
+}
+
+#[test]
+fn parsing_arg_values() {
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(#[values(1, 2, 3)] number: u32, #[values("a", "b")] s: &str) {}
+    };
+    let attrs = CaseAttrs::from_arg_values(&mut function).unwrap();
+    assert_matches!(attrs.count, CaseCount::Literal(6));
+    let expected: Expr = syn::parse_quote!(test_casing::Product(([1, 2, 3,], ["a", "b",],)));
+    assert_eq!(attrs.expr, expected);
+    let arg_attrs = function.sig.inputs.iter().map(|arg| match arg {
+        FnArg::Typed(typed) => typed.attrs.len(),
+        FnArg::Receiver(_) => unreachable!(),
+    });
+    assert!(arg_attrs.eq([0, 0]));
+
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(#[values(1, 2, 3)] number: u32) {}
+    };
+    let attrs = CaseAttrs::from_arg_values(&mut function).unwrap();
+    assert_matches!(attrs.count, CaseCount::Literal(3));
+    assert_eq!(attrs.expr, syn::parse_quote!([1, 2, 3,]));
+}
+
+#[test]
+fn parsing_arg_values_errors() {
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(#[values(1, 2, 3)] number: u32, s: &str) {}
+    };
+    let err = CaseAttrs::from_arg_values(&mut function)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("must have a #[values(..)] attribute"), "{err}");
+
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(#[values()] number: u32) {}
+    };
+    let err = CaseAttrs::from_arg_values(&mut function)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("at least one value"), "{err}");
+
+    let function: ItemFn = syn::parse_quote! {
+        fn tested_fn(#[values(1, 2)] number: u32) {}
+    };
+    let err = CaseAttrs::check_no_arg_values(&function)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("without args"), "{err}");
+}
+
+#[test]
+fn expanding_inline_cases() {
+    let mut function: ItemFn = syn::parse_quote! {
+        #[case(2, "2")]
+        #[case(3, "3")]
+        #[should_panic]
+        fn tested_fn(number: u32, s: &str, #[default(false)] flag: bool) {}
+    };
+    let attr = quote!(inline, print_args = pretty);
+    let attr = CaseAttrs::expand_inline(&attr, &mut function)
+        .unwrap()
+        .unwrap();
+    assert_eq!(function.attrs.len(), 1);
+    let attrs = CaseAttrs::parse(attr).unwrap();
+    assert_matches!(attrs.count, CaseCount::Literal(2));
+    assert_eq!(attrs.expr, syn::parse_quote!([(2, "2",), (3, "3",),]));
+    assert_eq!(attrs.print_args.mode, PrintMode::Pretty);
+
+    let mut function: ItemFn = syn::parse_quote! {
+        #[case(2)]
+        fn tested_fn(number: u32) {}
+    };
+    let attr = CaseAttrs::expand_inline(&quote!(inline), &mut function)
+        .unwrap()
+        .unwrap();
+    let attrs = CaseAttrs::parse(attr).unwrap();
+    assert_matches!(attrs.count, CaseCount::Literal(1));
+    assert_eq!(attrs.expr, syn::parse_quote!([2,]));
+
+    let attr = quote!(2, CASES);
+    assert!(CaseAttrs::expand_inline(&attr, &mut function)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn expanding_inline_cases_errors() {
+    let mut function: ItemFn = syn::parse_quote! {
+        #[case(2)]
+        fn tested_fn(number: u32, s: &str) {}
+    };
+    let err = CaseAttrs::expand_inline(&quote!(inline), &mut function)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("expected 2 value(s)"), "{err}");
+
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(number: u32) {}
+    };
+    let err = CaseAttrs::expand_inline(&quote!(inline), &mut function)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("at least one #[case(..)]"), "{err}");
+
+    let function: ItemFn = syn::parse_quote! {
+        #[case(2)]
+        fn tested_fn(number: u32) {}
+    };
+    let err = CaseAttrs::check_no_inline_cases(&function)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("#[test_casing(inline)]"), "{err}");
+}
+
+#[test]
+fn generating_marker_test_for_zero_cases() {
+    let attrs = CaseAttrs::parse(quote!(0, CASES)).unwrap();
+    assert_matches!(attrs.count, CaseCount::Literal(0));
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(number: u32) {}
+    };
+    let wrapper = FunctionWrapper::new(attrs, &mut function).unwrap();
+    let output = FunctionWrapper::wrap(&[wrapper]).to_string();
+    assert!(output.contains("fn no_cases"), "{output}");
+    assert!(!output.contains("fn case_"), "{output}");
+}
+
+#[cfg(not(feature = "nightly"))]
+#[test]
+fn generating_descriptive_case_names() {
+    let attrs = CaseAttrs::parse(quote!(2, [("foo", -1), ("Bar baz", 2)])).unwrap();
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(s: &str, #[fmt(redact)] number: i32) {}
+    };
+    let wrapper = FunctionWrapper::new(attrs, &mut function).unwrap();
+    let output = FunctionWrapper::wrap(&[wrapper]).to_string();
+    assert!(output.contains("fn case_0_foo ()"), "{output}");
+    assert!(output.contains("fn case_1_bar_baz ()"), "{output}");
+    assert!(
+        output.contains(r#"doc = "Test case #1: s = \"Bar baz\", number = ***""#),
+        "{output}"
+    );
+
+    // Non-literal cases should fall back to indices.
+    let attrs = CaseAttrs::parse(quote!(2, [("foo", -1), ("bar", VALUE)])).unwrap();
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(s: &str, number: i32) {}
+    };
+    let wrapper = FunctionWrapper::new(attrs, &mut function).unwrap();
+    let output = FunctionWrapper::wrap(&[wrapper]).to_string();
+    assert!(output.contains("fn case_0 ()"), "{output}");
+    assert!(!output.contains("doc ="), "{output}");
+}
+
+#[cfg(not(feature = "nightly"))]
+#[test]
+fn generating_index_only_case_names() {
+    let attr = quote!(2, [("foo", -1), ("Bar baz", 2)], names = index);
+    let attrs = CaseAttrs::parse(attr).unwrap();
+    assert_eq!(attrs.names, CaseNames::Index);
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(s: &str, number: i32) {}
+    };
+    let wrapper = FunctionWrapper::new(attrs, &mut function).unwrap();
+    let output = FunctionWrapper::wrap(&[wrapper]).to_string();
+    assert!(output.contains("fn case_0 ()"), "{output}");
+    assert!(output.contains("fn case_1 ()"), "{output}");
+    assert!(!output.contains("doc ="), "{output}");
+
+    let attr = quote!(2, CASES, names = fancy);
+    let err = CaseAttrs::parse(attr).unwrap_err().to_string();
+    assert!(err.contains("unknown case naming"), "{err}");
+    let attr = quote!(2, CASES, names = index, names = descriptive);
+    let err = CaseAttrs::parse(attr).unwrap_err().to_string();
+    assert!(err.contains("duplicate `names`"), "{err}");
+}
+
+#[test]
+fn generating_validation_test() {
+    let attrs = CaseAttrs::parse(quote!(2, CASES, tags = ["slow"])).unwrap();
+    let mut function: ItemFn = syn::parse_quote! {
+        async fn tested_fn(number: u32) {}
+    };
+    let wrapper = FunctionWrapper::new(attrs, &mut function).unwrap();
+    let validate_fn = wrapper.validate_cases_fn(&wrapper.attrs.count);
+    let validate_fn: ItemFn = syn::parse_quote!(#validate_fn);
+    assert_eq!(validate_fn.sig.ident, "validate_cases__tag_slow__");
+    assert!(validate_fn.sig.asyncness.is_none());
+
+    let body = quote!(#validate_fn).to_string();
+    assert!(body.contains("ignore"), "{body}");
+    assert!(!body.contains("tested_fn"), "{body}");
+}
+
+#[test]
+fn parsing_case_attrs_with_group() {
+    let attr = quote!(2, CASES, group = basic, tags = ["slow"]);
+    let attrs = CaseAttrs::parse(attr).unwrap();
+    assert_eq!(attrs.group.unwrap(), "basic");
+    assert_eq!(attrs.tags.name_suffix(), "__tag_slow__");
+
+    let attr = quote!(2, CASES, group = basic, group = other);
+    let err = CaseAttrs::parse(attr).unwrap_err().to_string();
+    assert!(err.contains("duplicate `group`"), "{err}");
+}
+
+#[test]
+fn exporting_cases() {
+    let attr = quote!(2, CASES, export, group = basic);
+    let attrs = CaseAttrs::parse(attr).unwrap();
+    assert!(attrs.export);
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(number: u32) {}
+    };
+    let wrapper = FunctionWrapper::new(attrs, &mut function).unwrap();
+    let (count_name, fn_name) = wrapper.exported_names();
+    assert_eq!(count_name, "CASE_COUNT_BASIC");
+    assert_eq!(fn_name, "run_case_basic");
+
+    let module = FunctionWrapper::wrap(&[wrapper]).to_string();
+    assert!(
+        module.contains("# [cfg (not (test))] pub (crate) mod tested_fn"),
+        "{module}"
+    );
+    assert!(
+        module.contains(
+            "pub (crate) use self :: __exports :: { CASE_COUNT_BASIC , run_case_basic , }"
+        ),
+        "{module}"
+    );
+
+    let attr = quote!(2, CASES, export, export);
+    let err = CaseAttrs::parse(attr).unwrap_err().to_string();
+    assert!(err.contains("duplicate `export`"), "{err}");
+}
+
+#[test]
+fn generating_coverage_check() {
+    let attr = quote!(3, CASES, exhaustive = Op::ALL);
+    let attrs = CaseAttrs::parse(attr).unwrap();
+    assert!(attrs.exhaustive.is_some());
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(op: Op) {}
+    };
+    let wrapper = FunctionWrapper::new(attrs, &mut function).unwrap();
+    let coverage_fn = wrapper.check_coverage_fn(&wrapper.attrs.count).unwrap();
+    let coverage_fn: ItemFn = syn::parse2(coverage_fn).unwrap();
+    assert_eq!(coverage_fn.sig.ident, "check_coverage");
+    let body = coverage_fn.block.to_token_stream().to_string();
+    assert!(
+        body.contains("test_casing :: assert_cases_cover (__cases , Op :: ALL , \"CASES\")"),
+        "{body}"
+    );
+
+    let attr = quote!(3, CASES, exhaustive = Op::ALL, exhaustive = 0..3);
+    let err = CaseAttrs::parse(attr).unwrap_err().to_string();
+    assert!(err.contains("duplicate `exhaustive`"), "{err}");
+}
+
+#[test]
+fn parsing_case_attrs_with_print_args() {
+    let attr = quote!(2, CASES, print_args = pretty, max_arg_width = 40);
+    let attrs = CaseAttrs::parse(attr).unwrap();
+    assert_eq!(attrs.print_args.mode, PrintMode::Pretty);
+    assert_eq!(attrs.print_args.max_width, Some(40));
+
+    let options = attrs.print_args.options().to_string();
+    assert!(options.contains(". pretty ()"), "{options}");
+    assert!(options.contains(". with_max_width (40usize)"), "{options}");
+
+    let attr = quote!(2, CASES, print_args = compact);
+    let attrs = CaseAttrs::parse(attr).unwrap();
+    assert!(attrs.print_args.is_default());
+
+    let attr = quote!(2, CASES, print_args = json);
+    let attrs = CaseAttrs::parse(attr).unwrap();
+    assert_eq!(attrs.print_args.mode, PrintMode::Json);
+    let options = attrs.print_args.options().to_string();
+    assert!(options.contains(". json ()"), "{options}");
+
+    let attr = quote!(2, CASES, print_args = fancy);
+    let err = CaseAttrs::parse(attr).unwrap_err().to_string();
+    assert!(err.contains("unknown print mode"), "{err}");
+}
+
+#[test]
+fn extracting_case_groups() {
+    let mut function: ItemFn = syn::parse_quote! {
+        #[test_casing(3, FUZZ_CASES, group = fuzz)]
+        #[should_panic]
+        fn tested_fn(number: u32) {}
+    };
+    let groups = CaseAttrs::take_from(&mut function.attrs).unwrap();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].group.as_ref().unwrap(), "fuzz");
+    assert_eq!(function.attrs.len(), 1);
+
+    let first_group = CaseAttrs::parse(quote!(2, CASES)).unwrap();
+    let groups = [first_group, groups[0].clone()];
+    let err = CaseAttrs::check_groups(&groups).unwrap_err().to_string();
+    assert!(err.contains("unique group name"), "{err}");
+
+    let first_group = CaseAttrs::parse(quote!(2, CASES, group = fuzz)).unwrap();
+    let groups = [first_group, groups[1].clone()];
+    let err = CaseAttrs::check_groups(&groups).unwrap_err().to_string();
+    assert!(err.contains("duplicate group name"), "{err}");
+}
+
+#[test]
+fn generating_cases_with_property_args() {
+    let attrs = CaseAttrs::parse(quote!(2, CASES)).unwrap();
+    let mut function: ItemFn = syn::parse_quote! {
+        #[proptest]
+        fn tested_fn(number: u32, #[property] #[strategy(0..10_u32)] mut other: u32) {}
+    };
+    let wrapper = FunctionWrapper::new(attrs, &mut function).unwrap();
+    assert_eq!(wrapper.case_arg_count(), 1);
+    assert_matches!(&function.sig.inputs[1], FnArg::Typed(arg) if arg.attrs.is_empty());
+
+    let case_fn = wrapper.case_fn(0, &syn::parse_quote!(case_0));
+    let case_fn: ItemFn = syn::parse2(case_fn).unwrap();
+    let attr_paths: Vec<_> = case_fn.attrs.iter().map(Attribute::path).collect();
+    assert_eq!(attr_paths, [&syn::parse_quote!(proptest)] as [&Path; 1]);
+    let [FnArg::Typed(property_arg)] = case_fn.sig.inputs.iter().collect::<Vec<_>>()[..] else {
+        panic!("unexpected case fn signature: {:?}", case_fn.sig);
+    };
+    assert_eq!(
+        quote!(#property_arg).to_string(),
+        "# [strategy (0 .. 10_u32)] other : u32"
+    );
+    let body = quote!(#case_fn).to_string();
+    assert!(body.contains("tested_fn (__case_arg , other ,)"), "{body}");
+    if cfg!(not(feature = "nightly")) {
+        assert!(body.contains("__PRINT_ONCE"), "{body}");
+    }
+
+    let test_cases_iter = wrapper.test_cases_iter();
+    let test_cases_iter = quote!(#test_cases_iter).to_string();
+    assert!(
+        test_cases_iter.contains("tested_fn (__case_arg , (|| :: core :: unreachable ! ()) () ,)"),
+        "{test_cases_iter}"
+    );
+}
+
+#[test]
+fn skipping_quickcheck_cases() {
+    let attrs = CaseAttrs::parse(quote!(2, CASES)).unwrap();
+    let mut function: ItemFn = syn::parse_quote! {
+        #[quickcheck_macros::quickcheck]
+        fn tested_fn(number: u32, #[property] other: u32) -> bool {}
+    };
+    let wrapper = FunctionWrapper::new(attrs, &mut function).unwrap();
+    let case_fn = wrapper.case_fn(0, &syn::parse_quote!(case_0)).to_string();
+    assert!(case_fn.contains("return true ;"), "{case_fn}");
+    assert!(
+        !case_fn.contains(":: core :: prelude :: v1 :: test"),
+        "{case_fn}"
+    );
+}
+
+#[test]
+fn property_args_errors() {
+    let attrs = CaseAttrs::parse(quote!(2, CASES)).unwrap();
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(number: u32, #[property] other: u32) {}
+    };
+    let err = FunctionWrapper::new(attrs.clone(), &mut function)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("require a property test attribute"), "{err}");
+
+    let mut function: ItemFn = syn::parse_quote! {
+        #[proptest]
+        fn tested_fn(#[property] other: u32, number: u32) {}
+    };
+    let err = FunctionWrapper::new(attrs.clone(), &mut function)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("must be placed after all args provided by cases"),
+        "{err}"
+    );
+
+    let mut function: ItemFn = syn::parse_quote! {
+        #[proptest]
+        fn tested_fn(number: u32, #[property] #[map(ref)] other: u32) {}
+    };
+    let err = FunctionWrapper::new(attrs.clone(), &mut function)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("cannot have #[map(..)]"), "{err}");
+
+    let mut function: ItemFn = syn::parse_quote! {
+        #[proptest]
+        async fn tested_fn(number: u32, #[property] other: u32) {}
+    };
+    let err = FunctionWrapper::new(attrs, &mut function)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("not supported for async functions"), "{err}");
+}
+
+#[test]
+fn generating_case_with_async_test_attr() {
+    let attrs = CaseAttrs::parse(quote!(2, CASES, async_with = tokio::test)).unwrap();
+    let mut function: ItemFn = syn::parse_quote! {
+        async fn tested_fn(number: u32) {}
+    };
+    let wrapper = FunctionWrapper::new(attrs, &mut function).unwrap();
+    let case_name: Ident = syn::parse_quote!(case0);
+    let case_fn = wrapper.case_fn(0, &case_name);
+    let case_fn: ItemFn = syn::parse_quote!(#case_fn);
+    let expected_attr: Attribute = syn::parse_quote!(#[tokio::test]);
+    assert_eq!(case_fn.attrs, [expected_attr]);
+    assert!(case_fn.sig.asyncness.is_some());
+
+    // An explicitly specified test attribute should take precedence.
+    let attrs = CaseAttrs::parse(quote!(2, CASES, async_with = tokio::test)).unwrap();
+    let mut function: ItemFn = syn::parse_quote! {
+        #[async_std::test]
+        async fn tested_fn(number: u32) {}
+    };
+    let wrapper = FunctionWrapper::new(attrs, &mut function).unwrap();
+    assert!(wrapper.async_test_attr().is_none());
+}
+
+#[test]
+fn async_fn_without_test_attr() {
+    let attrs = CaseAttrs::parse(quote!(2, CASES)).unwrap();
+    let mut function: ItemFn = syn::parse_quote! {
+        async fn tested_fn(number: u32) {}
+    };
+    let wrapper = FunctionWrapper::new(attrs, &mut function).unwrap();
+    let err = wrapper.check_async_test_attr().unwrap_err().to_string();
+    assert!(err.contains("missing an async test attribute"), "{err}");
+
+    let attrs = CaseAttrs::parse(quote!(2, CASES)).unwrap();
+    let mut function: ItemFn = syn::parse_quote! {
+        #[wasm_bindgen_test]
+        async fn tested_fn(number: u32) {}
+    };
+    let wrapper = FunctionWrapper::new(attrs, &mut function).unwrap();
+    wrapper.check_async_test_attr().unwrap();
 }
 
 #[test]
@@ -51,8 +525,16 @@ fn processing_map_attr_with_path() {
 #[test]
 fn initializing_fn_wrapper() {
     let attrs = CaseAttrs {
-        count: 2,
+        count: CaseCount::Literal(2),
         expr: syn::parse_quote!(CASES),
+        tags: Tags::default(),
+        group: None,
+        async_with: None,
+        print_args: PrintArgs::default(),
+        names: CaseNames::default(),
+        case_fields: None,
+        export: false,
+        exhaustive: None,
     };
     let mut function: ItemFn = syn::parse_quote! {
         #[allow(unused)]
@@ -93,8 +575,16 @@ fn initializing_fn_wrapper() {
 
 fn create_wrapper() -> FunctionWrapper {
     let attrs = CaseAttrs {
-        count: 2,
+        count: CaseCount::Literal(2),
         expr: syn::parse_quote!(CASES),
+        tags: Tags::default(),
+        group: None,
+        async_with: None,
+        print_args: PrintArgs::default(),
+        names: CaseNames::default(),
+        case_fields: None,
+        export: false,
+        exhaustive: None,
     };
     let mut function: ItemFn = syn::parse_quote! {
         fn tested_fn(number: u32, #[map(ref)] s: &str) {}
@@ -103,6 +593,86 @@ fn create_wrapper() -> FunctionWrapper {
     FunctionWrapper::new(attrs, &mut function).unwrap()
 }
 
+#[test]
+fn processing_fmt_attrs() {
+    let attrs = CaseAttrs::parse(quote!(3, CASES)).unwrap();
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(
+            number: u32,
+            #[fmt(redact)] token: &str,
+            #[fmt(skip)] blob: Vec<u8>,
+            #[fmt(opaque)] client: Client,
+        ) {}
+    };
+    let wrapper = FunctionWrapper::new(attrs, &mut function).unwrap();
+    assert_matches!(
+        wrapper.arg_formats.as_slice(),
+        [
+            None,
+            Some(ArgFormat::Redact),
+            Some(ArgFormat::Skip),
+            Some(ArgFormat::Opaque)
+        ]
+    );
+    for arg in &function.sig.inputs {
+        let FnArg::Typed(arg) = arg else {
+            unreachable!();
+        };
+        assert!(arg.attrs.is_empty());
+    }
+
+    let print_args = wrapper.print_args(&quote!(&__case), PrintArgs::default());
+    let print_args: Expr = syn::parse_quote!(#print_args);
+    let expected: Expr = syn::parse_quote! {{
+        let (__case_arg0, __case_arg1, _, __case_arg3,) = &__case;
+        test_casing::PrintOptions::new().print(&[
+            ("number", __case_arg0 as &dyn ::core::fmt::Debug),
+            ("token", &test_casing::Redacted as &dyn ::core::fmt::Debug),
+            ("client", &test_casing::Opaque as &dyn ::core::fmt::Debug),
+        ])
+    }};
+    assert_eq!(print_args, expected, "{}", quote!(#print_args));
+
+    let attrs = CaseAttrs::parse(quote!(3, CASES)).unwrap();
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(
+            #[fmt(hex)] bytes: &[u8],
+            #[fmt(display)] addr: SocketAddr,
+            #[fmt(with = fmt_len)] values: Vec<u32>,
+        ) {}
+    };
+    let wrapper = FunctionWrapper::new(attrs, &mut function).unwrap();
+    assert_matches!(
+        wrapper.arg_formats.as_slice(),
+        [Some(ArgFormat::Hex), Some(ArgFormat::Display), Some(ArgFormat::With(path))]
+            if path.is_ident("fmt_len")
+    );
+
+    let print_args = wrapper.print_args(&quote!(&__case), PrintArgs::default());
+    let print_args: Expr = syn::parse_quote!(#print_args);
+    let expected: Expr = syn::parse_quote! {{
+        let (__case_arg0, __case_arg1, __case_arg2,) = &__case;
+        test_casing::PrintOptions::new().print(&[
+            ("bytes", &test_casing::FormatHex(__case_arg0) as &dyn ::core::fmt::Debug),
+            ("addr", &test_casing::FormatDisplay(__case_arg1) as &dyn ::core::fmt::Debug),
+            (
+                "values",
+                &test_casing::FormatWith(__case_arg2, fmt_len) as &dyn ::core::fmt::Debug
+            ),
+        ])
+    }};
+    assert_eq!(print_args, expected, "{}", quote!(#print_args));
+
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(#[fmt(hide)] token: &str) {}
+    };
+    let attrs = CaseAttrs::parse(quote!(3, CASES)).unwrap();
+    let err = FunctionWrapper::new(attrs, &mut function)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("unknown arg format"), "{err}");
+}
+
 #[test]
 fn computing_arg_names() {
     let wrapper = create_wrapper();
@@ -117,7 +687,7 @@ fn computing_arg_names() {
 #[test]
 fn computing_case_bindings() {
     let wrapper = create_wrapper();
-    let (case_binding, case_args) = wrapper.case_binding();
+    let (case_binding, case_args) = wrapper.case_binding(&0_usize, &[]);
     let case_binding: Pat = syn::parse_quote!(#case_binding);
     let expected: Pat = syn::parse_quote!((__case_arg0, __case_arg1,));
     assert_eq!(case_binding, expected, "{}", quote!(#case_binding));
@@ -127,6 +697,134 @@ fn computing_case_bindings() {
     assert_eq!(case_args, expected, "{}", quote!(#case_args));
 }
 
+#[test]
+fn computing_case_bindings_with_defaults() {
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(number: u32, #[default(Mode::Fast)] mode: Mode) {}
+    };
+    let wrapper = FunctionWrapper::new(CaseAttrs::parse(quote!(2, CASES)).unwrap(), &mut function);
+    let wrapper = wrapper.unwrap();
+    assert_eq!(wrapper.case_arg_count(), 1);
+
+    let arg_names = wrapper.arg_names();
+    let arg_names: Item = syn::parse_quote!(#arg_names);
+    let expected: Item = syn::parse_quote! {
+        const __ARG_NAMES: [&'static str; 1usize] = ["number",];
+    };
+    assert_eq!(arg_names, expected, "{}", quote!(#arg_names));
+
+    let (case_binding, case_args) = wrapper.case_binding(&0_usize, &[]);
+    let case_binding: Pat = syn::parse_quote!(#case_binding);
+    let expected: Pat = syn::parse_quote!(__case_arg);
+    assert_eq!(case_binding, expected, "{}", quote!(#case_binding));
+    let case_args: Expr = syn::parse_quote!((#case_args));
+    let expected: Expr = syn::parse_quote!((__case_arg, Mode::Fast,));
+    assert_eq!(case_args, expected, "{}", quote!(#case_args));
+}
+
+#[test]
+fn computing_case_bindings_with_case_fields() {
+    let attrs = CaseAttrs::parse(quote!(2, CASES, case_fields = TestCase)).unwrap();
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(number: u32, #[map(ref)] s: &str, #[default(false)] flag: bool) {}
+    };
+    let wrapper = FunctionWrapper::new(attrs, &mut function).unwrap();
+    wrapper.check_case_fields().unwrap();
+
+    let (case_binding, case_args) = wrapper.case_binding(&0_usize, &[]);
+    let case_binding: Pat = syn::parse_quote!(#case_binding);
+    let expected: Pat = syn::parse_quote!(TestCase {
+        number: __case_arg0,
+        s: __case_arg1,
+        ..
+    });
+    assert_eq!(case_binding, expected, "{}", quote!(#case_binding));
+    let case_args: Expr = syn::parse_quote!((#case_args));
+    let expected: Expr = syn::parse_quote!((__case_arg0, &__case_arg1, false,));
+    assert_eq!(case_args, expected, "{}", quote!(#case_args));
+
+    let printed = wrapper.print_args(&quote!(&__case), PrintArgs::default());
+    let printed = printed.to_string();
+    assert!(
+        printed.contains("let TestCase { number : __case_arg0 , s : __case_arg1 , .. } = & __case"),
+        "{printed}"
+    );
+    assert!(
+        printed.contains("print_with_args (__ARG_NAMES , & (__case_arg0 , __case_arg1 ,))"),
+        "{printed}"
+    );
+}
+
+#[test]
+fn case_fields_with_non_ident_arg() {
+    let attrs = CaseAttrs::parse(quote!(2, CASES, case_fields = TestCase)).unwrap();
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn((x, y): (u32, u32)) {}
+    };
+    let wrapper = FunctionWrapper::new(attrs, &mut function).unwrap();
+    let err = wrapper.check_case_fields().unwrap_err().to_string();
+    assert!(err.contains("simple identifiers"), "{err}");
+}
+
+#[test]
+fn non_trailing_default_arg() {
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(#[default(1)] number: u32, s: &str) {}
+    };
+    let err = FunctionWrapper::new(CaseAttrs::parse(quote!(2, CASES)).unwrap(), &mut function)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("must be placed after all other args"), "{err}");
+}
+
+#[test]
+fn computing_case_bindings_with_expected_output() {
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(
+            s: &str,
+            #[expected(file = "tests/expected/{fn}/{case}.txt")] expected: Expected,
+        ) {}
+    };
+    let wrapper = FunctionWrapper::new(CaseAttrs::parse(quote!(2, CASES)).unwrap(), &mut function);
+    let wrapper = wrapper.unwrap();
+    assert_eq!(wrapper.case_arg_count(), 1);
+
+    let (_, case_args) = wrapper.case_binding(&1_usize, &[]);
+    let case_args: Expr = syn::parse_quote!((#case_args));
+    let expected: Expr = syn::parse_quote!((
+        __case_arg,
+        test_casing::Expected::load("tests/expected/{fn}/{case}.txt", "tested_fn", 1usize),
+    ));
+    assert_eq!(case_args, expected, "{}", quote!(#case_args));
+}
+
+#[test]
+fn expected_output_errors() {
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(s: &str, #[expected(file = "{fn}/{name}.txt")] expected: Expected) {}
+    };
+    let err = FunctionWrapper::new(CaseAttrs::parse(quote!(2, CASES)).unwrap(), &mut function)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("unknown placeholder `{name}`"), "{err}");
+
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(s: &str, #[expected(path = "{fn}.txt")] expected: Expected) {}
+    };
+    let err = FunctionWrapper::new(CaseAttrs::parse(quote!(2, CASES)).unwrap(), &mut function)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("expected `file = "), "{err}");
+
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(#[expected(file = "{fn}.txt")] expected: Expected, s: &str) {}
+    };
+    let err = FunctionWrapper::new(CaseAttrs::parse(quote!(2, CASES)).unwrap(), &mut function)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("must be placed after all other args"), "{err}");
+}
+
 #[cfg(feature = "nightly")]
 #[test]
 fn generating_case() {
@@ -137,7 +835,19 @@ fn generating_case() {
 
     let expected: ItemFn = syn::parse_quote! {
         fn case0() {
-            let (__case_arg0, __case_arg1,) = test_casing::case(CASES, 0usize);
+            if test_casing::is_case_skipped(
+                0usize,
+                ::core::concat!(::core::module_path!(), "::", "case0"),
+            ) {
+                return;
+            }
+            let __case = __CASES.take(|| CASES, 0usize, 2usize, "CASES");
+            let __failure_guard = test_casing::CaseFailureGuard::new(
+                ::core::concat!(::core::module_path!(), "::", "case0"),
+                0usize,
+                || test_casing::ArgNames::print_with_args(__ARG_NAMES, &__case),
+            );
+            let (__case_arg0, __case_arg1,) = __case;
             tested_fn(__case_arg0, &__case_arg1,);
         }
     };
@@ -155,15 +865,126 @@ fn generating_case() {
     let expected: ItemFn = syn::parse_quote! {
         #[::core::prelude::v1::test]
         fn case0() {
-            let __case = test_casing::case(CASES, 0usize);
-            println!(
-                "Testing case #{}: {}",
-                0usize,
-                test_casing::ArgNames::print_with_args(__ARG_NAMES, &__case)
-            );
-            let (__case_arg0, __case_arg1,) = __case;
-            tested_fn(__case_arg0, &__case_arg1,);
+            {
+                #[allow(unused_imports)]
+                use test_casing::decorators::{ApplyDefaultTimeout as _, SkipDefaultTimeout as _};
+                (&test_casing::decorators::DefaultTimeoutFn::new(|| {
+                    if test_casing::is_case_skipped(
+                        0usize,
+                        ::core::concat!(::core::module_path!(), "::", "case0"),
+                    ) {
+                        return;
+                    }
+                    let __case = __CASES.take(|| CASES, 0usize, 2usize, "CASES");
+                    test_casing::print_case(|| format!(
+                        "Testing case #{}: {}",
+                        0usize,
+                        test_casing::ArgNames::print_with_args(__ARG_NAMES, &__case)
+                    ));
+                    let __failure_guard = test_casing::CaseFailureGuard::new(
+                        ::core::concat!(::core::module_path!(), "::", "case0"),
+                        0usize,
+                        || test_casing::ArgNames::print_with_args(__ARG_NAMES, &__case),
+                    );
+                    let (__case_arg0, __case_arg1,) = __case;
+                    tested_fn(__case_arg0, &__case_arg1,)
+                })).run_with_default_timeout()
+            };
         }
     };
     assert_eq!(case_fn, expected, "{}", quote!(#case_fn));
 }
+
+#[test]
+fn omitting_failure_guard_for_should_panic() {
+    let attrs = CaseAttrs::parse(quote!(2, CASES)).unwrap();
+    let mut function: ItemFn = syn::parse_quote! {
+        #[should_panic]
+        fn tested_fn(number: u32) {}
+    };
+    let wrapper = FunctionWrapper::new(attrs, &mut function).unwrap();
+    let case_fn = wrapper.case_fn(0, &syn::parse_quote!(case_0)).to_string();
+    assert!(!case_fn.contains("CaseFailureGuard"), "{case_fn}");
+}
+
+#[test]
+fn rejecting_should_panic_with_expr_count() {
+    let attrs = CaseAttrs::parse(quote!(CASES.len(), CASES)).unwrap();
+    let mut function: ItemFn = syn::parse_quote! {
+        #[should_panic]
+        fn tested_fn(number: u32) {}
+    };
+    let err = FunctionWrapper::new(attrs, &mut function)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("#[should_panic] is not supported"), "{err}");
+}
+
+#[test]
+fn generating_all_cases_fn() {
+    let attrs = CaseAttrs {
+        count: CaseCount::Expr(Box::new(syn::parse_quote!(CASES_LEN))),
+        expr: syn::parse_quote!(CASES),
+        tags: Tags::default(),
+        group: None,
+        async_with: None,
+        print_args: PrintArgs::default(),
+        names: CaseNames::default(),
+        case_fields: None,
+        export: false,
+        exhaustive: None,
+    };
+    let mut function: ItemFn = syn::parse_quote! {
+        fn tested_fn(number: u32) -> Result<(), String> {}
+    };
+    let wrapper = FunctionWrapper::new(attrs, &mut function).unwrap();
+    let all_cases_fn = wrapper.all_cases_fn();
+    let all_cases_fn: ItemFn = syn::parse_quote!(#all_cases_fn);
+
+    let expected: ItemFn = syn::parse_quote! {
+        #[::core::prelude::v1::test]
+        fn all_cases() -> Result<(), String> {
+            {
+                #[allow(unused_imports)]
+                use test_casing::decorators::{ApplyDefaultTimeout as _, SkipDefaultTimeout as _};
+                (&test_casing::decorators::DefaultTimeoutFn::new(|| -> Result<(), String> {
+                    let mut __cases = ::core::iter::IntoIterator::into_iter(CASES);
+                    let mut __runner = test_casing::AllCasesRunner::new(
+                        ::core::concat!(::core::module_path!(), "::", "all_cases"),
+                    );
+                    for __index in 0..__CASE_COUNT {
+                        let __case =
+                            test_casing::next_case(&mut __cases, __index, __CASE_COUNT, "CASES");
+                        if test_casing::is_case_skipped(
+                            __index,
+                            ::core::concat!(::core::module_path!(), "::", "all_cases"),
+                        ) {
+                            continue;
+                        }
+                        test_casing::print_case(|| format!(
+                            "Testing case #{}: {}",
+                            __index,
+                            test_casing::ArgNames::print_with_args(__ARG_NAMES, &__case)
+                        ));
+                        __runner.run(__index, || {
+                            let __failure_guard = test_casing::CaseFailureGuard::new(
+                                ::core::concat!(::core::module_path!(), "::", "all_cases"),
+                                __index,
+                                || test_casing::ArgNames::print_with_args(__ARG_NAMES, &__case),
+                            );
+                            let __case_arg = __case;
+                            {
+                                let __output = tested_fn(__case_arg);
+                                __failure_guard.finish(&__output);
+                                __output
+                            }
+                        });
+                    }
+                    __runner.finish();
+                    Ok(())
+                })).run_with_default_timeout()
+            }
+        }
+    };
+    assert_eq!(all_cases_fn, expected, "{}", quote!(#all_cases_fn));
+}